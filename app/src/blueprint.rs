@@ -6,21 +6,26 @@ use pavex::blueprint::Blueprint;
 use pavex::cookie::CookieKit;
 use pavex::f;
 use pavex::kit::ApiKit;
-use pavex_session_sqlx::PostgresSessionKit;
 
 /// The main blueprint, containing all the routes, middlewares, constructors and error handlers
 /// required by our API.
 pub fn blueprint() -> Blueprint {
     let mut bp = Blueprint::new();
     ApiKit::new().register(&mut bp);
-    PostgresSessionKit::new().register(&mut bp);
     CookieKit::new().register(&mut bp);
     telemetry::register(&mut bp);
     configuration::register(&mut bp);
 
     routes::register(&mut bp);
-    bp.singleton(f!(crate::configuration::DatabaseConfig::get_pool));
+    bp.singleton(f!(crate::repository::SharedStore::new));
+    bp.request_scoped(f!(crate::tenancy::resolve_tenant))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.request_scoped(f!(crate::repository::FlashcardStore::for_tenant));
+    bp.singleton(f!(crate::rate_limit::RateLimiter::new));
+    bp.request_scoped(f!(crate::rate_limit::RateLimitStatus::new));
     bp.singleton(f!(pavex_tera_template::TemplateEngine::from_config));
     bp.transient(f!(pavex_static_files::StaticServer::from_config));
+    bp.request_scoped(f!(crate::identity::authenticate_user))
+        .error_handler(f!(crate::errors::api_error2response));
     bp
 }
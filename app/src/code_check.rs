@@ -0,0 +1,506 @@
+// app/src/code_check.rs
+
+// compile-checks of the Rust snippets held in flash cards; every snippet is compiled on its own,
+// in a throwaway crate, with the local toolchain. Snippets are only compiled, never run, and the
+// compiler runs in a sandbox cut off from the network and from the files of the host; on top of
+// it, the snippets able to read files or the environment at compile time are refused, and only
+// the diagnostics pointing into the snippet are kept.
+
+// dependencies
+use crate::configuration::CodeCheckConfig;
+use crate::errors::ApiError;
+use crate::markdown::{RustSnippet, rust_snippets};
+use crate::models::{CodeStatus, CodeVerification, FlashCard};
+use jiff_sqlx::ToSqlx;
+use pavex::time::Timestamp;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Maximum number of characters of compiler output kept for a flash card.
+pub const MAX_COMPILER_OUTPUT: usize = 10_000;
+
+// the environment variables the compiler is allowed to see; enough for rustup to find the
+// toolchain, nothing else is passed on to it
+const ALLOWED_ENV: [&str; 5] = [
+    "PATH",
+    "HOME",
+    "RUSTUP_HOME",
+    "RUSTUP_TOOLCHAIN",
+    "CARGO_HOME",
+];
+
+// the macros reading files or environment variables at compile time, refused as soon as their
+// name shows up, so that they can't be reached through another macro either; `std::env` can't be
+// named in a checked snippet as a result
+const FORBIDDEN_MACROS: [&str; 5] = [
+    "include",
+    "include_str",
+    "include_bytes",
+    "env",
+    "option_env",
+];
+
+// the attributes reading files at compile time; `path` is only refused within an attribute, as
+// the `mod` items it would apply to are refused anyway
+const FORBIDDEN_ATTRIBUTES: [&str; 2] = ["debugger_visualizer", "path"];
+
+/// Compile-check the Rust snippets of the question and answer of a flash card, skipping those
+/// marked `ignore`.
+pub async fn verify_flashcard(
+    config: &CodeCheckConfig,
+    card: &FlashCard,
+) -> Result<CodeVerification, ApiError> {
+    let snippets: Vec<RustSnippet> = rust_snippets(&card.question)
+        .into_iter()
+        .chain(rust_snippets(&card.answer))
+        .filter(|snippet| !snippet.ignore)
+        .collect();
+
+    let sysroot = if config.sandbox && !snippets.is_empty() {
+        Some(sysroot(config).await?)
+    } else {
+        None
+    };
+
+    let mut failures = Vec::new();
+    for (index, snippet) in snippets.iter().enumerate() {
+        if let Some(forbidden) = forbidden_use(&crate_source(&snippet.code)) {
+            failures.push(format!(
+                "snippet {}:\n{} is not allowed in checked snippets",
+                index + 1,
+                forbidden
+            ));
+            continue;
+        }
+        if let Some(output) = check_snippet(config, sysroot.as_deref(), snippet).await? {
+            failures.push(format!("snippet {}:\n{}", index + 1, output.trim_end()));
+        }
+    }
+
+    let status = if snippets.is_empty() {
+        CodeStatus::NoCode
+    } else if failures.is_empty() {
+        CodeStatus::Passed
+    } else {
+        CodeStatus::Failed
+    };
+
+    Ok(CodeVerification {
+        flashcard_id: card.id,
+        status,
+        snippet_count: snippets.len(),
+        output: truncate(failures.join("\n\n"), MAX_COMPILER_OUTPUT),
+        checked_at: Timestamp::now().to_sqlx(),
+    })
+}
+
+// the sysroot of the configured toolchain, whose compiler is run straight from it in the sandbox,
+// where the rustup proxy wouldn't find its home
+async fn sysroot(config: &CodeCheckConfig) -> Result<PathBuf, ApiError> {
+    let output = Command::new(&config.rustc)
+        .args(["--print", "sysroot"])
+        .env_clear()
+        .envs(allowed_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(ApiError::CompilerError)?;
+    if !output.status.success() {
+        return Err(ApiError::CompilerError(std::io::Error::other(
+            "the sysroot of the toolchain could not be found",
+        )));
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+// compile a snippet in its own temporary crate, in the sandbox when given the sysroot of the
+// toolchain; `None` when it behaved as expected, otherwise what went wrong
+async fn check_snippet(
+    config: &CodeCheckConfig,
+    sysroot: Option<&Path>,
+    snippet: &RustSnippet,
+) -> Result<Option<String>, ApiError> {
+    let dir = std::env::temp_dir().join(format!("snippet-{}", Uuid::new_v4()));
+    let result = compile_in(config, sysroot, snippet, &dir).await;
+    // the crate is thrown away whatever happened
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+
+    result
+}
+
+// write the snippet as the main file of a crate in the given directory, and compile it
+async fn compile_in(
+    config: &CodeCheckConfig,
+    sysroot: Option<&Path>,
+    snippet: &RustSnippet,
+    dir: &Path,
+) -> Result<Option<String>, ApiError> {
+    let src = dir.join("src");
+    tokio::fs::create_dir_all(&src)
+        .await
+        .map_err(ApiError::CompilerError)?;
+    let main = src.join("main.rs");
+    tokio::fs::write(&main, crate_source(&snippet.code))
+        .await
+        .map_err(ApiError::CompilerError)?;
+
+    let edition = snippet.edition.as_deref().unwrap_or(&config.edition);
+    let mut command = match sysroot {
+        Some(sysroot) => sandboxed(config, sysroot, dir),
+        None => Command::new(&config.rustc),
+    };
+    command
+        .args(["--crate-name", "snippet", "--crate-type", "bin"])
+        .args(["--edition", edition])
+        .args(["--emit=metadata", "--cap-lints", "allow"])
+        .args(["--error-format", "short"])
+        .arg("--out-dir")
+        .arg(dir)
+        .arg(&main)
+        .current_dir(dir)
+        .env_clear()
+        .envs(allowed_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let output = match tokio::time::timeout(config.timeout, command.output()).await {
+        Ok(output) => output.map_err(ApiError::CompilerError)?,
+        Err(_) => {
+            return Ok(Some(format!(
+                "compilation timed out after {} seconds",
+                config.timeout.as_secs()
+            )));
+        }
+    };
+    let compiled = output.status.success();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // the sandbox reports its own failures to be set up, which say nothing of the snippet
+    if let Some(error) = stderr.lines().find(|line| line.starts_with("bwrap: ")) {
+        return Err(ApiError::CompilerError(std::io::Error::other(
+            error.to_string(),
+        )));
+    }
+
+    let outcome = match (compiled, snippet.compile_fail) {
+        (true, false) | (false, true) => None,
+        (true, true) => Some("expected a compile error, but the snippet compiled".to_string()),
+        (false, false) => Some(diagnostics(&stderr, &main.to_string_lossy())),
+    };
+    Ok(outcome)
+}
+
+// the environment variables of the server the compiler is allowed to see
+fn allowed_env() -> impl Iterator<Item = (&'static str, String)> {
+    ALLOWED_ENV
+        .iter()
+        .filter_map(|key| Some((*key, std::env::var(key).ok()?)))
+}
+
+// the compiler of the toolchain, the arguments of which are still to be added, run by prlimit
+// with the memory and process limits of the configuration, in a bubblewrap sandbox with no
+// network, an empty environment, and no files but the system libraries and the toolchain,
+// read-only, and the crate of the snippet
+fn sandboxed(config: &CodeCheckConfig, sysroot: &Path, dir: &Path) -> Command {
+    let mut command = Command::new(&config.prlimit);
+    command
+        .arg(format!("--as={}", config.max_memory_mib * 1024 * 1024))
+        .arg(format!("--nproc={}", config.max_processes))
+        .args(["--core=0", "--"])
+        .arg(&config.bwrap)
+        .args([
+            "--unshare-all",
+            "--die-with-parent",
+            "--new-session",
+            "--clearenv",
+        ])
+        .args(["--ro-bind", "/usr", "/usr"])
+        .args(["--ro-bind-try", "/lib", "/lib"])
+        .args(["--ro-bind-try", "/lib64", "/lib64"])
+        .args(["--ro-bind-try", "/etc/ld.so.cache", "/etc/ld.so.cache"])
+        .args(["--proc", "/proc", "--dev", "/dev", "--tmpfs", "/tmp"])
+        .arg("--ro-bind")
+        .args([sysroot, sysroot])
+        .arg("--bind")
+        .args([dir, dir])
+        .arg("--chdir")
+        .arg(dir)
+        .arg("--")
+        .arg(sysroot.join("bin").join("rustc"));
+    command
+}
+
+// what makes a snippet able to read the files or the environment of the host, if anything: one
+// of the forbidden macros or attributes, or a `mod` item, which `#[path]` would load from any
+// file. The code is tokenized the way the compiler does, so that comments can't split what they
+// surround and raw identifiers stand for the names they escape; code which can't be tokenized
+// wouldn't compile, and is refused
+fn forbidden_use(code: &str) -> Option<String> {
+    match code.parse::<TokenStream>() {
+        Ok(tokens) => forbidden_token(tokens, false),
+        Err(_) => Some("code which can't be tokenized".to_string()),
+    }
+}
+
+// the first forbidden token of a stream, looking into its groups, the attributes included
+fn forbidden_token(tokens: TokenStream, in_attribute: bool) -> Option<String> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (position, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                let name = ident.strip_prefix("r#").unwrap_or(&ident);
+                if FORBIDDEN_MACROS.contains(&name) {
+                    return Some(format!("`{}!`", name));
+                }
+                if FORBIDDEN_ATTRIBUTES.contains(&name) && (in_attribute || name != "path") {
+                    return Some(format!("`#[{}]`", name));
+                }
+                if name == "mod" {
+                    return Some("a `mod` item".to_string());
+                }
+            }
+            TokenTree::Group(group) => {
+                let attribute =
+                    in_attribute || opens_attribute(&tokens[..position], group.delimiter());
+                if let Some(forbidden) = forbidden_token(group.stream(), attribute) {
+                    return Some(forbidden);
+                }
+            }
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+    }
+    None
+}
+
+// whether a group with the given delimiter, following the given tokens, holds an attribute:
+// `#[...]`, or `#![...]` for an inner one
+fn opens_attribute(before: &[TokenTree], delimiter: Delimiter) -> bool {
+    let is_punct = |token: Option<&TokenTree>, c: char| matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c);
+    let mut before = before.iter().rev();
+    delimiter == Delimiter::Bracket
+        && match before.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '!' => is_punct(before.next(), '#'),
+            token => is_punct(token, '#'),
+        }
+}
+
+// the diagnostics of a failed compilation which point into the snippet, in the short format
+// `main.rs:line:column: message`; the other lines of the compiler output are dropped, as they may
+// name files and directories of the host
+fn diagnostics(stderr: &str, main: &str) -> String {
+    let lines: Vec<String> = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix(main))
+        .map(|location| format!("main.rs{}", location))
+        .collect();
+    if lines.is_empty() {
+        "the snippet failed to compile".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+// turn a snippet into the source of a binary crate, the way rustdoc does: lines hidden with a
+// leading `# ` are kept, unused code is allowed, and a snippet without a `main` function is
+// wrapped in one, its crate attributes left at the top
+fn crate_source(code: &str) -> String {
+    let lines: Vec<&str> = code
+        .lines()
+        .map(|line| match line.trim_start() {
+            "#" => "",
+            trimmed => trimmed.strip_prefix("# ").unwrap_or(line),
+        })
+        .collect();
+
+    let mut source = String::from("#![allow(unused)]\n");
+    if lines.iter().any(|line| line.contains("fn main")) {
+        for line in lines {
+            source.push_str(line);
+            source.push('\n');
+        }
+        return source;
+    }
+
+    let (attributes, body): (Vec<&str>, Vec<&str>) = lines
+        .into_iter()
+        .partition(|line| line.trim_start().starts_with("#!["));
+    for line in attributes {
+        source.push_str(line);
+        source.push('\n');
+    }
+    source.push_str("fn main() {\n");
+    for line in body {
+        source.push_str(line);
+        source.push('\n');
+    }
+    source.push_str("}\n");
+    source
+}
+
+// keep at most `max` characters of a text
+fn truncate(text: String, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => format!("{}\n[output truncated]", &text[..index]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_without_main_are_wrapped() {
+        let source = crate_source("#![deny(warnings)]\n# let x = 1;\nprintln!(\"{}\", x);");
+
+        assert_eq!(
+            source,
+            "#![allow(unused)]\n#![deny(warnings)]\nfn main() {\nlet x = 1;\nprintln!(\"{}\", x);\n}\n"
+        );
+    }
+
+    #[test]
+    fn snippets_with_main_are_kept_as_is() {
+        let source = crate_source("fn main() {\n    let x = 1;\n}");
+
+        assert_eq!(
+            source,
+            "#![allow(unused)]\nfn main() {\n    let x = 1;\n}\n"
+        );
+    }
+
+    fn config() -> CodeCheckConfig {
+        CodeCheckConfig {
+            rustc: "rustc".to_string(),
+            edition: "2024".to_string(),
+            timeout: std::time::Duration::from_secs(30),
+            sandbox: true,
+            bwrap: "bwrap".to_string(),
+            prlimit: "prlimit".to_string(),
+            max_memory_mib: 2048,
+            max_processes: 512,
+        }
+    }
+
+    #[test]
+    fn snippets_reading_the_host_are_refused() {
+        assert_eq!(
+            forbidden_use("let key = include_str!(\"/etc/ssh/ssh_host_rsa_key\");"),
+            Some("`include_str!`".to_string())
+        );
+        assert_eq!(
+            forbidden_use(
+                "macro_rules! read { ($m:ident) => { $m!(\"/etc/hosts\") } }\nread!(include_bytes);"
+            ),
+            Some("`include_bytes!`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("let home = env!(\"HOME\");"),
+            Some("`env!`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("#[path = \"/etc/passwd\"]\nmod secrets;"),
+            Some("`#[path]`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("#[cfg_attr(all(), path = \"/etc/passwd\")]\nmod secrets;"),
+            Some("`#[path]`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("mod inline { }"),
+            Some("a `mod` item".to_string())
+        );
+        assert_eq!(
+            forbidden_use(
+                "let path = std::path::Path::new(\"/etc/passwd\");\n#[derive(Debug)]\nstruct Config;"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn comments_and_raw_identifiers_do_not_hide_forbidden_uses() {
+        assert_eq!(
+            forbidden_use("let secret = env/**/!(\"SECRET\");"),
+            Some("`env!`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("let key = r#include_str!(\"/etc/shadow\");"),
+            Some("`include_str!`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("#[path = \"/etc/passwd\"]\nmod r#x;"),
+            Some("`#[path]`".to_string())
+        );
+        assert_eq!(
+            forbidden_use("// include_str! is only mentioned\nlet text = \"include_bytes!\";"),
+            None
+        );
+        assert_eq!(
+            forbidden_use("let unclosed = (1;"),
+            Some("code which can't be tokenized".to_string())
+        );
+    }
+
+    #[test]
+    fn sandboxed_compilers_only_see_the_toolchain_and_the_snippet() {
+        let command = sandboxed(
+            &config(),
+            Path::new("/opt/rust/toolchain"),
+            Path::new("/tmp/snippet-1"),
+        );
+        let command = command.as_std();
+        let args: Vec<&str> = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap())
+            .collect();
+
+        assert_eq!(command.get_program(), "prlimit");
+        assert_eq!(
+            args[..4],
+            ["--as=2147483648", "--nproc=512", "--core=0", "--"]
+        );
+        assert_eq!(args[4], "bwrap");
+        assert!(args.contains(&"--unshare-all"));
+        assert!(args.contains(&"--clearenv"));
+        assert!(
+            args.windows(3)
+                .any(|window| window == ["--ro-bind", "/opt/rust/toolchain", "/opt/rust/toolchain"])
+        );
+        assert!(
+            args.windows(3)
+                .any(|window| window == ["--bind", "/tmp/snippet-1", "/tmp/snippet-1"])
+        );
+        assert_eq!(args.last(), Some(&"/opt/rust/toolchain/bin/rustc"));
+    }
+
+    #[test]
+    fn only_the_diagnostics_of_the_snippet_are_kept() {
+        let stderr = "/tmp/snippet-1/src/main.rs:2:5: error[E0425]: cannot find value `x` in this scope\nerror: aborting due to 1 previous error\n";
+
+        assert_eq!(
+            diagnostics(stderr, "/tmp/snippet-1/src/main.rs"),
+            "main.rs:2:5: error[E0425]: cannot find value `x` in this scope"
+        );
+        assert_eq!(
+            diagnostics(
+                "error: couldn't read `/etc/shadow`\n",
+                "/tmp/snippet-1/src/main.rs"
+            ),
+            "the snippet failed to compile"
+        );
+    }
+
+    #[test]
+    fn long_output_is_truncated() {
+        assert_eq!(truncate("abcdef".to_string(), 3), "abc\n[output truncated]");
+        assert_eq!(truncate("abc".to_string(), 3), "abc");
+    }
+}
@@ -15,8 +15,17 @@ use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions, PgSslMode};
 /// on how to manage configuration values.
 pub fn register(bp: &mut Blueprint) {
     bp.config("server", t!(self::ServerConfig));
+    bp.config("proxy", t!(self::ProxyConfig));
     bp.config("database", t!(self::DatabaseConfig));
     bp.config("authorization", t!(self::AuthConfig));
+    bp.config("daily", t!(self::DailyConfig));
+    bp.config("code_check", t!(self::CodeCheckConfig));
+    bp.config("import", t!(self::ImportConfig));
+    bp.config("feedback", t!(self::FeedbackConfig));
+    bp.config("quiz", t!(self::QuizConfig));
+    bp.config("routing", t!(self::RoutingConfig));
+    bp.config("cors", t!(self::CorsConfig));
+    bp.config("rate_limit", t!(self::RateLimitConfig));
     bp.config("templateconfig", t!(pavex_tera_template::TemplateConfig));
     bp.config(
         "staticserverconfig",
@@ -74,9 +83,99 @@ impl ServerConfig {
     }
 }
 
+// struct type to represent the reverse proxies in front of the instance
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ProxyConfig {
+    /// The addresses of the reverse proxies allowed to forward the host a request was sent to,
+    /// in `X-Forwarded-Host`, and the address of its client, in `X-Forwarded-For`; the headers
+    /// are ignored in the requests of any other peer. None by default.
+    #[serde(default)]
+    pub trusted_addresses: Vec<std::net::IpAddr>,
+}
+
+// methods for the proxy config struct type
+impl ProxyConfig {
+    /// Whether the peer at this address is one of the trusted reverse proxies.
+    pub fn trusts(&self, peer: std::net::IpAddr) -> bool {
+        let peer = peer.to_canonical();
+        self.trusted_addresses
+            .iter()
+            .any(|address| address.to_canonical() == peer)
+    }
+}
+
+// function to aid in database pool timeout configuration
+fn deserialize_pool_duration<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() {
+        Err(serde::de::Error::custom(
+            "database pool timeouts must be positive",
+        ))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+// default values for the database connection pool settings
+fn default_min_connections() -> u32 {
+    0
+}
+
+fn default_max_connections() -> u32 {
+    10
+}
+
+fn default_acquire_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(2)
+}
+
+fn default_idle_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(10 * 60)
+}
+
+fn default_max_lifetime() -> std::time::Duration {
+    std::time::Duration::from_secs(30 * 60)
+}
+
+// default location of the SQLite database file
+fn default_sqlite_path() -> std::path::PathBuf {
+    std::path::PathBuf::from("rusty_flash_knowledge.db")
+}
+
+// enum type to represent the storage backends the flash cards can be kept in
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// Flash cards are stored in Postgres.
+    #[default]
+    Postgres,
+    /// Flash cards are kept in memory and lost on restart; handy for demos.
+    Memory,
+    /// Flash cards are stored in a local SQLite database file.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+/// The application name of the connections of the shared pool, as seen by Postgres.
+pub const POOL_APPLICATION_NAME: &str = "rusty-flash-knowledge";
+
 // struct type to represent the database configuration
 #[derive(Clone, Debug, Deserialize)]
 pub struct DatabaseConfig {
+    /// The storage backend for flash cards, `postgres` (the default), `memory` or,
+    /// with the `sqlite` cargo feature, `sqlite`.
+    ///
+    /// Set the `PX_DATABASE__BACKEND` environment variable to override its value.
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// The database file used by the `sqlite` backend; created if it doesn't exist.
+    ///
+    /// Set the `PX_DATABASE__SQLITE_PATH` environment variable to override its value.
+    #[serde(default = "default_sqlite_path")]
+    pub sqlite_path: std::path::PathBuf,
     pub username: String,
     pub password: SecretString,
     #[serde(deserialize_with = "deserialize_number_from_string")]
@@ -84,6 +183,46 @@ pub struct DatabaseConfig {
     pub host: String,
     pub database_name: String,
     pub require_ssl: bool,
+    /// The number of connections the pool keeps open, even when idle.
+    ///
+    /// Set the `PX_DATABASE__MIN_CONNECTIONS` environment variable to override its value.
+    #[serde(
+        default = "default_min_connections",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub min_connections: u32,
+    /// The maximum number of connections the pool will open.
+    ///
+    /// Set the `PX_DATABASE__MAX_CONNECTIONS` environment variable to override its value.
+    #[serde(
+        default = "default_max_connections",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub max_connections: u32,
+    /// How long a request waits for a connection before giving up, e.g. `2 seconds`.
+    ///
+    /// Set the `PX_DATABASE__ACQUIRE_TIMEOUT` environment variable to override its value.
+    #[serde(
+        default = "default_acquire_timeout",
+        deserialize_with = "deserialize_pool_duration"
+    )]
+    pub acquire_timeout: std::time::Duration,
+    /// How long a connection can stay idle in the pool before being closed, e.g. `10 minutes`.
+    ///
+    /// Set the `PX_DATABASE__IDLE_TIMEOUT` environment variable to override its value.
+    #[serde(
+        default = "default_idle_timeout",
+        deserialize_with = "deserialize_pool_duration"
+    )]
+    pub idle_timeout: std::time::Duration,
+    /// The maximum lifetime of a connection before it is closed and replaced, e.g. `30 minutes`.
+    ///
+    /// Set the `PX_DATABASE__MAX_LIFETIME` environment variable to override its value.
+    #[serde(
+        default = "default_max_lifetime",
+        deserialize_with = "deserialize_pool_duration"
+    )]
+    pub max_lifetime: std::time::Duration,
 }
 
 // methods for the database configuration type
@@ -110,10 +249,19 @@ impl DatabaseConfig {
             .ssl_mode(ssl_mode)
     }
 
+    /// Build the connection pool shared by every request.
+    ///
+    /// The Postgres backend builds it along with the shared store, once at startup;
+    /// connections are opened lazily, on first use, and go by
+    /// `POOL_APPLICATION_NAME` in `pg_stat_activity`.
     pub async fn get_pool(&self) -> PgPool {
         PgPoolOptions::new()
-            .acquire_timeout(std::time::Duration::from_secs(2))
-            .connect_lazy_with(self.with_db())
+            .min_connections(self.min_connections)
+            .max_connections(self.max_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+            .max_lifetime(self.max_lifetime)
+            .connect_lazy_with(self.with_db().application_name(POOL_APPLICATION_NAME))
     }
 }
 
@@ -122,3 +270,567 @@ impl DatabaseConfig {
 pub struct AuthConfig {
     pub api_key: String,
 }
+
+// default number of days during which a card of the day doesn't come up again
+fn default_repeat_window() -> u32 {
+    30
+}
+
+// struct type to represent the card of the day configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct DailyConfig {
+    /// The number of previous days whose cards can't be the card of the day again; ignored
+    /// when there are not enough cards to choose from.
+    ///
+    /// Set the `PX_DAILY__REPEAT_WINDOW` environment variable to override its value.
+    #[serde(
+        default = "default_repeat_window",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub repeat_window: u32,
+}
+
+// default toolchain settings used to compile-check code snippets
+fn default_rustc() -> String {
+    "rustc".to_string()
+}
+
+fn default_edition() -> String {
+    "2024".to_string()
+}
+
+fn default_compile_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(30)
+}
+
+// function to aid in compile timeout configuration
+fn deserialize_compile_timeout<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() || duration.is_zero() {
+        Err(serde::de::Error::custom("compile timeout must be positive"))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+fn default_sandbox() -> bool {
+    true
+}
+
+fn default_bwrap() -> String {
+    "bwrap".to_string()
+}
+
+fn default_prlimit() -> String {
+    "prlimit".to_string()
+}
+
+fn default_max_memory_mib() -> u64 {
+    2048
+}
+
+fn default_max_processes() -> u64 {
+    512
+}
+
+// struct type to represent the configuration of the code snippet compile-checks
+#[derive(Clone, Debug, Deserialize)]
+pub struct CodeCheckConfig {
+    /// The compiler snippets are checked with, `rustc` from the `PATH` by default.
+    ///
+    /// Set the `PX_CODE_CHECK__RUSTC` environment variable to override its value.
+    #[serde(default = "default_rustc")]
+    pub rustc: String,
+    /// The edition of the snippets which don't ask for one with an `editionXXXX` attribute.
+    ///
+    /// Set the `PX_CODE_CHECK__EDITION` environment variable to override its value.
+    #[serde(default = "default_edition")]
+    pub edition: String,
+    /// How long a single snippet may take to compile before it is reported as failed,
+    /// e.g. `30 seconds`.
+    ///
+    /// Set the `PX_CODE_CHECK__TIMEOUT` environment variable to override its value.
+    #[serde(
+        default = "default_compile_timeout",
+        deserialize_with = "deserialize_compile_timeout"
+    )]
+    pub timeout: std::time::Duration,
+    /// Whether the compiler runs in a sandbox, with no network, no view of the host files
+    /// besides the toolchain and the system libraries, and limited memory and processes.
+    /// Only meant to be turned off where the sandbox can't run, e.g. on a development machine.
+    ///
+    /// Set the `PX_CODE_CHECK__SANDBOX` environment variable to override its value.
+    #[serde(default = "default_sandbox")]
+    pub sandbox: bool,
+    /// The bubblewrap executable the sandbox is set up with, `bwrap` from the `PATH` by default.
+    ///
+    /// Set the `PX_CODE_CHECK__BWRAP` environment variable to override its value.
+    #[serde(default = "default_bwrap")]
+    pub bwrap: String,
+    /// The executable setting the resource limits of the sandbox, `prlimit` from the `PATH`
+    /// by default.
+    ///
+    /// Set the `PX_CODE_CHECK__PRLIMIT` environment variable to override its value.
+    #[serde(default = "default_prlimit")]
+    pub prlimit: String,
+    /// The address space, in MiB, the sandboxed compiler may use.
+    ///
+    /// Set the `PX_CODE_CHECK__MAX_MEMORY_MIB` environment variable to override its value.
+    #[serde(
+        default = "default_max_memory_mib",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub max_memory_mib: u64,
+    /// The processes and threads the user running the server may have while a snippet is
+    /// sandboxed, the compiler's included; the limit applies to the user as a whole, so it is best
+    /// left to a user of its own.
+    ///
+    /// Set the `PX_CODE_CHECK__MAX_PROCESSES` environment variable to override its value.
+    #[serde(
+        default = "default_max_processes",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub max_processes: u64,
+}
+
+// default directory holding the rustdoc JSON files which can be imported
+fn default_rustdoc_dir() -> std::path::PathBuf {
+    std::path::PathBuf::from("target/doc")
+}
+
+// struct type to represent the configuration of the draft card imports
+#[derive(Clone, Debug, Deserialize)]
+pub struct ImportConfig {
+    /// The directory rustdoc JSON files are imported from; import requests name a file
+    /// relative to it, `target/doc` by default.
+    ///
+    /// Set the `PX_IMPORT__RUSTDOC_DIR` environment variable to override its value.
+    #[serde(default = "default_rustdoc_dir")]
+    pub rustdoc_dir: std::path::PathBuf,
+}
+
+// default number of feedback entries a client may send within a window
+fn default_feedback_limit() -> u32 {
+    10
+}
+
+fn default_feedback_window() -> std::time::Duration {
+    std::time::Duration::from_secs(60 * 60)
+}
+
+// function to aid in feedback window configuration
+fn deserialize_feedback_window<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() || duration.is_zero() {
+        Err(serde::de::Error::custom("feedback window must be positive"))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+// struct type to represent the rate limit on the feedback left by public clients
+#[derive(Clone, Debug, Deserialize)]
+pub struct FeedbackConfig {
+    /// The number of flags and upvotes a single client may send within a window.
+    ///
+    /// Set the `PX_FEEDBACK__LIMIT` environment variable to override its value.
+    #[serde(
+        default = "default_feedback_limit",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub limit: u32,
+    /// The sliding window the limit applies to, e.g. `1 hour`.
+    ///
+    /// Set the `PX_FEEDBACK__WINDOW` environment variable to override its value.
+    #[serde(
+        default = "default_feedback_window",
+        deserialize_with = "deserialize_feedback_window"
+    )]
+    pub window: std::time::Duration,
+}
+
+fn default_quiz_retention() -> std::time::Duration {
+    std::time::Duration::from_secs(24 * 60 * 60)
+}
+
+// function to aid in quiz retention configuration
+fn deserialize_quiz_retention<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() || duration.is_zero() {
+        Err(serde::de::Error::custom("quiz retention must be positive"))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+// struct type to represent how long the quizzes handed out are kept for grading
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuizConfig {
+    /// How long a quiz can be submitted after it was built, e.g. `24 hours`; older quizzes are
+    /// deleted as new ones are built.
+    ///
+    /// Set the `PX_QUIZ__RETENTION` environment variable to override its value.
+    #[serde(
+        default = "default_quiz_retention",
+        deserialize_with = "deserialize_quiz_retention"
+    )]
+    pub retention: std::time::Duration,
+}
+
+// default path prefix of the public and admin APIs
+fn default_api_prefix() -> String {
+    "v1".to_string()
+}
+
+/// The first path segments of the web pages, which are served at the root path; an API prefix
+/// equal to one of them would shadow the pages.
+pub const WEB_PATH_SEGMENTS: [&str; 2] = ["flashcards", "static"];
+
+// function to aid in API path prefix configuration; the routes of the APIs are registered under a
+// single `{prefix}` path segment, so a prefix can't span several of them, nor be the first
+// segment of a web page
+fn deserialize_api_prefix<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let prefix = String::deserialize(deserializer)?;
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() || prefix.contains('/') {
+        Err(serde::de::Error::custom(
+            "API path prefixes must be a single, non-empty path segment",
+        ))
+    } else if WEB_PATH_SEGMENTS.contains(&prefix) {
+        Err(serde::de::Error::custom(format!(
+            "API path prefix `{prefix}` collides with the web pages"
+        )))
+    } else {
+        Ok(prefix.to_string())
+    }
+}
+
+// enum type to represent how requests are told apart between the public API, the admin API and
+// the web pages
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingMode {
+    /// Each of them is served at its own host, and under its path prefix.
+    #[default]
+    Domains,
+    /// All of them are served at whichever host the request was sent to, told apart by their
+    /// path prefixes alone; handy for local development.
+    SingleHost,
+}
+
+// struct type to represent where one of the APIs is served
+#[derive(Clone, Debug, Deserialize)]
+pub struct ApiRoutingConfig {
+    /// The host the API is served at, in the `domains` mode.
+    pub host: String,
+    /// The first segment of the path of every route of the API, e.g. `v1`.
+    #[serde(
+        default = "default_api_prefix",
+        deserialize_with = "deserialize_api_prefix"
+    )]
+    pub prefix: String,
+}
+
+// struct type to represent where the web pages are served; they always are at the root path
+#[derive(Clone, Debug, Deserialize)]
+pub struct WebRoutingConfig {
+    /// The host the web pages are served at, in the `domains` mode.
+    pub host: String,
+}
+
+// struct type to represent the hosts and path prefixes the routes are served at
+#[derive(Clone, Debug, Deserialize)]
+pub struct RoutingConfig {
+    /// `domains` (the default) or `single_host`.
+    ///
+    /// Set the `PX_ROUTING__MODE` environment variable to override its value.
+    #[serde(default)]
+    pub mode: RoutingMode,
+    /// Set the `PX_ROUTING__PUBLIC__HOST` and `PX_ROUTING__PUBLIC__PREFIX` environment
+    /// variables to override its values.
+    pub public: ApiRoutingConfig,
+    /// Set the `PX_ROUTING__ADMIN__HOST` and `PX_ROUTING__ADMIN__PREFIX` environment
+    /// variables to override its values.
+    pub admin: ApiRoutingConfig,
+    /// Set the `PX_ROUTING__WEB__HOST` environment variable to override its value.
+    pub web: WebRoutingConfig,
+}
+
+// methods for the routing configuration type
+impl RoutingConfig {
+    /// The base URL the web pages call the public API at: under its prefix at the origin of the
+    /// page in the `single_host` mode, and at its host, with the scheme of the page, otherwise.
+    pub fn public_api_base(&self) -> String {
+        match self.mode {
+            RoutingMode::Domains => format!("//{}/{}", self.public.host, self.public.prefix),
+            RoutingMode::SingleHost => format!("/{}", self.public.prefix),
+        }
+    }
+
+    /// The base URI of the `type` of the problem details documents, at the host of the public
+    /// API, e.g. `https://cards.example.com/problems/`.
+    pub fn problem_type_base(&self) -> String {
+        format!("https://{}/problems/", self.public.host)
+    }
+
+    /// Whether a request sent to `host`, if known, for `path` may reach a route served at
+    /// `served_host` and, for the APIs, under `prefix`.
+    pub fn serves(
+        &self,
+        served_host: &str,
+        prefix: Option<&str>,
+        host: Option<&str>,
+        path: &str,
+    ) -> bool {
+        let host_matches = match self.mode {
+            RoutingMode::Domains => host.is_some_and(|host| host.eq_ignore_ascii_case(served_host)),
+            RoutingMode::SingleHost => true,
+        };
+        let prefix_matches = prefix
+            .is_none_or(|prefix| path.trim_start_matches('/').split('/').next() == Some(prefix));
+
+        host_matches && prefix_matches
+    }
+}
+
+// default cross-origin policy, which lets any web page read the APIs without credentials
+fn default_allowed_origins() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_allowed_methods() -> Vec<String> {
+    ["GET", "POST", "PUT", "DELETE"]
+        .iter()
+        .map(|method| method.to_string())
+        .collect()
+}
+
+fn default_allowed_headers() -> Vec<String> {
+    vec!["Content-Type".to_string(), "Authorization".to_string()]
+}
+
+fn default_cors_max_age() -> std::time::Duration {
+    std::time::Duration::from_secs(60 * 60)
+}
+
+// function to aid in preflight max-age configuration
+fn deserialize_cors_max_age<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() {
+        Err(serde::de::Error::custom("CORS max age can't be negative"))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+// struct type to represent the cross-origin resource sharing policy of the public and admin APIs
+#[derive(Clone, Debug, Deserialize)]
+pub struct CorsConfig {
+    /// The origins of the web pages allowed to call the APIs: `*` for any, an exact origin such as
+    /// `https://cards.example.com`, or one with a wildcard subdomain such as
+    /// `https://*.example.com`.
+    #[serde(default = "default_allowed_origins")]
+    pub allowed_origins: Vec<String>,
+    /// The methods allowed in cross-origin requests, among those of the route.
+    #[serde(default = "default_allowed_methods")]
+    pub allowed_methods: Vec<String>,
+    /// The request headers allowed in cross-origin requests, or `*` for any.
+    #[serde(default = "default_allowed_headers")]
+    pub allowed_headers: Vec<String>,
+    /// The response headers exposed to the web pages, besides the CORS-safelisted ones.
+    #[serde(default)]
+    pub exposed_headers: Vec<String>,
+    /// How long browsers may cache a preflight response, e.g. `1 hour`.
+    ///
+    /// Set the `PX_CORS__MAX_AGE` environment variable to override its value.
+    #[serde(
+        default = "default_cors_max_age",
+        deserialize_with = "deserialize_cors_max_age"
+    )]
+    pub max_age: std::time::Duration,
+    /// Whether cross-origin requests may carry cookies and `Authorization` headers, in which
+    /// case the origin of the request is sent back instead of `*`, and only the origins listed
+    /// in `allowed_origins` are allowed, not any.
+    ///
+    /// Set the `PX_CORS__ALLOW_CREDENTIALS` environment variable to override its value.
+    #[serde(default)]
+    pub allow_credentials: bool,
+}
+
+// default switch of the rate limiter
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+// function to aid in rate limit period configuration
+fn deserialize_rate_limit_period<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration = SignedDuration::deserialize(deserializer)?;
+    if duration.is_negative() || duration.is_zero() {
+        Err(serde::de::Error::custom(
+            "rate limit period must be positive",
+        ))
+    } else {
+        duration.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+// function to aid in rate limit configuration; an empty bucket would never be refilled
+fn deserialize_rate_limit_requests<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let requests: u32 = deserialize_number_from_string(deserializer)?;
+    if requests == 0 {
+        Err(serde::de::Error::custom(
+            "rate limits must allow at least one request",
+        ))
+    } else {
+        Ok(requests)
+    }
+}
+
+// struct type to represent the rate limit of one of the APIs, a token bucket holding `requests`
+// tokens and refilled with as many every `per`
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimit {
+    /// The number of requests a client may send in a burst, and per period.
+    #[serde(deserialize_with = "deserialize_rate_limit_requests")]
+    pub requests: u32,
+    /// The period it takes to refill an empty bucket, e.g. `1 minute`.
+    #[serde(deserialize_with = "deserialize_rate_limit_period")]
+    pub per: std::time::Duration,
+}
+
+// struct type to represent the per-client rate limits of the public and admin APIs
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitConfig {
+    /// Whether requests are rate limited at all.
+    ///
+    /// Set the `PX_RATE_LIMIT__ENABLED` environment variable to override its value.
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// Set the `PX_RATE_LIMIT__PUBLIC__REQUESTS` and `PX_RATE_LIMIT__PUBLIC__PER` environment
+    /// variables to override its values.
+    pub public: RateLimit,
+    /// Set the `PX_RATE_LIMIT__ADMIN__REQUESTS` and `PX_RATE_LIMIT__ADMIN__PER` environment
+    /// variables to override its values.
+    pub admin: RateLimit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routing(mode: RoutingMode) -> RoutingConfig {
+        RoutingConfig {
+            mode,
+            public: ApiRoutingConfig {
+                host: "cards.example.com".to_string(),
+                prefix: "api".to_string(),
+            },
+            admin: ApiRoutingConfig {
+                host: "admin.cards.example.com".to_string(),
+                prefix: "admin".to_string(),
+            },
+            web: WebRoutingConfig {
+                host: "www.cards.example.com".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn domains_are_told_apart_by_host_and_prefix() {
+        let config = routing(RoutingMode::Domains);
+        let public = &config.public;
+
+        assert!(config.serves(
+            &public.host,
+            Some(&public.prefix),
+            Some("Cards.Example.com"),
+            "/api/flashcards"
+        ));
+        assert!(!config.serves(
+            &public.host,
+            Some(&public.prefix),
+            Some("admin.cards.example.com"),
+            "/api/flashcards"
+        ));
+        assert!(!config.serves(
+            &public.host,
+            Some(&public.prefix),
+            Some("cards.example.com"),
+            "/v1/flashcards"
+        ));
+        assert!(!config.serves(&public.host, Some(&public.prefix), None, "/api/flashcards"));
+        assert!(config.serves(&config.web.host, None, Some("www.cards.example.com"), "/"));
+    }
+
+    #[test]
+    fn a_single_host_only_checks_prefixes() {
+        let config = routing(RoutingMode::SingleHost);
+        let admin = &config.admin;
+
+        assert!(config.serves(
+            &admin.host,
+            Some(&admin.prefix),
+            Some("localhost"),
+            "/admin/drafts"
+        ));
+        assert!(config.serves(&admin.host, Some(&admin.prefix), None, "/admin/drafts"));
+        assert!(!config.serves(
+            &admin.host,
+            Some(&admin.prefix),
+            Some("localhost"),
+            "/api/drafts"
+        ));
+    }
+
+    #[test]
+    fn web_pages_call_the_configured_public_api() {
+        assert_eq!(
+            routing(RoutingMode::Domains).public_api_base(),
+            "//cards.example.com/api"
+        );
+        assert_eq!(routing(RoutingMode::SingleHost).public_api_base(), "/api");
+        assert_eq!(
+            routing(RoutingMode::Domains).problem_type_base(),
+            "https://cards.example.com/problems/"
+        );
+    }
+
+    #[test]
+    fn api_prefixes_are_validated() {
+        let prefix = |prefix: &str| {
+            serde_json::from_value::<ApiRoutingConfig>(serde_json::json!({
+                "host": "cards.example.com",
+                "prefix": prefix,
+            }))
+            .map(|config| config.prefix)
+        };
+
+        assert_eq!(prefix("/api/").unwrap(), "api");
+        assert!(prefix("").is_err());
+        assert!(prefix("/").is_err());
+        assert!(prefix("api/v1").is_err());
+        assert!(prefix("flashcards").is_err());
+        assert!(prefix("/static/").is_err());
+    }
+}
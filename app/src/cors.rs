@@ -0,0 +1,209 @@
+// app/src/cors.rs
+
+// cross-origin resource sharing for the public and admin APIs, as configured in the `cors`
+// section: responses to an allowed origin carry the Access-Control-* headers, and a preflight
+// request is answered for every route, with the methods of the route the configuration allows.
+// Unless any origin is allowed without credentials, the headers depend on the Origin of the
+// request, so the responses vary on it, whether the origin is allowed or not
+
+// dependencies
+use crate::configuration::CorsConfig;
+use pavex::http::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, HeaderMap, HeaderValue, ORIGIN,
+    VARY,
+};
+use pavex::http::{HeaderName, Method};
+use pavex::request::RequestHead;
+
+/// Add the CORS headers of a response to an actual, not preflight, request.
+pub fn apply(config: &CorsConfig, request: &RequestHead, headers: &mut HeaderMap) {
+    vary_on_origin(config, headers);
+    let Some(origin) = allowed_origin(config, request) else {
+        return;
+    };
+
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    if config.allow_credentials {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+    if !config.exposed_headers.is_empty() {
+        insert_list(
+            headers,
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            config.exposed_headers.iter().map(String::as_str),
+        );
+    }
+}
+
+/// Whether a request is a preflight request, sent by a browser before a cross-origin request.
+pub fn is_preflight(request: &RequestHead) -> bool {
+    request.method == Method::OPTIONS
+        && request.headers.contains_key(ORIGIN)
+        && request.headers.contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Add the CORS headers of the response to a preflight request for a route accepting
+/// `route_methods`; none are added, so that the browser gives up on the request, when the origin
+/// or the requested method isn't allowed.
+pub fn apply_preflight(
+    config: &CorsConfig,
+    request: &RequestHead,
+    route_methods: &[&str],
+    headers: &mut HeaderMap,
+) {
+    vary_on_origin(config, headers);
+    let Some(origin) = allowed_origin(config, request) else {
+        return;
+    };
+    let methods: Vec<&str> = route_methods
+        .iter()
+        .copied()
+        .filter(|method| {
+            config
+                .allowed_methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method))
+        })
+        .collect();
+    let requested = request
+        .headers
+        .get(ACCESS_CONTROL_REQUEST_METHOD)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !methods.contains(&requested) {
+        return;
+    }
+
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    if config.allow_credentials {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+    insert_list(headers, ACCESS_CONTROL_ALLOW_METHODS, methods.into_iter());
+    if config.allowed_headers.iter().any(|header| header == "*") {
+        // any header is allowed, so the ones the browser asks for are echoed back
+        headers.append(
+            VARY,
+            HeaderValue::from_static("Access-Control-Request-Headers"),
+        );
+        if let Some(requested) = request.headers.get(ACCESS_CONTROL_REQUEST_HEADERS) {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, requested.clone());
+        }
+    } else {
+        insert_list(
+            headers,
+            ACCESS_CONTROL_ALLOW_HEADERS,
+            config.allowed_headers.iter().map(String::as_str),
+        );
+    }
+    headers.insert(
+        ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from(config.max_age.as_secs()),
+    );
+}
+
+// the value of Access-Control-Allow-Origin for the origin of the request, if it is allowed: `*`
+// when any origin is, without credentials, and the origin itself otherwise. With credentials, the
+// `*` entry allows no origin, since echoing any of them would let every web page read the
+// responses meant for the user of the browser
+fn allowed_origin(config: &CorsConfig, request: &RequestHead) -> Option<HeaderValue> {
+    let origin = request.headers.get(ORIGIN)?;
+    if allows_any_origin(config) {
+        return Some(HeaderValue::from_static("*"));
+    }
+    let value = origin.to_str().ok()?;
+
+    config
+        .allowed_origins
+        .iter()
+        .filter(|pattern| *pattern != "*")
+        .any(|pattern| origin_matches(pattern, value))
+        .then(|| origin.clone())
+}
+
+// whether the headers are the same for every origin, which spares caches from varying on it
+fn allows_any_origin(config: &CorsConfig) -> bool {
+    !config.allow_credentials && config.allowed_origins.iter().any(|origin| origin == "*")
+}
+
+// add Origin to Vary, when the headers depend on the origin of the request
+fn vary_on_origin(config: &CorsConfig, headers: &mut HeaderMap) {
+    if !allows_any_origin(config) {
+        headers.append(VARY, HeaderValue::from_static("Origin"));
+    }
+}
+
+// insert a header holding a comma-separated list of values, skipping invalid ones
+fn insert_list<'a>(
+    headers: &mut HeaderMap,
+    name: HeaderName,
+    values: impl Iterator<Item = &'a str>,
+) {
+    let list = values.collect::<Vec<_>>().join(", ");
+    if let Ok(value) = HeaderValue::from_str(&list) {
+        headers.insert(name, value);
+    }
+}
+
+// whether an origin matches a configured one: an exact origin, or an origin whose host starts
+// with `*.`, matching any of its subdomains but not the domain itself
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    match pattern.split_once("://*.") {
+        Some((scheme, domain)) => {
+            let Some((origin_scheme, host)) = origin.split_once("://") else {
+                return false;
+            };
+            let host = host.to_ascii_lowercase();
+            let suffix = format!(".{}", domain.to_ascii_lowercase());
+
+            origin_scheme.eq_ignore_ascii_case(scheme)
+                && host.len() > suffix.len()
+                && host.ends_with(&suffix)
+        }
+        None => pattern.eq_ignore_ascii_case(origin),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_match_exactly_or_by_subdomain() {
+        assert!(origin_matches(
+            "https://cards.example.com",
+            "https://Cards.example.com"
+        ));
+        assert!(!origin_matches(
+            "https://cards.example.com",
+            "http://cards.example.com"
+        ));
+        assert!(origin_matches(
+            "https://*.example.com",
+            "https://app.example.com"
+        ));
+        assert!(origin_matches(
+            "https://*.example.com",
+            "https://eu.app.example.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "https://example.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "https://app.badexample.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "http://app.example.com"
+        ));
+    }
+}
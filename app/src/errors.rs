@@ -1,34 +1,257 @@
 // app/src/errors.rs
 
 // dependencies
+use crate::configuration::RoutingConfig;
+use crate::models::CardStatus;
+use crate::rate_limit::Quota;
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::request::RequestHead;
 use pavex::response::Response;
-use pavex::{http::StatusCode, response::body::errors::JsonSerializationError};
-use serde::Serialize;
+use pavex::response::body::{
+    TypedBody,
+    errors::JsonSerializationError,
+    raw::{Bytes, Full},
+};
+use pavex::telemetry::ServerRequestId;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 
+// name of the unique constraint on the flashcards question column
+const QUESTION_UNIQUE_CONSTRAINT: &str = "flashcards_question_key";
+
+// the column SQLite names in the message of a unique violation on the question
+const QUESTION_UNIQUE_COLUMN: &str = "flashcards.question";
+
 #[derive(Debug, Error)]
 pub enum ApiError {
     #[error("Invalid API key")]
     ApiKeyError,
 
-    #[error("Questions must be unique: {0}")]
-    DuplicateQuestion(String),
+    #[error("Invalid user token")]
+    UserTokenError,
+
+    #[error("Only the API key of the default organization may manage organizations")]
+    DefaultOrganizationKeyRequired,
+
+    #[error("Questions must be unique")]
+    DuplicateQuestion,
+
+    #[error("Conflicting data, violates constraint: {0}")]
+    Conflict(String),
+
+    #[error("Invalid data, violates constraint: {0}")]
+    ConstraintViolation(String),
 
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("A {from} card cannot become {to}")]
+    InvalidStatusTransition { from: CardStatus, to: CardStatus },
+
+    #[error("The suggestion was already moderated")]
+    SuggestionClosed,
+
+    #[error("Too much feedback from this client")]
+    FeedbackLimitReached,
+
+    #[error("Too many requests from this client")]
+    RateLimited(Quota),
+
     #[error("Error serializing response data: {0}")]
     SerializationError(#[from] JsonSerializationError),
 
     #[error("Database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
+    DatabaseError(sqlx::Error),
 
     #[error("Uuid parsing error: {0}")]
     UuidError(#[from] uuid::Error),
 
+    #[error("Error running the Rust compiler: {0}")]
+    CompilerError(std::io::Error),
+
     #[error("Error validating incoming data: {0}")]
-    ValidationError(#[from] FlashcardValidationError),
+    ValidationError(#[from] ValidationErrors),
+}
+
+// methods for the ApiError type
+impl ApiError {
+    /// The HTTP status code associated with this error.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::ApiKeyError => StatusCode::UNAUTHORIZED,
+            ApiError::UserTokenError => StatusCode::UNAUTHORIZED,
+            ApiError::DefaultOrganizationKeyRequired => StatusCode::FORBIDDEN,
+            ApiError::DuplicateQuestion => StatusCode::CONFLICT,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::ConstraintViolation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::InvalidStatusTransition { .. } => StatusCode::CONFLICT,
+            ApiError::SuggestionClosed => StatusCode::CONFLICT,
+            ApiError::FeedbackLimitReached => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::UuidError(_) => StatusCode::BAD_REQUEST,
+            ApiError::CompilerError(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    /// A stable, machine-readable code identifying the kind of error.
+    ///
+    /// Clients should branch on this value rather than on the human-readable `detail`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::ApiKeyError => "invalid_api_key",
+            ApiError::UserTokenError => "invalid_user_token",
+            ApiError::DefaultOrganizationKeyRequired => "default_organization_key_required",
+            ApiError::DuplicateQuestion => "duplicate_question",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::ConstraintViolation(_) => "constraint_violation",
+            ApiError::DatabaseError(_) => "database_error",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::InvalidStatusTransition { .. } => "invalid_status_transition",
+            ApiError::SuggestionClosed => "suggestion_closed",
+            ApiError::FeedbackLimitReached => "feedback_limit_reached",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::SerializationError(_) => "serialization_error",
+            ApiError::UuidError(_) => "invalid_id",
+            ApiError::CompilerError(_) => "compiler_unavailable",
+            ApiError::ValidationError(_) => "validation_failed",
+        }
+    }
+
+    /// A short, human-readable summary of the kind of error.
+    pub fn title(&self) -> &'static str {
+        match self {
+            ApiError::ApiKeyError => "Invalid API key",
+            ApiError::UserTokenError => "Invalid user token",
+            ApiError::DefaultOrganizationKeyRequired => "Forbidden",
+            ApiError::DuplicateQuestion => "Duplicate question",
+            ApiError::Conflict(_) => "Conflict",
+            ApiError::ConstraintViolation(_) => "Constraint violation",
+            ApiError::DatabaseError(_) => "Internal server error",
+            ApiError::NotFound(_) => "Resource not found",
+            ApiError::InvalidStatusTransition { .. } => "Invalid status transition",
+            ApiError::SuggestionClosed => "Suggestion closed",
+            ApiError::FeedbackLimitReached => "Feedback limit reached",
+            ApiError::RateLimited(_) => "Too many requests",
+            ApiError::SerializationError(_) => "Internal server error",
+            ApiError::UuidError(_) => "Invalid identifier",
+            ApiError::CompilerError(_) => "Compiler unavailable",
+            ApiError::ValidationError(_) => "Validation failed",
+        }
+    }
+
+    /// A human-readable explanation specific to this occurrence of the error.
+    ///
+    /// Internal failures are reported with a generic message, the underlying error
+    /// is logged by the error observer but never sent to the client.
+    pub fn detail(&self) -> String {
+        match self {
+            ApiError::ApiKeyError => {
+                "The request is missing a valid API key in the Authorization header.".to_string()
+            }
+            ApiError::UserTokenError => {
+                "The request is missing a valid user token in the Authorization header.".to_string()
+            }
+            ApiError::DefaultOrganizationKeyRequired => {
+                "Organizations can only be managed with an API key of the default organization."
+                    .to_string()
+            }
+            ApiError::DuplicateQuestion => {
+                "A flashcard with the same question already exists.".to_string()
+            }
+            ApiError::Conflict(constraint) => constraint_detail(constraint)
+                .unwrap_or("The request conflicts with existing data.")
+                .to_string(),
+            ApiError::ConstraintViolation(constraint) => constraint_detail(constraint)
+                .unwrap_or("The request contains a value which is not accepted.")
+                .to_string(),
+            ApiError::DatabaseError(_) | ApiError::SerializationError(_) => {
+                "An unexpected error occurred while processing the request.".to_string()
+            }
+            ApiError::NotFound(details) => details.clone(),
+            ApiError::InvalidStatusTransition { from, to } => format!(
+                "A flashcard in the '{}' status cannot move to the '{}' status.",
+                from, to
+            ),
+            ApiError::SuggestionClosed => {
+                "The suggestion has already been approved or rejected.".to_string()
+            }
+            ApiError::FeedbackLimitReached => {
+                "Too much feedback was sent from this client recently, try again later.".to_string()
+            }
+            ApiError::RateLimited(quota) => format!(
+                "This client sent too many requests, try again in {} seconds.",
+                quota.retry_after.unwrap_or(1)
+            ),
+            ApiError::UuidError(_) => "The provided id is not a valid UUID.".to_string(),
+            ApiError::CompilerError(_) => {
+                "The Rust toolchain needed to check code snippets could not be run.".to_string()
+            }
+            ApiError::ValidationError(e) => e.to_string(),
+        }
+    }
+
+    /// The individual field errors, if this error is a validation failure.
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            ApiError::ValidationError(e) => e.errors(),
+            _ => &[],
+        }
+    }
+}
+
+// the explanation sent for a violated database constraint; the names of the constraints are part
+// of the schema, so they are only logged, by the error observer, and never sent to the client
+fn constraint_detail(constraint: &str) -> Option<&'static str> {
+    let detail = match constraint {
+        "tags_slug_key" => "A tag with the same name already exists.",
+        "topics_slug_key" => "A topic with the same slug already exists.",
+        "flashcard_drafts_question_key" => "A draft with the same question already exists.",
+        "organizations_slug_key" => "An organization with the same name already exists.",
+        "organization_hosts_pkey" => "The host is already served by another organization.",
+        "topics_parent_check" => "A topic cannot be its own parent.",
+        foreign_key if foreign_key.ends_with("_fkey") => {
+            "The request refers to a resource which does not exist."
+        }
+        _ => return None,
+    };
+
+    Some(detail)
+}
+
+// the central database error translation layer; every `sqlx::Error` coming out of
+// the queries is mapped to the API error matching what went wrong, so that missing
+// rows and constraint violations get a proper status code instead of a 500
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        let db_error = match &error {
+            sqlx::Error::RowNotFound => {
+                return ApiError::NotFound("The requested resource does not exist.".to_string());
+            }
+            sqlx::Error::Database(db_error) => db_error,
+            _ => return ApiError::DatabaseError(error),
+        };
+
+        // SQLite doesn't report constraint names, only the offending columns
+        let constraint = db_error.constraint().unwrap_or("unknown").to_string();
+        let is_question = constraint == QUESTION_UNIQUE_CONSTRAINT
+            || db_error.message().contains(QUESTION_UNIQUE_COLUMN);
+        match db_error.kind() {
+            ErrorKind::UniqueViolation if is_question => ApiError::DuplicateQuestion,
+            ErrorKind::UniqueViolation | ErrorKind::ForeignKeyViolation => {
+                ApiError::Conflict(constraint)
+            }
+            ErrorKind::CheckViolation | ErrorKind::NotNullViolation => {
+                ApiError::ConstraintViolation(constraint)
+            }
+            _ => ApiError::DatabaseError(error),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Error)]
@@ -47,36 +270,412 @@ pub enum FlashcardValidationError {
 
     #[error("Invalid difficulty level. Difficulty must be between 1 and 5")]
     InvalidDifficulty,
+
+    #[error(
+        "Question field cannot be longer than {} characters.",
+        crate::validation::MAX_QUESTION_LENGTH
+    )]
+    QuestionTooLong,
+
+    #[error(
+        "Answer field cannot be longer than {} characters.",
+        crate::validation::MAX_ANSWER_LENGTH
+    )]
+    AnswerTooLong,
+
+    #[error(
+        "Topic field cannot be longer than {} characters.",
+        crate::validation::MAX_TOPIC_LENGTH
+    )]
+    TopicTooLong,
+
+    #[error(
+        "A flash card cannot have more than {} tags.",
+        crate::validation::MAX_TAGS
+    )]
+    TooManyTags,
+
+    #[error("Tags cannot be empty.")]
+    EmptyTag,
+
+    #[error(
+        "Tags cannot be longer than {} characters.",
+        crate::validation::MAX_TAG_LENGTH
+    )]
+    TagTooLong,
+
+    #[error(
+        "Tags may only contain letters, digits, spaces, hyphens and underscores, and must start and end with a letter or digit."
+    )]
+    InvalidTagFormat,
+
+    #[error("Tags must be unique.")]
+    DuplicateTag,
+
+    #[error(
+        "Descriptions cannot be longer than {} characters.",
+        crate::validation::MAX_DESCRIPTION_LENGTH
+    )]
+    DescriptionTooLong,
+
+    #[error("Topics must contain at least one letter or digit.")]
+    InvalidTopicFormat,
+
+    #[error("A topic cannot be nested under itself or one of its descendants.")]
+    TopicCycle,
+
+    #[error("Count must be between 1 and {}.", crate::validation::MAX_RANDOM_COUNT)]
+    InvalidCount,
+
+    #[error("The minimum difficulty cannot be greater than the maximum difficulty.")]
+    InvalidDifficultyRange,
+
+    #[error("Unknown time zone; use an IANA name such as `Europe/Paris`.")]
+    InvalidTimeZone,
+
+    #[error(
+        "Quizzes must have between 1 and {} questions.",
+        crate::validation::MAX_QUIZ_SIZE
+    )]
+    InvalidQuizSize,
+
+    #[error("This answer refers to a question or an option which is not part of the quiz.")]
+    InvalidQuizAnswer,
+
+    #[error("Each question can only be answered once.")]
+    DuplicateQuizAnswer,
+
+    #[error(
+        "Multiple choice cards need between 2 and {} options.",
+        crate::validation::MAX_OPTIONS
+    )]
+    InvalidOptionCount,
+
+    #[error("Options cannot be empty.")]
+    EmptyOption,
+
+    #[error(
+        "Options cannot be longer than {} characters.",
+        crate::validation::MAX_OPTION_LENGTH
+    )]
+    OptionTooLong,
+
+    #[error("Duplicate options are not allowed.")]
+    DuplicateOption,
+
+    #[error("Multiple choice cards need at least one correct option.")]
+    NoCorrectOption,
+
+    #[error("Correct options must refer to distinct options of the card.")]
+    InvalidCorrectOption,
+
+    #[error("Cloze cards need at least one deletion, e.g. `{{{{c1::borrow checker}}}}`.")]
+    MissingClozeDeletion,
+
+    #[error("Cloze deletions must look like `{{{{c1::text}}}}` or `{{{{c1::text::hint}}}}`.")]
+    InvalidClozeFormat,
+
+    #[error("This card has no cloze deletion with that number.")]
+    InvalidClozeNumber,
+
+    #[error("Imports must name a `.json` file within the rustdoc directory.")]
+    InvalidImportPath,
+
+    #[error("This file cannot be read, or is not the JSON output of rustdoc.")]
+    InvalidRustdoc,
+
+    #[error("Rejecting a card requires a note for its author.")]
+    MissingReviewNote,
+
+    #[error(
+        "Reviewer notes cannot be longer than {} characters.",
+        crate::validation::MAX_REVIEW_NOTE_LENGTH
+    )]
+    ReviewNoteTooLong,
+
+    #[error("User names cannot be empty.")]
+    EmptyUserName,
+
+    #[error(
+        "User names cannot be longer than {} characters.",
+        crate::validation::MAX_USER_NAME_LENGTH
+    )]
+    UserNameTooLong,
+
+    #[error("A suggestion must change at least one field of the card.")]
+    EmptySuggestion,
+
+    #[error(
+        "Feedback comments cannot be longer than {} characters.",
+        crate::validation::MAX_FEEDBACK_COMMENT_LENGTH
+    )]
+    FeedbackCommentTooLong,
+
+    #[error("Organization names cannot be empty.")]
+    EmptyOrganizationName,
+
+    #[error(
+        "Organization names cannot be longer than {} characters.",
+        crate::validation::MAX_ORGANIZATION_NAME_LENGTH
+    )]
+    OrganizationNameTooLong,
+
+    #[error("Organization names must contain at least one letter or digit.")]
+    InvalidOrganizationName,
+
+    #[error("Hosts must be host names such as `flash.example.com`, without a port.")]
+    InvalidHost,
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
-    msg: String,
-    status: u16,
-    details: String,
+// methods for the FlashcardValidationError type
+impl FlashcardValidationError {
+    /// A stable, machine-readable code identifying the validation rule that failed.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FlashcardValidationError::EmptyQuestion => "empty_question",
+            FlashcardValidationError::EmptyAnswer => "empty_answer",
+            FlashcardValidationError::EmptyTopic => "empty_topic",
+            FlashcardValidationError::EmptyTags => "empty_tags",
+            FlashcardValidationError::InvalidDifficulty => "invalid_difficulty",
+            FlashcardValidationError::QuestionTooLong => "question_too_long",
+            FlashcardValidationError::AnswerTooLong => "answer_too_long",
+            FlashcardValidationError::TopicTooLong => "topic_too_long",
+            FlashcardValidationError::TooManyTags => "too_many_tags",
+            FlashcardValidationError::EmptyTag => "empty_tag",
+            FlashcardValidationError::TagTooLong => "tag_too_long",
+            FlashcardValidationError::InvalidTagFormat => "invalid_tag_format",
+            FlashcardValidationError::DuplicateTag => "duplicate_tag",
+            FlashcardValidationError::DescriptionTooLong => "description_too_long",
+            FlashcardValidationError::InvalidTopicFormat => "invalid_topic_format",
+            FlashcardValidationError::TopicCycle => "topic_cycle",
+            FlashcardValidationError::InvalidCount => "invalid_count",
+            FlashcardValidationError::InvalidDifficultyRange => "invalid_difficulty_range",
+            FlashcardValidationError::InvalidTimeZone => "invalid_time_zone",
+            FlashcardValidationError::InvalidQuizSize => "invalid_quiz_size",
+            FlashcardValidationError::InvalidQuizAnswer => "invalid_quiz_answer",
+            FlashcardValidationError::DuplicateQuizAnswer => "duplicate_quiz_answer",
+            FlashcardValidationError::InvalidOptionCount => "invalid_option_count",
+            FlashcardValidationError::EmptyOption => "empty_option",
+            FlashcardValidationError::OptionTooLong => "option_too_long",
+            FlashcardValidationError::DuplicateOption => "duplicate_option",
+            FlashcardValidationError::NoCorrectOption => "no_correct_option",
+            FlashcardValidationError::InvalidCorrectOption => "invalid_correct_option",
+            FlashcardValidationError::MissingClozeDeletion => "missing_cloze_deletion",
+            FlashcardValidationError::InvalidClozeFormat => "invalid_cloze_format",
+            FlashcardValidationError::InvalidClozeNumber => "invalid_cloze_number",
+            FlashcardValidationError::InvalidImportPath => "invalid_import_path",
+            FlashcardValidationError::InvalidRustdoc => "invalid_rustdoc",
+            FlashcardValidationError::MissingReviewNote => "missing_review_note",
+            FlashcardValidationError::ReviewNoteTooLong => "review_note_too_long",
+            FlashcardValidationError::EmptyUserName => "empty_user_name",
+            FlashcardValidationError::UserNameTooLong => "user_name_too_long",
+            FlashcardValidationError::EmptySuggestion => "empty_suggestion",
+            FlashcardValidationError::FeedbackCommentTooLong => "feedback_comment_too_long",
+            FlashcardValidationError::EmptyOrganizationName => "empty_organization_name",
+            FlashcardValidationError::OrganizationNameTooLong => "organization_name_too_long",
+            FlashcardValidationError::InvalidOrganizationName => "invalid_organization_name",
+            FlashcardValidationError::InvalidHost => "invalid_host",
+        }
+    }
 }
 
-// error handler for the static server endpoint
-pub fn api_error2response(error: &ApiError) -> Response {
-    let status = match error {
-        ApiError::ApiKeyError => StatusCode::UNAUTHORIZED,
-        ApiError::DuplicateQuestion(_) => StatusCode::CONFLICT,
-        ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-        ApiError::NotFound(_) => StatusCode::NOT_FOUND,
-        ApiError::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-        ApiError::UuidError(_) => StatusCode::BAD_REQUEST,
-        ApiError::ValidationError(_) => StatusCode::BAD_REQUEST,
-    };
+// struct type to represent a single failed validation rule on a given field
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub rule: String,
+    pub message: String,
+}
 
-    let payload = ErrorResponse {
-        msg: "Error".to_string(),
-        status: status.as_u16(),
-        details: error.to_string(),
-    };
+// struct type to represent every validation failure found in an incoming payload
+#[derive(Debug, Default, PartialEq, Error)]
+#[error("{} field(s) failed validation", .0.len())]
+pub struct ValidationErrors(Vec<FieldError>);
+
+// methods for the ValidationErrors type
+impl ValidationErrors {
+    /// Record a failed validation rule for the given field.
+    pub fn add(&mut self, field: impl Into<String>, error: FlashcardValidationError) {
+        self.0.push(FieldError {
+            field: field.into(),
+            rule: error.code().to_string(),
+            message: error.to_string(),
+        });
+    }
+
+    /// The individual field errors.
+    pub fn errors(&self) -> &[FieldError] {
+        &self.0
+    }
+
+    /// Returns `true` if no validation rule failed.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Turn the collected errors into a `Result`, `Ok` if no rule failed.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+// struct type to represent an RFC 9457 problem details document
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub instance: String,
+    pub code: String,
+    pub request_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+// methods for the ProblemDetails type
+impl ProblemDetails {
+    /// Build the problem details document describing the given error, its `type` under
+    /// `problem_type_base`.
+    pub fn new(
+        error: &ApiError,
+        instance: &str,
+        request_id: &ServerRequestId,
+        problem_type_base: &str,
+    ) -> Self {
+        let code = error.code();
+        Self {
+            problem_type: format!("{}{}", problem_type_base, code.replace('_', "-")),
+            title: error.title().to_string(),
+            status: error.status_code().as_u16(),
+            detail: error.detail(),
+            instance: instance.to_string(),
+            code: code.to_string(),
+            request_id: request_id.to_string(),
+            errors: error.field_errors().to_vec(),
+        }
+    }
+}
+
+// a serialized problem details document; wrapping it lets us set the
+// `application/problem+json` content type, which the `Json` body type doesn't allow
+struct ProblemJson(String);
+
+// implement the `TypedBody` trait for our ProblemJson type
+impl TypedBody for ProblemJson {
+    type Body = Full<Bytes>;
+
+    fn content_type(&self) -> HeaderValue {
+        HeaderValue::from_static("application/problem+json")
+    }
+
+    fn body(self) -> Self::Body {
+        Full::new(self.0.into())
+    }
+}
+
+// error handler for the API endpoints, responds with an RFC 9457 problem details document
+pub fn api_error2response(
+    error: &ApiError,
+    request_head: &RequestHead,
+    request_id: ServerRequestId,
+    routing: &RoutingConfig,
+) -> Response {
+    let status = error.status_code();
+    let problem_type_base = routing.problem_type_base();
+    let payload = ProblemDetails::new(
+        error,
+        request_head.target.path(),
+        &request_id,
+        &problem_type_base,
+    );
 
     let json = serde_json::to_string(&payload).unwrap_or_else(|_| {
-        r#"{"msg":"Error","status":500,"details":"Internal server error formatting error response"}"#.to_string()
+        format!(
+            r#"{{"type":"{}internal-error","title":"Internal server error","status":500,"code":"internal_error","request_id":"{}"}}"#,
+            problem_type_base, request_id
+        )
     });
 
-    Response::new(status).set_typed_body(json)
+    let mut response = Response::new(status).set_typed_body(ProblemJson(json));
+    if let ApiError::RateLimited(quota) = error {
+        quota.insert_headers(response.headers_mut());
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn database_errors_do_not_leak_details() {
+        let error = ApiError::DatabaseError(sqlx::Error::PoolTimedOut);
+        let problem = ProblemDetails::new(
+            &error,
+            "/v1/flashcards",
+            &ServerRequestId::generate(),
+            "https://rusty-flash-knowledge.net/problems/",
+        );
+
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.code, "database_error");
+        assert!(!problem.detail.contains("pool"));
+    }
+
+    #[test]
+    fn missing_rows_are_not_found() {
+        let error = ApiError::from(sqlx::Error::RowNotFound);
+
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(error.code(), "not_found");
+    }
+
+    #[test]
+    fn constraint_names_do_not_leak() {
+        let known = ApiError::Conflict("tags_slug_key".to_string());
+        let unknown = ApiError::ConstraintViolation("flashcards_kind_type_check".to_string());
+
+        assert_eq!(known.detail(), "A tag with the same name already exists.");
+        assert!(!known.detail().contains("tags_slug_key"));
+        assert_eq!(
+            unknown.detail(),
+            "The request contains a value which is not accepted."
+        );
+        assert_eq!(known.status_code(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn other_database_errors_are_internal() {
+        let error = ApiError::from(sqlx::Error::PoolTimedOut);
+
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn validation_errors_carry_every_field_error() {
+        let mut errors = ValidationErrors::default();
+        errors.add("answer", FlashcardValidationError::EmptyAnswer);
+        errors.add("difficulty", FlashcardValidationError::InvalidDifficulty);
+        let error = ApiError::ValidationError(errors);
+        let problem = ProblemDetails::new(
+            &error,
+            "/v1/flashcards",
+            &ServerRequestId::generate(),
+            "https://rusty-flash-knowledge.net/problems/",
+        );
+
+        assert_eq!(problem.status, 422);
+        assert_eq!(problem.code, "validation_failed");
+        assert_eq!(
+            problem.problem_type,
+            "https://rusty-flash-knowledge.net/problems/validation-failed"
+        );
+        assert_eq!(problem.instance, "/v1/flashcards");
+        assert_eq!(problem.errors.len(), 2);
+        assert_eq!(problem.errors[1].field, "difficulty");
+        assert_eq!(problem.errors[1].rule, "invalid_difficulty");
+    }
 }
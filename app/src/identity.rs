@@ -0,0 +1,135 @@
+// app/src/identity.rs
+
+// identification of the learners: on sign up, a user receives a random bearer token, of which
+// only a SHA-256 hash is stored; requests made on their behalf carry it in the Authorization
+// header, like the admin API key. Anonymous clients are told apart by a hash of their IP address,
+// as forwarded in X-Forwarded-For by the trusted reverse proxies
+
+// dependencies
+use crate::configuration::ProxyConfig;
+use crate::errors::ApiError;
+use crate::models::User;
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use pavex::connection::ConnectionInfo;
+use pavex::http::HeaderMap;
+use pavex::request::RequestHead;
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// A new random token, 244 bits of randomness from two version 4 UUIDs.
+pub fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// The hash of a token, as stored in the database.
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The token in the `Authorization: Bearer` header of a request, if any.
+pub fn bearer_token(request: &RequestHead) -> Option<&str> {
+    request
+        .headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// A hash identifying an anonymous client by its IP address, which is never stored as such.
+pub fn client_id(
+    request: &RequestHead,
+    connection_info: &ConnectionInfo,
+    proxy_config: &ProxyConfig,
+) -> String {
+    let peer = connection_info.peer_addr().ip();
+    hash_token(&client_ip(&request.headers, peer, proxy_config).to_string())
+}
+
+// the IP address of the client: the peer's or, from a trusted proxy, the last address of
+// X-Forwarded-For which isn't another trusted proxy's; the addresses before it were sent by the
+// client itself, which could forge them
+fn client_ip(headers: &HeaderMap, peer: IpAddr, proxy_config: &ProxyConfig) -> IpAddr {
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+
+    let mut client = peer;
+    for address in forwarded.into_iter().rev() {
+        if !proxy_config.trusts(client) {
+            break;
+        }
+        match address.trim().parse() {
+            Ok(address) => client = address,
+            Err(_) => break,
+        }
+    }
+
+    client.to_canonical()
+}
+
+/// Request-scoped constructor for the user making the request, identified by the token in the
+/// `Authorization: Bearer` header; `ApiError::UserTokenError` if it is missing or unknown.
+pub async fn authenticate_user(
+    request: &RequestHead,
+    repo: &FlashcardStore,
+) -> Result<User, ApiError> {
+    let token = bearer_token(request).ok_or(ApiError::UserTokenError)?;
+
+    repo.get_user_by_token(&hash_token(token))
+        .await?
+        .ok_or(ApiError::UserTokenError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_random_and_hashed_consistently() {
+        let token = new_token();
+
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn only_trusted_proxies_forward_the_client_address() {
+        let proxy_config = ProxyConfig {
+            trusted_addresses: vec!["10.0.0.2".parse().unwrap(), "10.0.0.3".parse().unwrap()],
+        };
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "x-forwarded-for",
+            "198.51.100.1, 203.0.113.7".parse().unwrap(),
+        );
+        headers.append("x-forwarded-for", "10.0.0.3".parse().unwrap());
+        let ip = |address: &str| address.parse::<IpAddr>().unwrap();
+
+        assert_eq!(
+            client_ip(&headers, ip("10.0.0.2"), &proxy_config),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(&headers, ip("192.0.2.9"), &proxy_config),
+            ip("192.0.2.9")
+        );
+        assert_eq!(
+            client_ip(&HeaderMap::new(), ip("10.0.0.2"), &proxy_config),
+            ip("10.0.0.2")
+        );
+    }
+}
@@ -2,13 +2,23 @@
 
 // module declarations
 mod blueprint;
+pub mod code_check;
 pub mod configuration;
+pub mod cors;
 pub mod errors;
+pub mod identity;
+pub mod markdown;
 pub mod middleware;
 pub mod models;
+pub mod progress;
 pub mod queries;
+pub mod rate_limit;
+pub mod repository;
 pub mod routes;
+pub mod rustdoc;
 pub mod telemetry;
+pub mod tenancy;
+pub mod validation;
 
 // re-exports
 pub use blueprint::blueprint;
@@ -0,0 +1,244 @@
+// app/src/markdown.rs
+
+// rendering of the Markdown held in questions and answers: fenced Rust code blocks are
+// highlighted, and the resulting HTML is sanitized so that it can be embedded as is
+
+// dependencies
+use ammonia::Builder;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html};
+use std::sync::LazyLock;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Prefix of the CSS classes given to highlighted tokens, e.g. `hl-keyword`.
+pub const HIGHLIGHT_CLASS_PREFIX: &str = "hl-";
+
+// the syntax definitions bundled with syntect, loaded once
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+// the sanitizer; on top of its defaults, the highlighting classes are kept
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .add_tag_attributes("pre", &["class"])
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("span", &["class"]);
+    builder
+});
+
+/// Whether the info string of a fenced code block marks Rust code. As with rustdoc, a block
+/// without a language is Rust, and attributes such as `ignore` or `edition2021` don't change
+/// the language.
+pub fn is_rust_block(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attribute| !attribute.is_empty())
+        .all(|attribute| attribute == "rust" || is_rustdoc_attribute(attribute))
+}
+
+// the attributes rustdoc understands on a Rust code block, besides the language
+fn is_rustdoc_attribute(attribute: &str) -> bool {
+    matches!(
+        attribute,
+        "ignore" | "should_panic" | "no_run" | "compile_fail" | "test_harness" | "standalone"
+    ) || attribute.starts_with("edition")
+}
+
+// struct type to represent a fenced Rust code block, along with the rustdoc attributes which
+// change how it is compile-checked
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RustSnippet {
+    pub code: String,
+    pub ignore: bool,
+    pub compile_fail: bool,
+    // e.g. `2021` for an `edition2021` block
+    pub edition: Option<String>,
+}
+
+/// The fenced Rust code blocks of a Markdown document, in order of appearance.
+pub fn rust_snippets(source: &str) -> Vec<RustSnippet> {
+    let mut snippets = Vec::new();
+    let mut current: Option<RustSnippet> = None;
+    for event in Parser::new(source) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) if is_rust_block(&info) => {
+                let mut snippet = RustSnippet::default();
+                for attribute in info.split(|c: char| c == ',' || c.is_whitespace()) {
+                    match attribute {
+                        "ignore" => snippet.ignore = true,
+                        "compile_fail" => snippet.compile_fail = true,
+                        _ => {
+                            if let Some(edition) = attribute.strip_prefix("edition") {
+                                snippet.edition = Some(edition.to_string());
+                            }
+                        }
+                    }
+                }
+                current = Some(snippet);
+            }
+            Event::Text(text) => {
+                if let Some(snippet) = current.as_mut() {
+                    snippet.code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => snippets.extend(current.take()),
+            _ => {}
+        }
+    }
+
+    snippets
+}
+
+/// Render Markdown to sanitized HTML, with fenced Rust code blocks highlighted.
+pub fn render_markdown(source: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    for event in Parser::new_ext(source, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => "text".to_string(),
+                };
+                code_block = Some((info, String::new()));
+            }
+            Event::Text(text) => match code_block.as_mut() {
+                Some((_, code)) => code.push_str(&text),
+                None => events.push(Event::Text(text)),
+            },
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((info, code)) = code_block.take() {
+                    events.push(Event::Html(render_code_block(&info, &code).into()));
+                }
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::with_capacity(source.len() * 2);
+    html::push_html(&mut unsafe_html, events.into_iter());
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+// render a code block, highlighted when it holds Rust code
+fn render_code_block(info: &str, code: &str) -> String {
+    let highlighted = if is_rust_block(info) {
+        highlight_rust(code)
+    } else {
+        None
+    };
+
+    match highlighted {
+        Some(highlighted) => format!(
+            "<pre class=\"code rust\"><code>{}</code></pre>\n",
+            highlighted
+        ),
+        None => format!(
+            "<pre class=\"code\"><code>{}</code></pre>\n",
+            escape_html(code)
+        ),
+    }
+}
+
+// highlight Rust code, each token wrapped in a span with its scopes as classes
+fn highlight_rust(code: &str) -> Option<String> {
+    let syntax = SYNTAXES.find_syntax_by_token("rust")?;
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax,
+        &SYNTAXES,
+        ClassStyle::SpacedPrefixed {
+            prefix: HIGHLIGHT_CLASS_PREFIX,
+        },
+    );
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+
+    Some(generator.finalize())
+}
+
+// escape the characters with a meaning in HTML
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rust_blocks_are_highlighted() {
+        let html = render_markdown("```rust\nfn main() {}\n```");
+
+        assert!(html.starts_with("<pre class=\"code rust\"><code>"));
+        assert!(html.contains("<span class=\"hl-"));
+        assert!(html.contains("main"));
+    }
+
+    #[test]
+    fn indentation_of_code_blocks_is_kept() {
+        let html = render_markdown("```text\nif x {\n    y();\n}\n```");
+
+        assert_eq!(
+            html,
+            "<pre class=\"code\"><code>if x {\n    y();\n}\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn raw_html_is_sanitized() {
+        let html = render_markdown("A <script>alert(1)</script> <b onclick=\"x()\">bold</b> word");
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onclick"));
+        assert!(html.contains("<b>bold</b>"));
+    }
+
+    #[test]
+    fn rust_snippets_carry_their_rustdoc_attributes() {
+        let source = "```rust,compile_fail,edition2021\nlet x: u8 = 256;\n```\n\n```toml\n[a]\n```\n\n```ignore\nfoo();\n```";
+
+        let snippets = rust_snippets(source);
+
+        assert_eq!(
+            snippets,
+            vec![
+                RustSnippet {
+                    code: "let x: u8 = 256;\n".to_string(),
+                    ignore: false,
+                    compile_fail: true,
+                    edition: Some("2021".to_string()),
+                },
+                RustSnippet {
+                    code: "foo();\n".to_string(),
+                    ignore: true,
+                    compile_fail: false,
+                    edition: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rustdoc_attributes_keep_blocks_rust() {
+        assert!(is_rust_block(""));
+        assert!(is_rust_block("rust,ignore"));
+        assert!(is_rust_block("compile_fail edition2021"));
+        assert!(!is_rust_block("toml"));
+        assert!(!is_rust_block("rust,python"));
+    }
+}
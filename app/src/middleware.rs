@@ -1,46 +1,170 @@
 // app/src/middleware.rs
 
 // dependencies
-use crate::configuration::AuthConfig;
+use crate::configuration::{
+    AuthConfig, CorsConfig, ProxyConfig, RateLimit, RateLimitConfig, RoutingConfig,
+};
+use crate::cors;
 use crate::errors::ApiError;
-use pavex::http::HeaderValue;
+use crate::identity::{bearer_token, client_id, hash_token};
+use crate::rate_limit::{RateLimitStatus, RateLimiter};
+use crate::tenancy::{Tenant, normalize_host};
+use pavex::connection::ConnectionInfo;
+use pavex::http::header::HOST;
 use pavex::middleware::Processing;
 use pavex::request::RequestHead;
 use pavex::response::Response;
 
-// pre-processing middleware function  which tests the validity of the API key, contained in Authorization: Bearer, in the request header
-pub async fn validate_api_key(
+// pre-processing middleware functions which only let a request reach the public API, the admin API
+// or the web pages when it was sent to the host, and under the path prefix, they are configured
+// to be served at; any other request is answered as if no route matched it
+pub fn route_public_api(request: &RequestHead, config: &RoutingConfig) -> Processing {
+    let public = &config.public;
+    route(request, config, &public.host, Some(&public.prefix))
+}
+
+pub fn route_admin_api(request: &RequestHead, config: &RoutingConfig) -> Processing {
+    let admin = &config.admin;
+    route(request, config, &admin.host, Some(&admin.prefix))
+}
+
+pub fn route_web(request: &RequestHead, config: &RoutingConfig) -> Processing {
+    route(request, config, &config.web.host, None)
+}
+
+fn route(
+    request: &RequestHead,
+    config: &RoutingConfig,
+    served_host: &str,
+    prefix: Option<&str>,
+) -> Processing {
+    let host = request
+        .headers
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .and_then(normalize_host);
+
+    if config.serves(served_host, prefix, host.as_deref(), request.target.path()) {
+        Processing::Continue
+    } else {
+        Processing::EarlyReturn(Response::not_found())
+    }
+}
+
+// the inputs of the rate limiting middlewares, which only differ by the limit they apply
+struct RateLimitInputs<'a> {
+    request: &'a RequestHead,
+    connection_info: &'a ConnectionInfo,
+    auth_config: &'a AuthConfig,
+    proxy_config: &'a ProxyConfig,
+    limiter: &'a RateLimiter,
+}
+
+// pre-processing middleware functions which take a token from the bucket of the client for the
+// public or the admin API, and refuse the request with a 429 when it is empty; the outcome is
+// kept for the response headers. They run before the organization of the request is resolved,
+// so that the requests beyond the limit never reach the database
+pub fn rate_limit_public_api(
+    request: &RequestHead,
+    connection_info: &ConnectionInfo,
     auth_config: &AuthConfig,
+    proxy_config: &ProxyConfig,
+    limiter: &RateLimiter,
+    config: &RateLimitConfig,
+    status: &mut RateLimitStatus,
+) -> Result<Processing, ApiError> {
+    let inputs = RateLimitInputs {
+        request,
+        connection_info,
+        auth_config,
+        proxy_config,
+        limiter,
+    };
+    rate_limit(inputs, config, "public", &config.public, status)
+}
+
+pub fn rate_limit_admin_api(
     request: &RequestHead,
+    connection_info: &ConnectionInfo,
+    auth_config: &AuthConfig,
+    proxy_config: &ProxyConfig,
+    limiter: &RateLimiter,
+    config: &RateLimitConfig,
+    status: &mut RateLimitStatus,
 ) -> Result<Processing, ApiError> {
-    let is_authorized = request
-        .headers
-        .get("authorization")
-        .and_then(|h| h.to_str().ok())
-        .map(|value| value == format!("Bearer {}", auth_config.api_key))
-        .unwrap_or(false);
+    let inputs = RateLimitInputs {
+        request,
+        connection_info,
+        auth_config,
+        proxy_config,
+        limiter,
+    };
+    rate_limit(inputs, config, "admin", &config.admin, status)
+}
+
+fn rate_limit(
+    inputs: RateLimitInputs<'_>,
+    config: &RateLimitConfig,
+    api: &str,
+    limit: &RateLimit,
+    status: &mut RateLimitStatus,
+) -> Result<Processing, ApiError> {
+    if !config.enabled {
+        return Ok(Processing::Continue);
+    }
+
+    let client = client_id(inputs.request, inputs.connection_info, inputs.proxy_config);
+    let ip_key = format!("{}:ip:{}", api, client);
+    let quota = match bearer_token(inputs.request) {
+        Some(token) if token == inputs.auth_config.api_key => {
+            let key = format!("{}:api_key:{}", api, hash_token(token));
+            inputs.limiter.check(key, limit)
+        }
+        Some(token) => {
+            let key = format!("{}:token:{}", api, hash_token(token));
+            inputs.limiter.check_or_open(key, ip_key, limit)
+        }
+        None => inputs.limiter.check(ip_key, limit),
+    };
+    status.0 = Some(quota.clone());
+    if quota.retry_after.is_some() {
+        return Err(ApiError::RateLimited(quota));
+    }
+
+    Ok(Processing::Continue)
+}
+
+// post-processing function to add the RateLimit-* headers of the quota of the request, if it
+// went through the rate limiter
+pub fn add_rate_limit_headers(response: Response, status: &RateLimitStatus) -> Response {
+    let mut response = response;
+
+    if let Some(quota) = &status.0 {
+        quota.insert_headers(response.headers_mut());
+    }
 
-    if !is_authorized {
+    response
+}
+
+// pre-processing middleware function  which tests the validity of the API key, contained in Authorization: Bearer, in the request header;
+// the key is checked while resolving the organization of the request, which it must belong to
+pub async fn validate_api_key(tenant: &Tenant) -> Result<Processing, ApiError> {
+    if !tenant.authenticated {
         return Err(ApiError::ApiKeyError);
     }
 
     Ok(Processing::Continue)
 }
 
-// post-processing function to add CORS related headers
-pub fn add_cors_headers(response: Response) -> Response {
+// post-processing function to add the CORS related headers allowed by the configured policy
+pub fn add_cors_headers(
+    response: Response,
+    request: &RequestHead,
+    config: &CorsConfig,
+) -> Response {
     let mut response = response;
 
-    let headers = response.headers_mut();
-    headers.insert("Access-Control-Allow-Origin", HeaderValue::from_static("*"));
-    headers.insert(
-        "Access-Control-Allow-Methods",
-        HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),
-    );
-    headers.insert(
-        "Access-Control-Allow-Headers",
-        HeaderValue::from_static("Content-Type, Authorization"),
-    );
+    cors::apply(config, request, response.headers_mut());
 
     response
 }
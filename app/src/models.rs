@@ -3,11 +3,21 @@
 // data models for the rusty-flash-knowledge api
 
 // dependencies
-use crate::errors::FlashcardValidationError;
+use crate::errors::ValidationErrors;
+use crate::validation::{
+    MAX_DIFFICULTY, MIN_DIFFICULTY, empty_suggestion_error, validate_card_kind,
+    validate_new_flashcard, validate_updated_flashcard,
+};
 use jiff_sqlx::{Timestamp as SqlxTimestamp, ToSqlx};
+use pavex::time::SignedDuration;
 use pavex::time::Timestamp as PavexTimestamp;
-use serde::{Deserialize, Serialize};
+use pavex::time::civil::Date;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 // struct type to represent a flash card
@@ -19,34 +29,265 @@ pub struct FlashCard {
     pub topic: String,
     pub tags: Vec<String>,
     pub difficulty: i32,
+    #[sqlx(json)]
+    pub kind: CardKind,
+    #[sqlx(try_from = "String")]
+    pub status: CardStatus,
     pub created_at: SqlxTimestamp,
     pub updated_at: Option<SqlxTimestamp>,
 }
 
-// implement the TryFrom trait, which aids in converting new data into the domain data model
-impl TryFrom<NewFlashCard> for FlashCard {
-    type Error = FlashcardValidationError;
+// enum type to represent where a flash card stands in the editorial workflow; only published
+// cards are shown to learners
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardStatus {
+    // being written, the status of every new card
+    #[default]
+    Draft,
+    // submitted, waiting for a reviewer to approve or reject it
+    InReview,
+    // approved, live on the public API
+    Published,
+    // withdrawn from the public API, kept for the record
+    Archived,
+}
 
-    fn try_from(new: NewFlashCard) -> Result<Self, Self::Error> {
-        if new.question.trim().is_empty() {
-            return Err(FlashcardValidationError::EmptyQuestion);
+// methods for the CardStatus type
+impl CardStatus {
+    /// The name of the status, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardStatus::Draft => "draft",
+            CardStatus::InReview => "in_review",
+            CardStatus::Published => "published",
+            CardStatus::Archived => "archived",
         }
+    }
 
-        if new.answer.trim().is_empty() {
-            return Err(FlashcardValidationError::EmptyAnswer);
-        }
+    /// Whether a card can move from this status to the given one: drafts are submitted for
+    /// review, reviews are approved or rejected back to draft, and published cards archived.
+    pub fn can_become(&self, next: CardStatus) -> bool {
+        matches!(
+            (self, next),
+            (CardStatus::Draft, CardStatus::InReview)
+                | (CardStatus::InReview, CardStatus::Published)
+                | (CardStatus::InReview, CardStatus::Draft)
+                | (CardStatus::Published, CardStatus::Archived)
+        )
+    }
+}
 
-        if new.topic.trim().is_empty() {
-            return Err(FlashcardValidationError::EmptyTopic);
+// implement the Display trait, for error messages
+impl std::fmt::Display for CardStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// implement the TryFrom trait, so that a status can be read back from the database
+impl TryFrom<String> for CardStatus {
+    type Error = String;
+
+    fn try_from(status: String) -> Result<Self, Self::Error> {
+        match status.as_str() {
+            "draft" => Ok(CardStatus::Draft),
+            "in_review" => Ok(CardStatus::InReview),
+            "published" => Ok(CardStatus::Published),
+            "archived" => Ok(CardStatus::Archived),
+            _ => Err(format!("unknown card status: {}", status)),
         }
+    }
+}
+
+// struct type to represent a change of status of a flash card, along with the note left by the
+// reviewer, e.g. why a card was rejected
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct CardReview {
+    pub id: Uuid,
+    pub flashcard_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub from_status: CardStatus,
+    #[sqlx(try_from = "String")]
+    pub to_status: CardStatus,
+    pub note: Option<String>,
+    pub reviewed_at: SqlxTimestamp,
+}
+
+// methods for the CardReview type
+impl CardReview {
+    /// A review moving a card from its current status to the given one, `None` if the workflow
+    /// doesn't allow it.
+    pub fn of(card: &FlashCard, to_status: CardStatus, note: Option<&str>) -> Option<Self> {
+        card.status.can_become(to_status).then(|| Self {
+            id: Uuid::new_v4(),
+            flashcard_id: card.id,
+            from_status: card.status,
+            to_status,
+            note: note
+                .map(str::trim)
+                .filter(|note| !note.is_empty())
+                .map(str::to_string),
+            reviewed_at: PavexTimestamp::now().to_sqlx(),
+        })
+    }
+}
+
+// struct type to represent the decision of a reviewer on a card, with an optional note; a note
+// is required to reject a card
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewDecision {
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+// enum type to represent the kind of a flash card, along with the data needed to grade it; the
+// answer of every kind holds the explanation shown once the card is answered
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CardKind {
+    // a question answered in free text
+    #[default]
+    FreeText,
+    // a question with a list of options, one or more of them correct (zero-based indexes)
+    MultipleChoice {
+        options: Vec<String>,
+        correct: Vec<usize>,
+    },
+    // a statement which is either true or false
+    TrueFalse {
+        correct: bool,
+    },
+    // a question holding cloze deletions, e.g. `{{c1::borrow checker}}`; each deletion number
+    // makes a sub-card
+    Cloze,
+}
 
-        if new.tags.is_empty() {
-            return Err(FlashcardValidationError::EmptyTags);
+// methods for the CardKind type
+impl CardKind {
+    /// The same kind, with the multiple choice options trimmed.
+    pub fn trimmed(&self) -> Self {
+        match self {
+            CardKind::MultipleChoice { options, correct } => CardKind::MultipleChoice {
+                options: options
+                    .iter()
+                    .map(|option| option.trim().to_string())
+                    .collect(),
+                correct: correct.clone(),
+            },
+            kind => kind.clone(),
         }
+    }
+}
+
+// struct type to represent a cloze deletion: `{{c1::borrow checker}}`, or `{{c1::borrow
+// checker::a compiler pass}}` with a hint
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClozeDeletion {
+    pub number: u32,
+    pub text: String,
+    pub hint: Option<String>,
+    // the byte range of the whole deletion within the question, braces included
+    pub span: std::ops::Range<usize>,
+}
 
-        if new.difficulty < 1 || new.difficulty > 5 {
-            return Err(FlashcardValidationError::InvalidDifficulty);
+// enum type to represent the ways cloze deletions can be malformed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClozeError {
+    // a `{{c` without its closing `}}`
+    Unclosed,
+    // a deletion without a number, or without `::` after it
+    MissingNumber,
+    // a deletion which hides nothing
+    EmptyDeletion,
+}
+
+/// Parse the cloze deletions of a question, in order of appearance.
+pub fn parse_cloze(question: &str) -> Result<Vec<ClozeDeletion>, ClozeError> {
+    let mut deletions = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = question[offset..].find("{{c") {
+        let start = offset + found;
+        let body_start = start + "{{c".len();
+        let end = question[body_start..]
+            .find("}}")
+            .map(|found| body_start + found)
+            .ok_or(ClozeError::Unclosed)?;
+        let (number, content) = question[body_start..end]
+            .split_once("::")
+            .ok_or(ClozeError::MissingNumber)?;
+        let number: u32 = number.parse().map_err(|_| ClozeError::MissingNumber)?;
+        let (text, hint) = match content.split_once("::") {
+            Some((text, hint)) => (text, Some(hint.trim().to_string())),
+            None => (content, None),
+        };
+        if text.trim().is_empty() {
+            return Err(ClozeError::EmptyDeletion);
         }
+        deletions.push(ClozeDeletion {
+            number,
+            text: text.trim().to_string(),
+            hint: hint.filter(|hint| !hint.is_empty()),
+            span: start..end + "}}".len(),
+        });
+        offset = end + "}}".len();
+    }
+
+    Ok(deletions)
+}
+
+// struct type to represent a sub-card of a cloze card: the question with the deletions of one
+// number hidden, and the hidden text as the answer
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClozeCard {
+    pub number: u32,
+    pub prompt: String,
+    pub answer: String,
+}
+
+/// Expand a cloze question into its sub-cards, one per deletion number, in ascending order; the
+/// hidden deletions read `[...]`, or `[hint]`, and the others show their text. A malformed
+/// question has no sub-cards.
+pub fn cloze_cards(question: &str) -> Vec<ClozeCard> {
+    let deletions = parse_cloze(question).unwrap_or_default();
+    let mut numbers: Vec<u32> = deletions.iter().map(|deletion| deletion.number).collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+
+    numbers
+        .into_iter()
+        .map(|number| {
+            let mut prompt = String::with_capacity(question.len());
+            let mut answers = Vec::new();
+            let mut position = 0;
+            for deletion in &deletions {
+                prompt.push_str(&question[position..deletion.span.start]);
+                if deletion.number == number {
+                    let hint = deletion.hint.as_deref().unwrap_or("...");
+                    prompt.push_str(&format!("[{}]", hint));
+                    answers.push(deletion.text.clone());
+                } else {
+                    prompt.push_str(&deletion.text);
+                }
+                position = deletion.span.end;
+            }
+            prompt.push_str(&question[position..]);
+            ClozeCard {
+                number,
+                prompt,
+                answer: answers.join(", "),
+            }
+        })
+        .collect()
+}
+
+// implement the TryFrom trait, which aids in converting new data into the domain data model
+impl TryFrom<NewFlashCard> for FlashCard {
+    type Error = ValidationErrors;
+
+    fn try_from(new: NewFlashCard) -> Result<Self, Self::Error> {
+        validate_new_flashcard(&new)?;
 
         let now = PavexTimestamp::now().to_sqlx();
 
@@ -55,8 +296,10 @@ impl TryFrom<NewFlashCard> for FlashCard {
             question: new.question.trim().to_string(),
             answer: new.answer.trim().to_string(),
             topic: new.topic.trim().to_string(),
-            tags: new.tags,
+            tags: new.tags.iter().map(|tag| tag.trim().to_string()).collect(),
             difficulty: new.difficulty,
+            kind: new.kind.trimmed(),
+            status: CardStatus::Draft,
             created_at: now,
             updated_at: None,
         })
@@ -72,10 +315,12 @@ pub struct NewFlashCard {
     pub topic: String,
     pub tags: Vec<String>,
     pub difficulty: i32,
+    #[serde(default)]
+    pub kind: CardKind,
 }
 
 // struct type to represent an updated flash card, coming in as input
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdatedFlashCard {
     pub question: Option<String>,
@@ -83,12 +328,1271 @@ pub struct UpdatedFlashCard {
     pub topic: Option<String>,
     pub tags: Option<Vec<String>>,
     pub difficulty: Option<i32>,
+    pub kind: Option<CardKind>,
+}
+
+// struct type to represent a tag, along with the number of flash cards carrying it
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct Tag {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub card_count: i64,
+}
+
+// struct type to represent changes to a tag, coming in as input
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedTag {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+// struct type to represent a request to merge a tag into another one, coming in as input
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMerge {
+    pub into: String,
+}
+
+// struct type to represent a topic of the curriculum, along with the number of flash cards
+// filed directly under it; `parent` is the slug of the parent topic, if any
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct Topic {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub parent: Option<String>,
+    pub card_count: i64,
+}
+
+// struct type to represent a topic within the topic tree; `total_card_count` includes the
+// flash cards filed under every descendant
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TopicNode {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub card_count: i64,
+    pub total_card_count: i64,
+    pub children: Vec<TopicNode>,
+}
+
+// struct type to represent a new topic, coming in as input
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTopic {
+    pub name: String,
+    pub description: Option<String>,
+    pub parent: Option<String>,
+}
+
+// struct type to represent changes to a topic, coming in as input; `parent` is left untouched
+// when missing, and set to `null` to turn the topic into a root topic
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedTopic {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent: Option<Option<String>>,
+}
+
+// the way random flash cards can be weighted by their difficulty
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DifficultyWeighting {
+    // harder cards come up more often
+    Harder,
+    // easier cards come up more often
+    Easier,
+}
+
+// methods for the DifficultyWeighting type
+impl DifficultyWeighting {
+    /// The name of the weighting, as passed to the database to weight the cards there.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DifficultyWeighting::Harder => "harder",
+            DifficultyWeighting::Easier => "easier",
+        }
+    }
+
+    /// The relative weight of a card with the given difficulty; always positive.
+    pub fn weight(self, difficulty: i32) -> f64 {
+        let difficulty = difficulty.clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+        match self {
+            DifficultyWeighting::Harder => f64::from(difficulty),
+            DifficultyWeighting::Easier => f64::from(MAX_DIFFICULTY + 1 - difficulty),
+        }
+    }
+}
+
+// struct type to represent the criteria used to pick random flash cards; a card must match the
+// topic, carry at least one of the tags (if any), and have a difficulty within the range
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomSelection {
+    pub topic: Option<String>,
+    pub tags: Vec<String>,
+    pub min_difficulty: i32,
+    pub max_difficulty: i32,
+    pub count: usize,
+    pub exclude: Vec<Uuid>,
+    pub weighting: Option<DifficultyWeighting>,
+}
+
+// the default selection is a single card, picked uniformly among every card
+impl Default for RandomSelection {
+    fn default() -> Self {
+        Self {
+            topic: None,
+            tags: Vec::new(),
+            min_difficulty: MIN_DIFFICULTY,
+            max_difficulty: MAX_DIFFICULTY,
+            count: 1,
+            exclude: Vec::new(),
+            weighting: None,
+        }
+    }
+}
+
+// methods for the RandomSelection type
+impl RandomSelection {
+    /// Whether the given card meets every criteria of the selection.
+    pub fn matches(&self, card: &FlashCard) -> bool {
+        let topic_matches = self
+            .topic
+            .as_ref()
+            .is_none_or(|topic| slugify(topic) == slugify(&card.topic));
+        let tags_match = self.tags.is_empty()
+            || card.tags.iter().any(|tag| {
+                self.tags
+                    .iter()
+                    .any(|wanted| slugify(wanted) == slugify(tag))
+            });
+
+        topic_matches
+            && tags_match
+            && (self.min_difficulty..=self.max_difficulty).contains(&card.difficulty)
+            && !self.exclude.contains(&card.id)
+    }
+}
+
+// struct type to represent the criteria used to pick the card of the day: the calendar day, an
+// optional topic, and the number of previous days whose cards shouldn't come up again
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailySelection {
+    pub day: Date,
+    pub topic: Option<String>,
+    pub repeat_window: u32,
+}
+
+// methods for the DailySelection type
+impl DailySelection {
+    /// The day, as recorded in the card of the day history, e.g. `2026-10-19`.
+    pub fn day_key(&self) -> String {
+        self.day.to_string()
+    }
+
+    /// The first day of the repeat window, as recorded in the card of the day history.
+    pub fn window_start(&self) -> String {
+        let window = SignedDuration::from_hours(24 * i64::from(self.repeat_window));
+        self.day.saturating_sub(window).to_string()
+    }
+
+    /// The history a card of the day belongs to: the topic slug, or empty for every card.
+    pub fn scope(&self) -> String {
+        self.topic.as_deref().map(slugify).unwrap_or_default()
+    }
+
+    /// Pick the card of the day among the candidates, leaving out the recent picks unless
+    /// every candidate was picked recently. The pick only depends on the day, the scope and the
+    /// ids, so it is stable across restarts.
+    pub fn pick(&self, candidates: &[Uuid], recent: &[Uuid]) -> Option<Uuid> {
+        let mut fresh: Vec<Uuid> = candidates
+            .iter()
+            .filter(|id| !recent.contains(id))
+            .copied()
+            .collect();
+        if fresh.is_empty() {
+            fresh = candidates.to_vec();
+        }
+        if fresh.is_empty() {
+            return None;
+        }
+        fresh.sort();
+        let seed = daily_seed(&format!("{}/{}", self.day_key(), self.scope()));
+
+        Some(fresh[(seed % fresh.len() as u64) as usize])
+    }
+}
+
+// a hash of the key which, unlike the standard library hasher, stays the same across releases;
+// FNV-1a followed by the SplitMix64 finalizer, so that consecutive days land far apart
+fn daily_seed(key: &str) -> u64 {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let mut z = hash.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Number of options offered by a quiz question, the right answer included.
+pub const QUIZ_OPTIONS: usize = 4;
+
+// struct type to represent a multiple-choice question of a quiz, built from a flash card; the
+// question and options are a snapshot, so later edits to the card don't change the quiz
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct QuizQuestion {
+    pub flashcard_id: Uuid,
+    pub question: String,
+    pub options: Vec<String>,
+    #[sqlx(try_from = "i32")]
+    pub correct_option: usize,
+}
+
+// struct type to represent a quiz, along with its answer key
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quiz {
+    pub id: Uuid,
+    pub topic: Option<String>,
+    pub tag: Option<String>,
+    pub questions: Vec<QuizQuestion>,
+    pub created_at: SqlxTimestamp,
+}
+
+// methods for the Quiz type
+impl Quiz {
+    /// Build a quiz asking the given cards. Multiple choice cards with a single correct option
+    /// and true/false cards bring their own options. For the other cards, the distractors are
+    /// the answers of other cards of the corpus filed under the same topic or sharing a tag,
+    /// topped up with unrelated answers when too few are related; a cloze card asks its first
+    /// sub-card. Cards which can't make a question are left out.
+    pub fn build(
+        topic: Option<String>,
+        tag: Option<String>,
+        cards: &[FlashCard],
+        corpus: &[FlashCard],
+        rng: &mut impl Rng,
+    ) -> Self {
+        let questions = cards
+            .iter()
+            .filter_map(|card| quiz_question(card, corpus, rng))
+            .collect();
+
+        Self {
+            id: Uuid::new_v4(),
+            topic,
+            tag,
+            questions,
+            created_at: PavexTimestamp::now().to_sqlx(),
+        }
+    }
+
+    /// Grade a submission; the questions without an answer count as unanswered. The submission
+    /// is expected to be validated against the quiz beforehand.
+    pub fn grade(&self, submission: &QuizSubmission) -> QuizResult {
+        let questions: Vec<QuestionResult> = self
+            .questions
+            .iter()
+            .enumerate()
+            .map(|(index, question)| {
+                let selected_option = submission
+                    .answers
+                    .iter()
+                    .find(|answer| answer.question == index)
+                    .map(|answer| answer.option);
+                QuestionResult {
+                    question: index,
+                    flashcard_id: question.flashcard_id,
+                    selected_option,
+                    correct_option: question.correct_option,
+                    correct: selected_option == Some(question.correct_option),
+                }
+            })
+            .collect();
+        let total = questions.len();
+        let correct = questions.iter().filter(|q| q.correct).count();
+        let unanswered = questions
+            .iter()
+            .filter(|q| q.selected_option.is_none())
+            .count();
+        let score = if total == 0 {
+            0.0
+        } else {
+            100.0 * correct as f64 / total as f64
+        };
+
+        QuizResult {
+            quiz_id: self.id,
+            total,
+            correct,
+            incorrect: total - correct - unanswered,
+            unanswered,
+            score,
+            questions,
+        }
+    }
+}
+
+// the quiz question made out of a card, if it can make one
+fn quiz_question(
+    card: &FlashCard,
+    corpus: &[FlashCard],
+    rng: &mut impl Rng,
+) -> Option<QuizQuestion> {
+    let (question, options, correct_option) = match &card.kind {
+        CardKind::MultipleChoice { options, correct } if correct.len() == 1 => {
+            (card.question.clone(), options.clone(), correct[0])
+        }
+        CardKind::MultipleChoice { .. } => return None,
+        CardKind::TrueFalse { correct } => (
+            card.question.clone(),
+            vec!["True".to_string(), "False".to_string()],
+            if *correct { 0 } else { 1 },
+        ),
+        CardKind::FreeText | CardKind::Cloze => {
+            let (question, answer) = quiz_prompt(card)?;
+            let mut options = distractors(card, &answer, corpus, rng);
+            if options.is_empty() {
+                return None;
+            }
+            let correct_option = rng.random_range(0..=options.len());
+            options.insert(correct_option, answer);
+            (question, options, correct_option)
+        }
+    };
+
+    Some(QuizQuestion {
+        flashcard_id: card.id,
+        question,
+        options,
+        correct_option,
+    })
+}
+
+// the question and the expected answer of a free text or cloze card
+fn quiz_prompt(card: &FlashCard) -> Option<(String, String)> {
+    match card.kind {
+        CardKind::Cloze => cloze_cards(&card.question)
+            .into_iter()
+            .next()
+            .map(|cloze| (cloze.prompt, cloze.answer)),
+        _ => Some((card.question.clone(), card.answer.clone())),
+    }
+}
+
+// the wrong answers offered alongside the answer of a card, without duplicates; they come from
+// the free text and cloze cards of the corpus
+fn distractors(
+    card: &FlashCard,
+    answer: &str,
+    corpus: &[FlashCard],
+    rng: &mut impl Rng,
+) -> Vec<String> {
+    let card_tags: Vec<String> = card.tags.iter().map(|tag| slugify(tag)).collect();
+    let mut seen = HashSet::from([answer.trim().to_lowercase()]);
+    let mut related = Vec::new();
+    let mut unrelated = Vec::new();
+    for other in corpus.iter().filter(|other| other.id != card.id) {
+        let Some((_, other_answer)) = quiz_prompt(other)
+            .filter(|_| matches!(other.kind, CardKind::FreeText | CardKind::Cloze))
+        else {
+            continue;
+        };
+        if !seen.insert(other_answer.trim().to_lowercase()) {
+            continue;
+        }
+        let is_related = slugify(&other.topic) == slugify(&card.topic)
+            || other
+                .tags
+                .iter()
+                .any(|tag| card_tags.contains(&slugify(tag)));
+        if is_related {
+            related.push(other_answer);
+        } else {
+            unrelated.push(other_answer);
+        }
+    }
+
+    let wanted = QUIZ_OPTIONS - 1;
+    let mut chosen: Vec<String> = related.choose_multiple(rng, wanted).cloned().collect();
+    let missing = wanted - chosen.len();
+    chosen.extend(unrelated.choose_multiple(rng, missing).cloned());
+    chosen
+}
+
+// struct type to represent the answer picked for a quiz question, both zero-based indexes
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizAnswer {
+    pub question: usize,
+    pub option: usize,
+}
+
+// struct type to represent the answers submitted for a quiz
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizSubmission {
+    pub answers: Vec<QuizAnswer>,
+}
+
+// struct type to represent the outcome of a single quiz question
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuestionResult {
+    pub question: usize,
+    pub flashcard_id: Uuid,
+    pub selected_option: Option<usize>,
+    pub correct_option: usize,
+    pub correct: bool,
+}
+
+// struct type to represent the graded quiz; the score is the percentage of correct answers
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuizResult {
+    pub quiz_id: Uuid,
+    pub total: usize,
+    pub correct: usize,
+    pub incorrect: usize,
+    pub unanswered: usize,
+    pub score: f64,
+    pub questions: Vec<QuestionResult>,
+}
+
+/// Edit similarity (between 0 and 1) from which a typed answer is close to the expected one.
+pub const CLOSE_EDIT_SIMILARITY: f64 = 0.8;
+
+/// Share of words in common (between 0 and 1) from which a typed answer is close to the
+/// expected one.
+pub const CLOSE_TOKEN_OVERLAP: f64 = 0.75;
+
+/// Length (in characters, once normalized) up to which answers are compared by edit similarity;
+/// longer ones, up to the thousands of characters a card answer may have, are only compared by
+/// their words in common, as the edit distance grows with the product of both lengths.
+pub const MAX_EDIT_LENGTH: usize = 300;
+
+// enum type to represent how well a typed answer matches the expected one
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerVerdict {
+    Exact,
+    Close,
+    Wrong,
+}
+
+// struct type to represent an answer typed by a learner; `cloze` picks the sub-card of a cloze
+// card, the first one by default
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerAttempt {
+    pub answer: String,
+    #[serde(default)]
+    pub cloze: Option<u32>,
+}
+
+// struct type to represent the verdict on a typed answer, along with the expected answer
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AnswerCheck {
+    pub flashcard_id: Uuid,
+    pub verdict: AnswerVerdict,
+    pub similarity: f64,
+    pub canonical_answer: String,
+}
+
+// methods for the FlashCard type
+impl FlashCard {
+    /// Whether the card is shown on the public API.
+    pub fn is_published(&self) -> bool {
+        self.status == CardStatus::Published
+    }
+
+    /// The answer a learner is expected to type: the correct options of a multiple choice card,
+    /// `true` or `false`, the hidden text of a cloze sub-card, or the answer itself. `None` when
+    /// a cloze card has no sub-card with the given number.
+    pub fn expected_answer(&self, cloze: Option<u32>) -> Option<String> {
+        match &self.kind {
+            CardKind::FreeText => Some(self.answer.clone()),
+            CardKind::MultipleChoice { options, correct } => Some(
+                correct
+                    .iter()
+                    .filter_map(|index| options.get(*index))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            CardKind::TrueFalse { correct } => Some(correct.to_string()),
+            CardKind::Cloze => cloze_cards(&self.question)
+                .into_iter()
+                .find(|card| cloze.is_none_or(|number| card.number == number))
+                .map(|card| card.answer),
+        }
+    }
+
+    /// Grade an answer typed for this card.
+    pub fn check_answer(&self, attempt: &AnswerAttempt) -> Option<AnswerCheck> {
+        let expected = self.expected_answer(attempt.cloze)?;
+        let (verdict, similarity) = grade_answer(&expected, &attempt.answer);
+        Some(AnswerCheck {
+            flashcard_id: self.id,
+            verdict,
+            similarity,
+            canonical_answer: expected,
+        })
+    }
+}
+
+/// Grade a typed answer against the expected one. Both are normalized first: lowercased, with
+/// punctuation which carries no meaning in Rust dropped and whitespace collapsed. Equal
+/// normalized answers are exact; otherwise the answer is close when its edit similarity or
+/// its share of words in common reach their threshold. The similarity returned is the higher
+/// of the two, rounded to two decimals. The edit similarity is left out when either answer is
+/// longer than [`MAX_EDIT_LENGTH`], or when their lengths differ too much for it to reach
+/// its threshold.
+pub fn grade_answer(expected: &str, given: &str) -> (AnswerVerdict, f64) {
+    let expected = normalize_answer(expected);
+    let given = normalize_answer(given);
+    if given.is_empty() {
+        return (AnswerVerdict::Wrong, 0.0);
+    }
+    if expected == given {
+        return (AnswerVerdict::Exact, 1.0);
+    }
+
+    let edit = edit_similarity(&expected, &given).unwrap_or(0.0);
+    let overlap = token_overlap(&expected, &given);
+    let verdict = if edit >= CLOSE_EDIT_SIMILARITY || overlap >= CLOSE_TOKEN_OVERLAP {
+        AnswerVerdict::Close
+    } else {
+        AnswerVerdict::Wrong
+    };
+    (verdict, (edit.max(overlap) * 100.0).round() / 100.0)
+}
+
+// lowercase an answer, keep the characters which matter in Rust snippets (`&str` isn't `str`)
+// and collapse everything else into single spaces
+fn normalize_answer(answer: &str) -> String {
+    answer
+        .to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || "&*_'<>!?[]()".contains(c)))
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// one minus the Levenshtein distance between two strings, relative to the longer one; `None`
+// when either is too long to be compared, or when the distance is at least the difference of
+// their lengths, which already rules out a close answer
+fn edit_similarity(a: &str, b: &str) -> Option<f64> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    let shortest = a.len().min(b.len());
+    if longest == 0 {
+        return Some(1.0);
+    }
+    if longest > MAX_EDIT_LENGTH || (shortest as f64) < longest as f64 * CLOSE_EDIT_SIMILARITY {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Some(1.0 - previous[b.len()] as f64 / longest as f64)
+}
+
+// the Dice coefficient of the words of two strings
+fn token_overlap(a: &str, b: &str) -> f64 {
+    let a: HashSet<&str> = a.split(' ').collect();
+    let b: HashSet<&str> = b.split(' ').collect();
+    let common = a.intersection(&b).count();
+
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+// struct type to represent a draft flash card, generated from documentation and waiting for an
+// editor to review it; `source` is the path of the documented item, e.g. `deck::Pile::shuffle`
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct FlashcardDraft {
+    pub id: Uuid,
+    pub question: String,
+    pub answer: String,
+    pub topic: String,
+    pub tags: Vec<String>,
+    pub difficulty: i32,
+    pub source: String,
+    pub created_at: SqlxTimestamp,
+}
+
+// methods for the FlashcardDraft type
+impl FlashcardDraft {
+    /// The flash card this draft turns into once accepted.
+    pub fn to_new_flashcard(&self) -> NewFlashCard {
+        NewFlashCard {
+            question: self.question.clone(),
+            answer: self.answer.clone(),
+            topic: self.topic.clone(),
+            tags: self.tags.clone(),
+            difficulty: self.difficulty,
+            kind: CardKind::FreeText,
+        }
+    }
+}
+
+// struct type to represent a request to import the rustdoc JSON output of a crate; the path is
+// relative to the configured rustdoc directory, and the topic defaults to the crate name
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RustdocImport {
+    pub path: String,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<i32>,
+}
+
+// enum type to represent the outcome of compile-checking the Rust snippets of a flash card
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeStatus {
+    // every snippet compiled, or failed to compile when marked `compile_fail`
+    Passed,
+    // at least one snippet didn't behave as expected
+    Failed,
+    // the card holds no Rust snippet to check, besides `ignore` ones
+    NoCode,
+}
+
+// methods for the CodeStatus type
+impl CodeStatus {
+    /// The name of the status, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodeStatus::Passed => "passed",
+            CodeStatus::Failed => "failed",
+            CodeStatus::NoCode => "no_code",
+        }
+    }
+}
+
+// implement the TryFrom trait, so that a status can be read back from the database
+impl TryFrom<String> for CodeStatus {
+    type Error = String;
+
+    fn try_from(status: String) -> Result<Self, Self::Error> {
+        match status.as_str() {
+            "passed" => Ok(CodeStatus::Passed),
+            "failed" => Ok(CodeStatus::Failed),
+            "no_code" => Ok(CodeStatus::NoCode),
+            _ => Err(format!("unknown code status: {}", status)),
+        }
+    }
+}
+
+// struct type to represent the last compile-check of the Rust snippets of a flash card; the
+// output holds the compiler messages of the snippets which failed
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct CodeVerification {
+    pub flashcard_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub status: CodeStatus,
+    #[sqlx(try_from = "i32")]
+    pub snippet_count: usize,
+    pub output: String,
+    pub checked_at: SqlxTimestamp,
+}
+
+// struct type to represent a registered learner; the token authenticating them is only kept as a
+// hash, see `identity`
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct User {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: SqlxTimestamp,
+}
+
+// struct type to represent a new learner, coming in as input
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewUser {
+    pub name: String,
+}
+
+// enum type to represent where a community suggestion stands in the moderation queue
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionStatus {
+    // waiting for a moderator
+    #[default]
+    Pending,
+    // applied to the deck
+    Approved,
+    // turned down, with a note from the moderator
+    Rejected,
+}
+
+// methods for the SuggestionStatus type
+impl SuggestionStatus {
+    /// The name of the status, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionStatus::Pending => "pending",
+            SuggestionStatus::Approved => "approved",
+            SuggestionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+// implement the TryFrom trait, so that a status can be read back from the database
+impl TryFrom<String> for SuggestionStatus {
+    type Error = String;
+
+    fn try_from(status: String) -> Result<Self, Self::Error> {
+        match status.as_str() {
+            "pending" => Ok(SuggestionStatus::Pending),
+            "approved" => Ok(SuggestionStatus::Approved),
+            "rejected" => Ok(SuggestionStatus::Rejected),
+            _ => Err(format!("unknown suggestion status: {}", status)),
+        }
+    }
+}
+
+// struct type to represent the change of a single field suggested for a flash card; the current
+// value is null for a new card
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub current: Value,
+    pub proposed: Value,
+}
+
+// struct type to represent a new card, or an edit to an existing one, proposed by a learner; the
+// changes are applied as an update once a moderator approves them, and the diff records what
+// they change on the card as it was when suggested
+#[derive(Clone, Debug, FromRow, PartialEq)]
+pub struct Suggestion {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    // `None` for a new card
+    pub flashcard_id: Option<Uuid>,
+    #[sqlx(json)]
+    pub changes: UpdatedFlashCard,
+    #[sqlx(json)]
+    pub diff: Vec<FieldChange>,
+    #[sqlx(try_from = "String")]
+    pub status: SuggestionStatus,
+    pub note: Option<String>,
+    pub created_at: SqlxTimestamp,
+    pub reviewed_at: Option<SqlxTimestamp>,
+}
+
+// methods for the Suggestion type
+impl Suggestion {
+    /// A suggested new card, validated like any new card.
+    pub fn new_card(user: &User, new: &NewFlashCard) -> Result<Self, ValidationErrors> {
+        let card = FlashCard::try_from(new.clone())?;
+        let changes = UpdatedFlashCard {
+            question: Some(card.question),
+            answer: Some(card.answer),
+            topic: Some(card.topic),
+            tags: Some(card.tags),
+            difficulty: Some(card.difficulty),
+            kind: Some(card.kind),
+        };
+
+        Ok(Self::pending(user, None, changes.diff(None), changes))
+    }
+
+    /// A suggested edit to a card; `EmptySuggestion` if it changes nothing.
+    pub fn edit(
+        user: &User,
+        card: &FlashCard,
+        changes: &UpdatedFlashCard,
+    ) -> Result<Self, ValidationErrors> {
+        validate_updated_flashcard(changes)?;
+        let changes = changes.trimmed();
+        let kind = changes.kind.as_ref().unwrap_or(&card.kind);
+        let question = changes.question.as_deref().unwrap_or(&card.question);
+        validate_card_kind(kind, question)?;
+
+        let diff = changes.diff(Some(card));
+        if diff.is_empty() {
+            return Err(empty_suggestion_error());
+        }
+        Ok(Self::pending(user, Some(card.id), diff, changes))
+    }
+
+    // a suggestion waiting for a moderator
+    fn pending(
+        user: &User,
+        flashcard_id: Option<Uuid>,
+        diff: Vec<FieldChange>,
+        changes: UpdatedFlashCard,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            flashcard_id,
+            changes,
+            diff,
+            status: SuggestionStatus::Pending,
+            note: None,
+            created_at: PavexTimestamp::now().to_sqlx(),
+            reviewed_at: None,
+        }
+    }
+
+    /// The new card this suggestion proposes, `None` for an edit or an incomplete card.
+    pub fn to_new_flashcard(&self) -> Option<NewFlashCard> {
+        if self.flashcard_id.is_some() {
+            return None;
+        }
+        let changes = self.changes.clone();
+        Some(NewFlashCard {
+            question: changes.question?,
+            answer: changes.answer?,
+            topic: changes.topic?,
+            tags: changes.tags?,
+            difficulty: changes.difficulty?,
+            kind: changes.kind.unwrap_or_default(),
+        })
+    }
+
+    /// This suggestion once a moderator has approved or rejected it, with their note.
+    pub fn closed(&self, status: SuggestionStatus, note: Option<&str>) -> Self {
+        Self {
+            status,
+            note: note
+                .map(str::trim)
+                .filter(|note| !note.is_empty())
+                .map(str::to_string),
+            reviewed_at: Some(PavexTimestamp::now().to_sqlx()),
+            ..self.clone()
+        }
+    }
+}
+
+// methods for the UpdatedFlashCard type
+impl UpdatedFlashCard {
+    // the changes with their text trimmed, the way they are stored
+    fn trimmed(&self) -> Self {
+        let trim = |text: &Option<String>| text.as_deref().map(|text| text.trim().to_string());
+        Self {
+            question: trim(&self.question),
+            answer: trim(&self.answer),
+            topic: trim(&self.topic),
+            tags: self
+                .tags
+                .as_ref()
+                .map(|tags| tags.iter().map(|tag| tag.trim().to_string()).collect()),
+            difficulty: self.difficulty,
+            kind: self.kind.as_ref().map(CardKind::trimmed),
+        }
+    }
+
+    // the fields these changes set to a new value on the given card, or on a new card
+    fn diff(&self, card: Option<&FlashCard>) -> Vec<FieldChange> {
+        let current = |value: fn(&FlashCard) -> Value| card.map_or(Value::Null, value);
+        let fields = [
+            (
+                "question",
+                current(|card| Value::from(card.question.as_str())),
+                self.question.as_deref().map(Value::from),
+            ),
+            (
+                "answer",
+                current(|card| Value::from(card.answer.as_str())),
+                self.answer.as_deref().map(Value::from),
+            ),
+            (
+                "topic",
+                current(|card| Value::from(card.topic.as_str())),
+                self.topic.as_deref().map(Value::from),
+            ),
+            (
+                "tags",
+                current(|card| Value::from(card.tags.clone())),
+                self.tags.clone().map(Value::from),
+            ),
+            (
+                "difficulty",
+                current(|card| Value::from(card.difficulty)),
+                self.difficulty.map(Value::from),
+            ),
+            (
+                "kind",
+                current(|card| serde_json::to_value(&card.kind).unwrap_or_default()),
+                self.kind
+                    .as_ref()
+                    .map(|kind| serde_json::to_value(kind).unwrap_or_default()),
+            ),
+        ];
+
+        fields
+            .into_iter()
+            .filter_map(|(field, current, proposed)| {
+                let proposed = proposed.filter(|proposed| *proposed != current)?;
+                Some(FieldChange {
+                    field: field.to_string(),
+                    current,
+                    proposed,
+                })
+            })
+            .collect()
+    }
+}
+
+// enum type to represent the feedback a learner leaves on a card; every kind but an upvote flags
+// the card as needing an editor
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackKind {
+    WrongAnswer,
+    Unclear,
+    Outdated,
+    Duplicate,
+    Upvote,
+}
+
+// methods for the FeedbackKind type
+impl FeedbackKind {
+    /// The name of the kind, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedbackKind::WrongAnswer => "wrong_answer",
+            FeedbackKind::Unclear => "unclear",
+            FeedbackKind::Outdated => "outdated",
+            FeedbackKind::Duplicate => "duplicate",
+            FeedbackKind::Upvote => "upvote",
+        }
+    }
+
+    /// Whether this feedback flags the card as needing an editor, until it is resolved.
+    pub fn is_flag(&self) -> bool {
+        *self != FeedbackKind::Upvote
+    }
+}
+
+// implement the TryFrom trait, so that a kind can be read back from the database
+impl TryFrom<String> for FeedbackKind {
+    type Error = String;
+
+    fn try_from(kind: String) -> Result<Self, Self::Error> {
+        match kind.as_str() {
+            "wrong_answer" => Ok(FeedbackKind::WrongAnswer),
+            "unclear" => Ok(FeedbackKind::Unclear),
+            "outdated" => Ok(FeedbackKind::Outdated),
+            "duplicate" => Ok(FeedbackKind::Duplicate),
+            "upvote" => Ok(FeedbackKind::Upvote),
+            _ => Err(format!("unknown feedback kind: {}", kind)),
+        }
+    }
+}
+
+// struct type to represent feedback on a card, coming in as input
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewFeedback {
+    pub kind: FeedbackKind,
+    pub comment: Option<String>,
+}
+
+// struct type to represent feedback left on a card; the client is a hash identifying who left
+// it, see `identity`, and a flag stays open until an editor resolves it
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct CardFeedback {
+    pub id: Uuid,
+    pub flashcard_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub kind: FeedbackKind,
+    pub comment: Option<String>,
+    pub client: String,
+    pub created_at: SqlxTimestamp,
+    pub resolved_at: Option<SqlxTimestamp>,
+}
+
+// methods for the CardFeedback type
+impl CardFeedback {
+    /// Feedback from the given client on a card, its comment trimmed and dropped if empty.
+    pub fn new(flashcard_id: Uuid, client: &str, new: &NewFeedback) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            flashcard_id,
+            kind: new.kind,
+            comment: new
+                .comment
+                .as_deref()
+                .map(str::trim)
+                .filter(|comment| !comment.is_empty())
+                .map(str::to_string),
+            client: client.to_string(),
+            created_at: PavexTimestamp::now().to_sqlx(),
+            resolved_at: None,
+        }
+    }
+}
+
+// struct type to represent a card in the feedback report, with its number of open flags and
+// upvotes
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct FlaggedCard {
+    pub flashcard_id: Uuid,
+    pub question: String,
+    pub open_flags: i64,
+    pub upvotes: i64,
+}
+
+// enum type to represent how well a learner recalled a card while studying it
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecallGrade {
+    // forgotten
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+// methods for the RecallGrade type
+impl RecallGrade {
+    /// The name of the grade, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecallGrade::Again => "again",
+            RecallGrade::Hard => "hard",
+            RecallGrade::Good => "good",
+            RecallGrade::Easy => "easy",
+        }
+    }
+}
+
+// implement the TryFrom trait, so that a grade can be read back from the database
+impl TryFrom<String> for RecallGrade {
+    type Error = String;
+
+    fn try_from(grade: String) -> Result<Self, Self::Error> {
+        match grade.as_str() {
+            "again" => Ok(RecallGrade::Again),
+            "hard" => Ok(RecallGrade::Hard),
+            "good" => Ok(RecallGrade::Good),
+            "easy" => Ok(RecallGrade::Easy),
+            _ => Err(format!("unknown recall grade: {}", grade)),
+        }
+    }
+}
+
+// struct type to represent a card studied by a learner, coming in as input
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewStudyReview {
+    pub flashcard_id: Uuid,
+    pub grade: RecallGrade,
+}
+
+// struct type to represent a card studied by a learner, the record their progress is derived
+// from, see `progress`
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct StudyReview {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub flashcard_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub grade: RecallGrade,
+    pub reviewed_at: SqlxTimestamp,
+}
+
+// methods for the StudyReview type
+impl StudyReview {
+    /// A card studied by the given user, just now.
+    pub fn new(user: &User, new: &NewStudyReview) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            flashcard_id: new.flashcard_id,
+            grade: new.grade,
+            reviewed_at: PavexTimestamp::now().to_sqlx(),
+        }
+    }
+}
+
+/// Id of the organization owning the content created before organizations existed; requests
+/// which name no other organization are served its content, with the API key of the
+/// configuration.
+pub const DEFAULT_ORGANIZATION_ID: Uuid = Uuid::nil();
+
+// struct type to represent a team sharing the instance, with its own cards, tags, topics,
+// learners and API keys; hosts are the host names it is served at
+#[derive(Clone, Debug, Eq, FromRow, PartialEq)]
+pub struct Organization {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub hosts: Vec<String>,
+    pub created_at: SqlxTimestamp,
+}
+
+// methods for the Organization type
+impl Organization {
+    /// A new organization, its slug derived from its name and its hosts lowercased.
+    pub fn new(new: &NewOrganization) -> Self {
+        let name = new.name.trim();
+        let mut hosts: Vec<String> = new
+            .hosts
+            .iter()
+            .map(|host| host.trim().to_lowercase())
+            .collect();
+        hosts.sort();
+        hosts.dedup();
+        Self {
+            id: Uuid::new_v4(),
+            slug: slugify(name),
+            name: name.to_string(),
+            hosts,
+            created_at: PavexTimestamp::now().to_sqlx(),
+        }
+    }
+}
+
+// struct type to represent a new organization, coming in as input
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewOrganization {
+    pub name: String,
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+// struct type to represent aggregate statistics over the flash cards
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FlashcardStats {
+    pub total: i64,
+    pub topics: Vec<TopicStats>,
+    pub tags: Vec<TagStats>,
+    pub difficulties: Vec<DifficultyStats>,
+    pub weeks: Vec<WeekStats>,
+}
+
+// struct type to represent the number of flash cards filed under a topic, and their average
+// difficulty; `None` when the topic has no cards
+#[derive(Clone, Debug, Deserialize, FromRow, PartialEq, Serialize)]
+pub struct TopicStats {
+    pub slug: String,
+    pub name: String,
+    pub card_count: i64,
+    pub average_difficulty: Option<f64>,
+}
+
+// struct type to represent the number of flash cards carrying a tag
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct TagStats {
+    pub slug: String,
+    pub name: String,
+    pub card_count: i64,
+}
+
+// struct type to represent the number of flash cards with a given difficulty
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct DifficultyStats {
+    pub difficulty: i32,
+    pub card_count: i64,
+}
+
+// struct type to represent the number of flash cards created during a week; `week` is the
+// date of its Monday (UTC), formatted as `YYYY-MM-DD`
+#[derive(Clone, Debug, Deserialize, Eq, FromRow, PartialEq, Serialize)]
+pub struct WeekStats {
+    pub week: String,
+    pub card_count: i64,
+}
+
+// deserialize a present field as `Some`, so that an explicit `null` can be told apart from
+// a missing field
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Arrange a flat list of topics into a tree, children sorted by name.
+///
+/// Topics whose parent is not part of the list are treated as root topics.
+pub fn build_topic_tree(topics: Vec<Topic>) -> Vec<TopicNode> {
+    let slugs: Vec<String> = topics.iter().map(|topic| topic.slug.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<Topic>> = HashMap::new();
+    for topic in topics {
+        let parent = topic.parent.clone().filter(|parent| slugs.contains(parent));
+        children.entry(parent).or_default().push(topic);
+    }
+
+    topic_nodes(None, &mut children)
+}
+
+// build the nodes of the topics under the given parent, removing them from `children`
+fn topic_nodes(
+    parent: Option<String>,
+    children: &mut HashMap<Option<String>, Vec<Topic>>,
+) -> Vec<TopicNode> {
+    let mut topics = children.remove(&parent).unwrap_or_default();
+    topics.sort_by(|a, b| a.name.cmp(&b.name));
+    topics
+        .into_iter()
+        .map(|topic| {
+            let nested = topic_nodes(Some(topic.slug.clone()), children);
+            let total_card_count =
+                topic.card_count + nested.iter().map(|node| node.total_card_count).sum::<i64>();
+            TopicNode {
+                slug: topic.slug,
+                name: topic.name,
+                description: topic.description,
+                card_count: topic.card_count,
+                total_card_count,
+                children: nested,
+            }
+        })
+        .collect()
+}
+
+/// Turn a tag or topic name into its slug: lowercased, with every run of characters other than
+/// letters and digits replaced by a single hyphen, e.g. `Memory Safety` becomes `memory-safety`.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::errors::FlashcardValidationError;
+
+    fn rules(result: Result<FlashCard, ValidationErrors>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .errors()
+            .iter()
+            .map(|e| e.rule.clone())
+            .collect()
+    }
 
     #[test]
     fn valid_flashcard_conversion() {
@@ -98,6 +1602,7 @@ mod tests {
             topic: "intro".to_string(),
             tags: vec!["memory-safe".to_string(), "fast".to_string()],
             difficulty: 3,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
@@ -119,10 +1624,11 @@ mod tests {
             topic: "valid topic".to_string(),
             tags: vec!["valid tag".to_string()],
             difficulty: 1,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
-        assert_eq!(result, Err(FlashcardValidationError::EmptyQuestion));
+        assert_eq!(rules(result), vec!["empty_question"]);
     }
 
     #[test]
@@ -133,10 +1639,11 @@ mod tests {
             topic: "valid topic".to_string(),
             tags: vec!["valid tag".to_string()],
             difficulty: 1,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
-        assert_eq!(result, Err(FlashcardValidationError::EmptyAnswer));
+        assert_eq!(rules(result), vec!["empty_answer"]);
     }
 
     #[test]
@@ -147,10 +1654,11 @@ mod tests {
             topic: "".to_string(),
             tags: vec!["valid tag".to_string()],
             difficulty: 1,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
-        assert_eq!(result, Err(FlashcardValidationError::EmptyTopic));
+        assert_eq!(rules(result), vec!["empty_topic"]);
     }
 
     #[test]
@@ -161,10 +1669,11 @@ mod tests {
             topic: "valid topic".to_string(),
             tags: vec![],
             difficulty: 1,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
-        assert_eq!(result, Err(FlashcardValidationError::EmptyTags));
+        assert_eq!(rules(result), vec!["empty_tags"]);
     }
 
     #[test]
@@ -175,9 +1684,301 @@ mod tests {
             topic: "valid topic".to_string(),
             tags: vec!["valid tag".to_string()],
             difficulty: 99,
+            kind: CardKind::FreeText,
+        };
+
+        let result = FlashCard::try_from(new);
+        assert_eq!(rules(result), vec!["invalid_difficulty"]);
+    }
+
+    #[test]
+    fn all_invalid_fields_are_reported_together() {
+        let new = NewFlashCard {
+            question: "".to_string(),
+            answer: "".to_string(),
+            topic: "".to_string(),
+            tags: vec![],
+            difficulty: 99,
+            kind: CardKind::FreeText,
         };
 
         let result = FlashCard::try_from(new);
-        assert_eq!(result, Err(FlashcardValidationError::InvalidDifficulty));
+        assert_eq!(
+            rules(result),
+            vec![
+                "empty_question",
+                "empty_answer",
+                "empty_topic",
+                "empty_tags",
+                "invalid_difficulty"
+            ]
+        );
+    }
+
+    #[test]
+    fn slugs_are_lowercase_and_hyphenated() {
+        assert_eq!(slugify("Memory Safety"), "memory-safety");
+        assert_eq!(slugify("  async/await  "), "async-await");
+        assert_eq!(slugify("snake_case--names"), "snake-case-names");
+    }
+
+    #[test]
+    fn cards_move_through_the_review_workflow_in_order() {
+        assert!(CardStatus::Draft.can_become(CardStatus::InReview));
+        assert!(CardStatus::InReview.can_become(CardStatus::Published));
+        assert!(CardStatus::InReview.can_become(CardStatus::Draft));
+        assert!(CardStatus::Published.can_become(CardStatus::Archived));
+        assert!(!CardStatus::Draft.can_become(CardStatus::Published));
+        assert!(!CardStatus::Archived.can_become(CardStatus::Published));
+        assert_eq!(
+            CardStatus::try_from("in_review".to_string()),
+            Ok(CardStatus::InReview)
+        );
+    }
+
+    #[test]
+    fn suggested_edits_only_record_the_fields_they_change() {
+        let user = User {
+            id: Uuid::new_v4(),
+            name: "ferris".to_string(),
+            created_at: PavexTimestamp::now().to_sqlx(),
+        };
+        let card = FlashCard::try_from(NewFlashCard {
+            question: "What is Rust?".to_string(),
+            answer: "A systems programming language.".to_string(),
+            topic: "intro".to_string(),
+            tags: vec!["fast".to_string()],
+            difficulty: 3,
+            kind: CardKind::FreeText,
+        })
+        .unwrap();
+        let changes = UpdatedFlashCard {
+            question: Some(" What is Rust? ".to_string()),
+            difficulty: Some(2),
+            ..UpdatedFlashCard::default()
+        };
+
+        let suggestion = Suggestion::edit(&user, &card, &changes).unwrap();
+
+        assert_eq!(
+            suggestion.diff,
+            vec![FieldChange {
+                field: "difficulty".to_string(),
+                current: Value::from(3),
+                proposed: Value::from(2),
+            }]
+        );
+        let unchanged = UpdatedFlashCard {
+            answer: Some(card.answer.clone()),
+            ..UpdatedFlashCard::default()
+        };
+        assert!(Suggestion::edit(&user, &card, &unchanged).is_err());
+    }
+
+    #[test]
+    fn cloze_questions_expand_into_one_sub_card_per_number() {
+        let question =
+            "{{c1::Ownership}} is checked by the {{c2::borrow checker::a compiler pass}}.";
+
+        let cards = cloze_cards(question);
+
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].prompt, "[...] is checked by the borrow checker.");
+        assert_eq!(cards[0].answer, "Ownership");
+        assert_eq!(
+            cards[1].prompt,
+            "Ownership is checked by the [a compiler pass]."
+        );
+        assert_eq!(cards[1].answer, "borrow checker");
+    }
+
+    #[test]
+    fn malformed_cloze_deletions_are_reported() {
+        assert_eq!(parse_cloze("{{c1::open"), Err(ClozeError::Unclosed));
+        assert_eq!(parse_cloze("{{cx::text}}"), Err(ClozeError::MissingNumber));
+        assert_eq!(parse_cloze("{{c1:: }}"), Err(ClozeError::EmptyDeletion));
+    }
+
+    #[test]
+    fn answers_are_graded_exact_close_or_wrong() {
+        let expected = "The borrow checker.";
+
+        assert_eq!(
+            grade_answer(expected, "the  Borrow checker"),
+            (AnswerVerdict::Exact, 1.0)
+        );
+        assert_eq!(
+            grade_answer(expected, "the borow checker").0,
+            AnswerVerdict::Close
+        );
+        assert_eq!(
+            grade_answer(expected, "checker the borrow").0,
+            AnswerVerdict::Close
+        );
+        assert_eq!(
+            grade_answer(expected, "the garbage collector").0,
+            AnswerVerdict::Wrong
+        );
+        assert_eq!(grade_answer(expected, "  ").0, AnswerVerdict::Wrong);
+    }
+
+    #[test]
+    fn rust_punctuation_is_kept_when_normalizing_answers() {
+        assert_eq!(grade_answer("&str", "&str").0, AnswerVerdict::Exact);
+        assert_ne!(grade_answer("&mut T", "T").0, AnswerVerdict::Exact);
+    }
+
+    #[test]
+    fn long_answers_are_graded_by_their_words_in_common() {
+        let words: Vec<String> = (0..500).map(|n| format!("word{n}")).collect();
+        let expected = words.join(" ");
+        let given = words[..499].join(" ");
+
+        assert_eq!(edit_similarity(&expected, &given), None);
+        assert_eq!(grade_answer(&expected, &given), (AnswerVerdict::Close, 1.0));
+        assert_eq!(
+            grade_answer(&expected, &"owner ship ".repeat(500)),
+            (AnswerVerdict::Wrong, 0.0)
+        );
+    }
+
+    fn topic(slug: &str, parent: Option<&str>, card_count: i64) -> Topic {
+        Topic {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            description: None,
+            parent: parent.map(str::to_string),
+            card_count,
+        }
+    }
+
+    #[test]
+    fn topics_are_arranged_into_a_tree() {
+        let topics = vec![
+            topic("lifetimes", Some("borrowing"), 3),
+            topic("traits", None, 1),
+            topic("borrowing", Some("ownership"), 2),
+            topic("ownership", None, 0),
+        ];
+
+        let tree = build_topic_tree(topics);
+
+        let roots: Vec<&str> = tree.iter().map(|node| node.slug.as_str()).collect();
+        assert_eq!(roots, vec!["ownership", "traits"]);
+        let ownership = &tree[0];
+        assert_eq!(ownership.total_card_count, 5);
+        assert_eq!(ownership.children[0].slug, "borrowing");
+        assert_eq!(ownership.children[0].children[0].slug, "lifetimes");
+    }
+
+    #[test]
+    fn topics_with_an_unknown_parent_are_roots() {
+        let tree = build_topic_tree(vec![topic("borrowing", Some("missing"), 1)]);
+
+        assert_eq!(tree.len(), 1);
+        assert!(tree[0].children.is_empty());
+    }
+
+    #[test]
+    fn an_explicit_null_parent_is_told_apart_from_a_missing_one() {
+        let detach: UpdatedTopic = serde_json::from_str(r#"{"parent": null}"#).unwrap();
+        let untouched: UpdatedTopic = serde_json::from_str(r#"{"name": "Ownership"}"#).unwrap();
+
+        assert_eq!(detach.parent, Some(None));
+        assert_eq!(untouched.parent, None);
+    }
+
+    #[test]
+    fn the_card_of_the_day_is_stable_and_avoids_recent_picks() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let selection = DailySelection {
+            day: Date::new(2026, 10, 19).unwrap(),
+            topic: None,
+            repeat_window: 30,
+        };
+
+        let pick = selection.pick(&ids, &[]).unwrap();
+        let mut shuffled = ids.clone();
+        shuffled.reverse();
+
+        assert_eq!(selection.pick(&shuffled, &[]), Some(pick));
+        assert_ne!(selection.pick(&ids, &[pick]), Some(pick));
+        assert_eq!(selection.pick(&ids, &ids), Some(pick));
+        assert_eq!(selection.pick(&[], &[]), None);
+        assert_eq!(selection.window_start(), "2026-09-19");
+    }
+
+    #[test]
+    fn quiz_questions_mix_the_answer_with_related_distractors() {
+        let card = |question: &str, answer: &str, topic: &str| {
+            FlashCard::try_from(NewFlashCard {
+                question: question.to_string(),
+                answer: answer.to_string(),
+                topic: topic.to_string(),
+                tags: vec![topic.to_string()],
+                difficulty: 1,
+                kind: CardKind::FreeText,
+            })
+            .unwrap()
+        };
+        let corpus = vec![
+            card("What is ownership?", "Single owner", "memory"),
+            card("What is borrowing?", "A reference", "memory"),
+            card("What is a lifetime?", "A scope", "memory"),
+            card("What is a box?", "A heap pointer", "memory"),
+            card("What is moving?", "A reference", "memory"),
+            card("What is a trait?", "An interface", "traits"),
+        ];
+
+        let quiz = Quiz::build(None, None, &corpus[..1], &corpus, &mut rand::rng());
+
+        let question = &quiz.questions[0];
+        assert_eq!(question.options.len(), QUIZ_OPTIONS);
+        assert_eq!(question.options[question.correct_option], "Single owner");
+        assert!(!question.options.contains(&"An interface".to_string()));
+        let distinct: HashSet<&String> = question.options.iter().collect();
+        assert_eq!(distinct.len(), QUIZ_OPTIONS);
+    }
+
+    #[test]
+    fn quiz_grading_counts_correct_incorrect_and_unanswered_questions() {
+        let question = |correct_option| QuizQuestion {
+            flashcard_id: Uuid::new_v4(),
+            question: "A question?".to_string(),
+            options: vec!["a".to_string(), "b".to_string()],
+            correct_option,
+        };
+        let quiz = Quiz {
+            id: Uuid::new_v4(),
+            topic: None,
+            tag: None,
+            questions: vec![question(0), question(1), question(0), question(1)],
+            created_at: PavexTimestamp::now().to_sqlx(),
+        };
+        let submission = QuizSubmission {
+            answers: vec![
+                QuizAnswer {
+                    question: 0,
+                    option: 0,
+                },
+                QuizAnswer {
+                    question: 1,
+                    option: 0,
+                },
+                QuizAnswer {
+                    question: 3,
+                    option: 1,
+                },
+            ],
+        };
+
+        let result = quiz.grade(&submission);
+
+        assert_eq!(
+            (result.correct, result.incorrect, result.unanswered),
+            (2, 1, 1)
+        );
+        assert_eq!(result.score, 50.0);
+        assert_eq!(result.questions[2].selected_option, None);
     }
 }
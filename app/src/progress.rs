@@ -0,0 +1,320 @@
+// app/src/progress.rs
+
+// the study progress of a learner, derived from the cards they studied: every card gets a
+// memory stability in days, which a successful recall multiplies and a lapse resets, and the
+// chance of still recalling it decays along an exponential forgetting curve from the last review
+
+// dependencies
+use crate::models::{FlashCard, RecallGrade, StudyReview};
+use pavex::time::civil::Date;
+use pavex::time::tz::TimeZone;
+use pavex::time::{Span, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// Number of days covered by the review history, today included.
+pub const HISTORY_DAYS: i64 = 90;
+
+/// Stability, in days, from which a card counts as learned rather than still being learned.
+pub const LEARNED_STABILITY: f64 = 21.0;
+
+// number of seconds in a day, to express elapsed times in days
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+// struct type to represent the progress of a learner; mastery and retention are percentages
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Progress {
+    pub topics: Vec<TopicMastery>,
+    pub cards: CardCounts,
+    pub streak: Streak,
+    pub daily_reviews: Vec<DailyReviews>,
+    // `None` until a card has been studied
+    pub predicted_retention: Option<f64>,
+}
+
+// struct type to represent the mastery of a topic: the chance of recalling its cards right now,
+// on average, with the cards never studied counting as forgotten
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TopicMastery {
+    pub topic: String,
+    pub card_count: usize,
+    pub mastery: f64,
+}
+
+// struct type to represent the number of cards in each stage of learning
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CardCounts {
+    pub learned: usize,
+    pub learning: usize,
+    pub new: usize,
+}
+
+// struct type to represent the runs of consecutive days with at least one review; the current
+// one is still going if the last review was yesterday
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+// struct type to represent the number of reviews on a given day, formatted as `YYYY-MM-DD`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DailyReviews {
+    pub day: String,
+    pub reviews: usize,
+}
+
+// struct type to represent what a learner remembers of a card
+struct Memory {
+    stability: f64,
+    last_review: Timestamp,
+}
+
+// methods for the Memory type
+impl Memory {
+    // the memory left by the reviews of a card, oldest first; `None` if it was never studied
+    fn of(reviews: &[&StudyReview]) -> Option<Self> {
+        let mut memory: Option<Memory> = None;
+        for review in reviews {
+            let stability = match (memory.as_ref(), review.grade) {
+                (_, RecallGrade::Again) => 0.2,
+                (None, RecallGrade::Hard) => 1.0,
+                (None, RecallGrade::Good) => 2.0,
+                (None, RecallGrade::Easy) => 4.0,
+                (Some(memory), RecallGrade::Hard) => memory.stability * 1.2,
+                (Some(memory), RecallGrade::Good) => memory.stability * 2.5,
+                (Some(memory), RecallGrade::Easy) => memory.stability * 4.0,
+            };
+            memory = Some(Memory {
+                stability,
+                last_review: review.reviewed_at.to_jiff(),
+            });
+        }
+        memory
+    }
+
+    // the chance of recalling the card at the given time, between 0 and 1
+    fn retention(&self, now: Timestamp) -> f64 {
+        let elapsed = now.duration_since(self.last_review).as_secs_f64() / SECONDS_PER_DAY;
+        (-elapsed.max(0.0) / self.stability).exp()
+    }
+}
+
+/// The progress of a learner on the given cards, from their reviews, oldest first; reviews of
+/// other cards only count towards the streak and the daily history. Days follow the given
+/// time zone.
+pub fn progress(
+    cards: &[FlashCard],
+    reviews: &[StudyReview],
+    now: Timestamp,
+    time_zone: &TimeZone,
+) -> Progress {
+    let mut reviews_by_card: HashMap<Uuid, Vec<&StudyReview>> = HashMap::new();
+    for review in reviews {
+        reviews_by_card
+            .entry(review.flashcard_id)
+            .or_default()
+            .push(review);
+    }
+
+    let mut counts = CardCounts::default();
+    let mut retentions: Vec<f64> = Vec::new();
+    // the retention of every card of a topic, sorted by topic
+    let mut topics: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for card in cards {
+        let memory = reviews_by_card
+            .get(&card.id)
+            .and_then(|reviews| Memory::of(reviews));
+        let retention = match &memory {
+            Some(memory) if memory.stability >= LEARNED_STABILITY => {
+                counts.learned += 1;
+                memory.retention(now)
+            }
+            Some(memory) => {
+                counts.learning += 1;
+                memory.retention(now)
+            }
+            None => {
+                counts.new += 1;
+                0.0
+            }
+        };
+        if memory.is_some() {
+            retentions.push(retention);
+        }
+        topics
+            .entry(card.topic.as_str())
+            .or_default()
+            .push(retention);
+    }
+
+    let day = |timestamp: Timestamp| timestamp.to_zoned(time_zone.clone()).date();
+    let today = day(now);
+    let mut reviews_by_day: BTreeMap<Date, usize> = BTreeMap::new();
+    for review in reviews {
+        *reviews_by_day
+            .entry(day(review.reviewed_at.to_jiff()))
+            .or_default() += 1;
+    }
+
+    Progress {
+        topics: topics
+            .into_iter()
+            .map(|(topic, retentions)| TopicMastery {
+                topic: topic.to_string(),
+                card_count: retentions.len(),
+                mastery: percentage(average(&retentions)),
+            })
+            .collect(),
+        cards: counts,
+        streak: streak(&reviews_by_day.keys().copied().collect(), today),
+        daily_reviews: (0..HISTORY_DAYS)
+            .rev()
+            .filter_map(|days_ago| today.checked_sub(Span::new().days(days_ago)).ok())
+            .map(|day| DailyReviews {
+                day: day.to_string(),
+                reviews: reviews_by_day.get(&day).copied().unwrap_or_default(),
+            })
+            .collect(),
+        predicted_retention: (!retentions.is_empty()).then(|| percentage(average(&retentions))),
+    }
+}
+
+// the longest and current runs of consecutive days among the given days
+fn streak(days: &BTreeSet<Date>, today: Date) -> Streak {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<Date> = None;
+    for day in days {
+        let follows = previous.and_then(|previous| previous.tomorrow().ok()) == Some(*day);
+        run = if follows { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    // a streak isn't broken until a whole day passes without a review
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.yesterday().ok()
+    };
+    while let Some(reviewed) = day.filter(|day| days.contains(day)) {
+        current += 1;
+        day = reviewed.yesterday().ok();
+    }
+
+    Streak { current, longest }
+}
+
+// the average of some values, 0 if there are none
+fn average(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// a ratio between 0 and 1 as a percentage, rounded to one decimal
+fn percentage(ratio: f64) -> f64 {
+    (ratio * 1_000.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CardKind, CardStatus, NewFlashCard};
+    use jiff_sqlx::ToSqlx;
+    use pavex::time::SignedDuration;
+
+    fn card(question: &str, topic: &str) -> FlashCard {
+        FlashCard {
+            status: CardStatus::Published,
+            ..FlashCard::try_from(NewFlashCard {
+                question: question.to_string(),
+                answer: "an answer".to_string(),
+                topic: topic.to_string(),
+                tags: vec!["basics".to_string()],
+                difficulty: 1,
+                kind: CardKind::FreeText,
+            })
+            .unwrap()
+        }
+    }
+
+    fn review(card: &FlashCard, grade: RecallGrade, reviewed_at: Timestamp) -> StudyReview {
+        StudyReview {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            flashcard_id: card.id,
+            grade,
+            reviewed_at: reviewed_at.to_sqlx(),
+        }
+    }
+
+    fn days_ago(now: Timestamp, days: i64) -> Timestamp {
+        now - SignedDuration::from_hours(24 * days)
+    }
+
+    #[test]
+    fn cards_are_learned_once_recalled_well_enough() {
+        let now: Timestamp = "2026-10-19T12:00:00Z".parse().unwrap();
+        let learned = card("What is ownership?", "memory");
+        let learning = card("What is borrowing?", "memory");
+        let new = card("What is a trait?", "traits");
+        let reviews = vec![
+            review(&learned, RecallGrade::Good, days_ago(now, 3)),
+            review(&learning, RecallGrade::Good, days_ago(now, 3)),
+            review(&learned, RecallGrade::Easy, days_ago(now, 2)),
+            review(&learning, RecallGrade::Again, days_ago(now, 2)),
+            review(&learned, RecallGrade::Easy, days_ago(now, 1)),
+        ];
+
+        let progress = progress(&[learned, learning, new], &reviews, now, &TimeZone::UTC);
+
+        assert_eq!(
+            progress.cards,
+            CardCounts {
+                learned: 1,
+                learning: 1,
+                new: 1,
+            }
+        );
+        let topics: Vec<(&str, usize)> = progress
+            .topics
+            .iter()
+            .map(|topic| (topic.topic.as_str(), topic.card_count))
+            .collect();
+        assert_eq!(topics, vec![("memory", 2), ("traits", 1)]);
+        assert_eq!(progress.topics[1].mastery, 0.0);
+        // the learned card, reviewed a day ago with a stability of 32 days, is still well
+        // known; the lapsed one is mostly forgotten
+        let retention = progress.predicted_retention.unwrap();
+        assert!(retention > 45.0 && retention < 50.0, "{}", retention);
+    }
+
+    #[test]
+    fn streaks_survive_until_a_whole_day_is_missed() {
+        let now: Timestamp = "2026-10-19T08:00:00Z".parse().unwrap();
+        let studied = card("What is ownership?", "memory");
+        let reviews: Vec<StudyReview> = [9, 8, 7, 3, 2, 1]
+            .into_iter()
+            .map(|days| review(&studied, RecallGrade::Good, days_ago(now, days)))
+            .collect();
+
+        let progress = progress(&[studied], &reviews, now, &TimeZone::UTC);
+
+        assert_eq!(
+            progress.streak,
+            Streak {
+                current: 3,
+                longest: 3
+            }
+        );
+        assert_eq!(progress.daily_reviews.len(), HISTORY_DAYS as usize);
+        let last = progress.daily_reviews.last().unwrap();
+        assert_eq!((last.day.as_str(), last.reviews), ("2026-10-19", 0));
+        assert_eq!(progress.daily_reviews[HISTORY_DAYS as usize - 2].reviews, 1);
+    }
+}
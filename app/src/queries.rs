@@ -1,152 +1,1557 @@
 // app/src/queries.rs
 
+// every query is scoped to the organization given as its first parameter; the rows attached to
+// a card, a quiz or a user are reached through it
+
 // dependencies
-use crate::{UpdatedFlashCard, models::FlashCard};
-use jiff_sqlx::ToSqlx;
+use crate::UpdatedFlashCard;
+use crate::models::{
+    CardFeedback, CardReview, CardStatus, CodeVerification, DailySelection, DifficultyStats,
+    FlaggedCard, FlashCard, FlashcardDraft, FlashcardStats, NewTopic, Organization, Quiz,
+    QuizQuestion, RandomSelection, StudyReview, Suggestion, SuggestionStatus, Tag, TagStats, Topic,
+    TopicStats, UpdatedTag, UpdatedTopic, User, WeekStats, slugify,
+};
+use crate::validation::MAX_DIFFICULTY;
+use jiff_sqlx::{Timestamp as SqlxTimestamp, ToSqlx};
 use pavex::time::Timestamp as PavexTimestamp;
-use sqlx::PgPool;
+use sqlx::types::Json;
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-// function which queries the database and returns all the flash cards
-pub async fn list_flashcards(pool: PgPool) -> Result<Vec<FlashCard>, sqlx::Error> {
-    let flash_cards: Vec<FlashCard> =
-        sqlx::query_as("SELECT * FROM flashcards ORDER BY created_at DESC;")
-            .fetch_all(&pool)
-            .await?;
+// the columns of a flash card, with its topic name and tag names gathered from their tables
+const SELECT_FLASHCARDS: &str = "SELECT f.id, f.question, f.answer, COALESCE(tp.name, '') AS topic,
+    ARRAY(SELECT t.name
+        FROM flashcard_tags ft
+        JOIN tags t ON t.id = ft.tag_id
+        WHERE ft.flashcard_id = f.id
+        ORDER BY ft.position) AS tags,
+    f.difficulty, f.kind, f.status, f.created_at, f.updated_at
+    FROM flashcards f
+    LEFT JOIN topics tp ON tp.id = f.topic_id";
+
+// the condition met by the flash cards shown on the public API
+const PUBLISHED: &str = "f.status = 'published'";
+
+// the condition met by the flash card of the organization $1 with the id $2
+const BY_ID: &str = "WHERE f.organization_id = $1 AND f.id = $2";
+
+// the id $2, provided it is the id of a flash card of the organization $1; scopes the rows
+// attached to a card
+const CARD_ID: &str = "SELECT id FROM flashcards WHERE organization_id = $1 AND id = $2";
+
+// the columns of a tag, with the number of published flash cards carrying it
+const SELECT_TAGS: &str =
+    "SELECT t.slug, t.name, t.description, COUNT(ft.flashcard_id) AS card_count
+    FROM tags t
+    LEFT JOIN (SELECT ft.tag_id, ft.flashcard_id
+        FROM flashcard_tags ft
+        JOIN flashcards f ON f.id = ft.flashcard_id
+        WHERE f.status = 'published') ft ON ft.tag_id = t.id";
+
+// the columns of a topic, with the slug of its parent and the number of published flash cards
+// filed under it
+const SELECT_TOPICS: &str =
+    "SELECT t.slug, t.name, t.description, p.slug AS parent, COUNT(f.id) AS card_count
+    FROM topics t
+    LEFT JOIN topics p ON p.id = t.parent_id
+    LEFT JOIN flashcards f ON f.topic_id = t.id AND f.status = 'published'";
+
+// the columns of a review of a flash card
+const SELECT_REVIEWS: &str =
+    "SELECT id, flashcard_id, from_status, to_status, note, reviewed_at FROM flashcard_reviews";
+
+// the ids of the topic of the organization $1 matching $2 and of all its descendants
+const TOPIC_SUBTREE: &str = "WITH RECURSIVE subtree AS (
+        SELECT id FROM topics WHERE organization_id = $1 AND slug = $2
+        UNION
+        SELECT t.id FROM topics t JOIN subtree s ON t.parent_id = s.id
+    )
+    SELECT id FROM subtree";
+
+// function which queries the database and returns all the published flash cards
+pub async fn list_flashcards(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<Vec<FlashCard>, sqlx::Error> {
+    let flash_cards: Vec<FlashCard> = sqlx::query_as(&format!(
+        "{} WHERE f.organization_id = $1 AND {} ORDER BY f.created_at DESC;",
+        SELECT_FLASHCARDS, PUBLISHED
+    ))
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
 
     Ok(flash_cards)
 }
 
-// function which queries the database and returns all the flash cards filtered by topic
+// function which queries the database and returns every flash card whatever its status, or
+// only those in the given status, for the review workflow
+pub async fn list_all_flashcards(
+    pool: &PgPool,
+    organization_id: Uuid,
+    status: Option<CardStatus>,
+) -> Result<Vec<FlashCard>, sqlx::Error> {
+    let flash_cards: Vec<FlashCard> = sqlx::query_as(&format!(
+        "{} WHERE f.organization_id = $1 AND ($2::TEXT IS NULL OR f.status = $2)
+        ORDER BY f.created_at DESC;",
+        SELECT_FLASHCARDS
+    ))
+    .bind(organization_id)
+    .bind(status.map(|status| status.as_str()))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(flash_cards)
+}
+
+// function which queries the database and returns all the published flash cards filtered by topic; the
+// topic is matched on its slug, and the flash cards filed under its descendants are included
+// on request
 pub async fn list_flashcards_by_topic(
-    pool: PgPool,
+    pool: &PgPool,
+    organization_id: Uuid,
     topic: &str,
+    include_descendants: bool,
 ) -> Result<Vec<FlashCard>, sqlx::Error> {
-    let flash_cards: Vec<FlashCard> = sqlx::query_as(
-        "SELECT *
-            FROM flashcards
-            WHERE topic ILIKE $1
-            ORDER BY created_at DESC;",
-    )
-    .bind(topic)
-    .fetch_all(&pool)
+    let filter = if include_descendants {
+        format!(
+            "WHERE f.organization_id = $1 AND f.topic_id IN ({}) AND {}",
+            TOPIC_SUBTREE, PUBLISHED
+        )
+    } else {
+        format!(
+            "WHERE f.organization_id = $1 AND tp.slug = $2 AND {}",
+            PUBLISHED
+        )
+    };
+    let flash_cards: Vec<FlashCard> = sqlx::query_as(&format!(
+        "{}
+            {}
+            ORDER BY f.created_at DESC;",
+        SELECT_FLASHCARDS, filter
+    ))
+    .bind(organization_id)
+    .bind(slugify(topic))
+    .fetch_all(pool)
     .await?;
 
     Ok(flash_cards)
 }
 
-// function which queries the database and returns all the flash cards filtered by tag
+// function which queries the database and returns all the published flash cards filtered by tag;
+// the tag is matched on its slug, so `Memory Safety` and `memory-safety` are the same tag
 pub async fn list_flashcards_by_tag(
-    pool: PgPool,
+    pool: &PgPool,
+    organization_id: Uuid,
     tag: &str,
 ) -> Result<Vec<FlashCard>, sqlx::Error> {
-    let flash_cards: Vec<FlashCard> = sqlx::query_as(
-        "SELECT *
-            FROM flashcards
-            WHERE $1 = ANY(tags)
-            ORDER BY created_at DESC;",
-    )
-    .bind(tag)
-    .fetch_all(&pool)
+    let flash_cards: Vec<FlashCard> = sqlx::query_as(&format!(
+        "{}
+            WHERE f.organization_id = $1 AND {} AND EXISTS (
+                SELECT 1
+                FROM flashcard_tags ft
+                JOIN tags t ON t.id = ft.tag_id
+                WHERE ft.flashcard_id = f.id AND t.slug = $2
+            )
+            ORDER BY f.created_at DESC;",
+        SELECT_FLASHCARDS, PUBLISHED
+    ))
+    .bind(organization_id)
+    .bind(slugify(tag))
+    .fetch_all(pool)
     .await?;
 
     Ok(flash_cards)
 }
 
-// function which queries the database and returns a single flash card give an id
-pub async fn list_flashcard(pool: PgPool, id: Uuid) -> Result<FlashCard, sqlx::Error> {
-    let flash_card = sqlx::query_as("SELECT * FROM flashcards WHERE id = $1;")
+// function which queries the database and returns a single published flash card give an id
+pub async fn list_flashcard(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<FlashCard, sqlx::Error> {
+    let flash_card = sqlx::query_as(&format!(
+        "{} WHERE f.organization_id = $1 AND f.id = $2 AND {};",
+        SELECT_FLASHCARDS, PUBLISHED
+    ))
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(flash_card)
+}
+
+// function which queries the database and returns a single flash card whatever its status,
+// given an id
+pub async fn get_flashcard_any_status(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<FlashCard, sqlx::Error> {
+    let flash_card = sqlx::query_as(&format!("{} {};", SELECT_FLASHCARDS, BY_ID))
+        .bind(organization_id)
         .bind(id)
-        .fetch_one(&pool)
+        .fetch_one(pool)
         .await?;
 
     Ok(flash_card)
 }
 
-// function which queries the database and returns a list of available tags
-pub async fn list_tags(pool: PgPool) -> Result<Vec<String>, sqlx::Error> {
-    let tags: Vec<String> = sqlx::query_scalar(
-        "SELECT DISTINCT UNNEST(tags) AS tag
-FROM flashcards
-WHERE tags IS NOT NULL
-ORDER BY tag ASC;",
-    )
-    .fetch_all(&pool)
+// function which queries the database and returns the list of tags, with their usage counts
+pub async fn list_tags(pool: &PgPool, organization_id: Uuid) -> Result<Vec<Tag>, sqlx::Error> {
+    let tags: Vec<Tag> = sqlx::query_as(&format!(
+        "{}
+            WHERE t.organization_id = $1
+            GROUP BY t.id
+            ORDER BY t.slug ASC;",
+        SELECT_TAGS
+    ))
+    .bind(organization_id)
+    .fetch_all(pool)
     .await?;
 
     Ok(tags)
 }
 
-// function which queries the database and returns a list of available topics
-pub async fn list_topics(pool: PgPool) -> Result<Vec<String>, sqlx::Error> {
+// function which queries the database and returns a single tag, given its slug
+pub async fn get_tag(pool: &PgPool, organization_id: Uuid, slug: &str) -> Result<Tag, sqlx::Error> {
+    let tag: Tag = sqlx::query_as(&format!(
+        "{}
+            WHERE t.organization_id = $1 AND t.slug = $2
+            GROUP BY t.id;",
+        SELECT_TAGS
+    ))
+    .bind(organization_id)
+    .bind(slug)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(tag)
+}
+
+// function which queries the database and returns a list of the topics in use by published cards
+pub async fn list_topics(pool: &PgPool, organization_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
     let topics: Vec<String> = sqlx::query_scalar(
-        "SELECT DISTINCT topic
+        "SELECT DISTINCT tp.name
+        FROM flashcards f
+        JOIN topics tp ON tp.id = f.topic_id
+        WHERE f.organization_id = $1 AND f.status = 'published'
+        ORDER BY tp.name ASC",
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(topics)
+}
+
+// function which queries the database and returns aggregate statistics over the published flash
+// cards, each computed by a grouped query
+pub async fn flashcard_stats(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<FlashcardStats, sqlx::Error> {
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM flashcards WHERE organization_id = $1 AND status = 'published';",
+    )
+    .bind(organization_id)
+    .fetch_one(pool)
+    .await?;
+
+    let topics: Vec<TopicStats> = sqlx::query_as(
+        "SELECT t.slug, t.name, COUNT(f.id) AS card_count, AVG(f.difficulty)::FLOAT8 AS average_difficulty
+        FROM topics t
+        LEFT JOIN flashcards f ON f.topic_id = t.id AND f.status = 'published'
+        WHERE t.organization_id = $1
+        GROUP BY t.id
+        ORDER BY card_count DESC, t.slug ASC;",
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
+
+    let tags: Vec<TagStats> = sqlx::query_as(&format!(
+        "SELECT slug, name, card_count FROM ({}
+            WHERE t.organization_id = $1
+            GROUP BY t.id) tag_counts
+        ORDER BY card_count DESC, slug ASC;",
+        SELECT_TAGS
+    ))
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
+
+    let difficulties: Vec<DifficultyStats> = sqlx::query_as(
+        "SELECT difficulty, COUNT(*) AS card_count
         FROM flashcards
-        ORDER BY topic ASC",
+        WHERE organization_id = $1 AND difficulty IS NOT NULL AND status = 'published'
+        GROUP BY difficulty
+        ORDER BY difficulty ASC;",
     )
-    .fetch_all(&pool)
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
+
+    let weeks: Vec<WeekStats> = sqlx::query_as(
+        "SELECT to_char(date_trunc('week', created_at AT TIME ZONE 'UTC'), 'YYYY-MM-DD') AS week,
+            COUNT(*) AS card_count
+        FROM flashcards
+        WHERE organization_id = $1 AND status = 'published'
+        GROUP BY week
+        ORDER BY week ASC;",
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(FlashcardStats {
+        total,
+        topics,
+        tags,
+        difficulties,
+        weeks,
+    })
+}
+
+// function which queries the database and returns every topic, with its parent and usage count
+pub async fn list_topic_details(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<Vec<Topic>, sqlx::Error> {
+    let topics: Vec<Topic> = sqlx::query_as(&format!(
+        "{}
+            WHERE t.organization_id = $1
+            GROUP BY t.id, p.slug
+            ORDER BY t.slug ASC;",
+        SELECT_TOPICS
+    ))
+    .bind(organization_id)
+    .fetch_all(pool)
     .await?;
 
     Ok(topics)
 }
 
+// function which queries the database and returns a single topic, given its slug
+pub async fn get_topic(
+    pool: &PgPool,
+    organization_id: Uuid,
+    slug: &str,
+) -> Result<Topic, sqlx::Error> {
+    let topic: Topic = sqlx::query_as(&format!(
+        "{}
+            WHERE t.organization_id = $1 AND t.slug = $2
+            GROUP BY t.id, p.slug;",
+        SELECT_TOPICS
+    ))
+    .bind(organization_id)
+    .bind(slug)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(topic)
+}
+
+// function which returns whether the topic `slug` is the topic `ancestor` or one of its descendants
+async fn topic_is_within(
+    transaction: &mut Transaction<'_, Postgres>,
+    organization_id: Uuid,
+    slug: &str,
+    ancestor: &str,
+) -> Result<bool, sqlx::Error> {
+    let within: bool = sqlx::query_scalar(&format!(
+        "SELECT EXISTS (
+            SELECT 1 FROM topics WHERE organization_id = $1 AND slug = $3 AND id IN ({})
+        );",
+        TOPIC_SUBTREE
+    ))
+    .bind(organization_id)
+    .bind(ancestor)
+    .bind(slug)
+    .fetch_one(&mut **transaction)
+    .await?;
+
+    Ok(within)
+}
+
+// function which returns the id of a topic given its slug, `None` if no slug is given
+async fn find_topic_id(
+    executor: impl PgExecutor<'_>,
+    organization_id: Uuid,
+    slug: Option<&str>,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    match slug {
+        Some(slug) => {
+            sqlx::query_scalar("SELECT id FROM topics WHERE organization_id = $1 AND slug = $2;")
+                .bind(organization_id)
+                .bind(slug)
+                .fetch_one(executor)
+                .await
+                .map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+// function which creates a topic, nested under its parent if it has one
+pub async fn create_topic(
+    pool: &PgPool,
+    organization_id: Uuid,
+    new_topic: &NewTopic,
+) -> Result<Topic, sqlx::Error> {
+    let parent = new_topic.parent.as_deref().map(slugify);
+    let parent_id = find_topic_id(pool, organization_id, parent.as_deref()).await?;
+    let name = new_topic.name.trim();
+    let slug: String = sqlx::query_scalar(
+        "INSERT INTO topics (organization_id, id, slug, name, description, parent_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING slug;",
+    )
+    .bind(organization_id)
+    .bind(Uuid::new_v4())
+    .bind(slugify(name))
+    .bind(name)
+    .bind(&new_topic.description)
+    .bind(parent_id)
+    .fetch_one(pool)
+    .await?;
+
+    get_topic(pool, organization_id, &slug).await
+}
+
+// function which renames, describes and/or moves a topic, given its slug; `None` if the topic
+// would be moved under itself or one of its descendants. The topics of the organization stay
+// locked from the check to the move, lest two concurrent moves form a cycle together
+pub async fn update_topic(
+    pool: &PgPool,
+    organization_id: Uuid,
+    slug: &str,
+    updated_topic: &UpdatedTopic,
+) -> Result<Option<Topic>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    let parent = match &updated_topic.parent {
+        Some(parent) => {
+            let parent = parent.as_deref().map(slugify);
+            if let Some(parent) = &parent {
+                sqlx::query("SELECT id FROM topics WHERE organization_id = $1 FOR NO KEY UPDATE;")
+                    .bind(organization_id)
+                    .execute(&mut *transaction)
+                    .await?;
+                if topic_is_within(&mut transaction, organization_id, parent, slug).await? {
+                    return Ok(None);
+                }
+            }
+            Some(find_topic_id(&mut *transaction, organization_id, parent.as_deref()).await?)
+        }
+        None => None,
+    };
+    let name = updated_topic.name.as_deref().map(str::trim);
+    let new_slug: String = sqlx::query_scalar(
+        "UPDATE topics
+        SET name = COALESCE($2, name), slug = COALESCE($3, slug), description = COALESCE($4, description),
+            parent_id = CASE WHEN $5 THEN $6 ELSE parent_id END
+        WHERE organization_id = $1 AND slug = $7
+        RETURNING slug;",
+    )
+    .bind(organization_id)
+    .bind(name)
+    .bind(name.map(slugify))
+    .bind(&updated_topic.description)
+    .bind(parent.is_some())
+    .bind(parent.flatten())
+    .bind(slug)
+    .fetch_one(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    get_topic(pool, organization_id, &new_slug).await.map(Some)
+}
+
+// function which returns the id of the topic with the given name, creating it as a root topic
+// if it doesn't exist yet
+async fn upsert_topic(
+    transaction: &mut Transaction<'_, Postgres>,
+    organization_id: Uuid,
+    name: &str,
+) -> Result<Uuid, sqlx::Error> {
+    let name = name.trim();
+    sqlx::query(
+        "INSERT INTO topics (organization_id, id, slug, name) VALUES ($1, $2, $3, $4)
+        ON CONFLICT (organization_id, slug) DO NOTHING;",
+    )
+    .bind(organization_id)
+    .bind(Uuid::new_v4())
+    .bind(slugify(name))
+    .bind(name)
+    .execute(&mut **transaction)
+    .await?;
+
+    sqlx::query_scalar("SELECT id FROM topics WHERE organization_id = $1 AND slug = $2;")
+        .bind(organization_id)
+        .bind(slugify(name))
+        .fetch_one(&mut **transaction)
+        .await
+}
+
+// function which links a flash card to the given tags, creating the tags which don't exist yet;
+// the previous tags of the flash card are unlinked
+async fn set_flashcard_tags(
+    transaction: &mut Transaction<'_, Postgres>,
+    organization_id: Uuid,
+    id: Uuid,
+    tags: &[String],
+) -> Result<(), sqlx::Error> {
+    let names: Vec<String> = tags.iter().map(|tag| tag.trim().to_string()).collect();
+    let slugs: Vec<String> = names.iter().map(|name| slugify(name)).collect();
+
+    sqlx::query("DELETE FROM flashcard_tags WHERE flashcard_id = $1;")
+        .bind(id)
+        .execute(&mut **transaction)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO tags (organization_id, id, slug, name)
+        SELECT $1, gen_random_uuid(), slug, name
+        FROM UNNEST($2::TEXT[], $3::TEXT[]) AS new_tags(slug, name)
+        ON CONFLICT (organization_id, slug) DO NOTHING;",
+    )
+    .bind(organization_id)
+    .bind(&slugs)
+    .bind(&names)
+    .execute(&mut **transaction)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+        SELECT $2, t.id, card_tags.position
+        FROM UNNEST($3::TEXT[]) WITH ORDINALITY AS card_tags(slug, position)
+        JOIN tags t ON t.organization_id = $1 AND t.slug = card_tags.slug
+        ON CONFLICT DO NOTHING;",
+    )
+    .bind(organization_id)
+    .bind(id)
+    .bind(&slugs)
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
+
 // function which queries the database and returns all the flash cards
 pub async fn create_flashcard(
-    pool: PgPool,
+    pool: &PgPool,
+    organization_id: Uuid,
     new_card: &FlashCard,
 ) -> Result<FlashCard, sqlx::Error> {
-    let new_flash_card: FlashCard =
-        sqlx::query_as("INSERT INTO flashcards (id, question, answer, topic, tags, difficulty, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *;")
-            .bind(new_card.id)
-            .bind(&new_card.question)
-            .bind(&new_card.answer)
-            .bind(&new_card.topic)
-            .bind(&new_card.tags)
-            .bind(new_card.difficulty)
-            .bind(new_card.created_at)
-            .bind(new_card.updated_at)
-            .fetch_one(&pool)
-            .await?;
+    let mut transaction = pool.begin().await?;
+    let new_flash_card = insert_flashcard(&mut transaction, organization_id, new_card).await?;
+    transaction.commit().await?;
 
     Ok(new_flash_card)
 }
 
+// insert a flash card, along with its topic and its tags, within a transaction
+async fn insert_flashcard(
+    transaction: &mut Transaction<'_, Postgres>,
+    organization_id: Uuid,
+    new_card: &FlashCard,
+) -> Result<FlashCard, sqlx::Error> {
+    let topic_id = upsert_topic(transaction, organization_id, &new_card.topic).await?;
+    sqlx::query("INSERT INTO flashcards (organization_id, id, question, answer, topic_id, difficulty, kind, status, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);")
+        .bind(organization_id)
+        .bind(new_card.id)
+        .bind(&new_card.question)
+        .bind(&new_card.answer)
+        .bind(topic_id)
+        .bind(new_card.difficulty)
+        .bind(Json(&new_card.kind))
+        .bind(new_card.status.as_str())
+        .bind(new_card.created_at)
+        .bind(new_card.updated_at)
+        .execute(&mut **transaction)
+        .await?;
+    set_flashcard_tags(transaction, organization_id, new_card.id, &new_card.tags).await?;
+
+    sqlx::query_as(&format!("{} {};", SELECT_FLASHCARDS, BY_ID))
+        .bind(organization_id)
+        .bind(new_card.id)
+        .fetch_one(&mut **transaction)
+        .await
+}
+
 // function which queries the database, given a flash card id, and deletes that entry
-pub async fn delete_flashcard(pool: PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("DELETE FROM flashcards WHERE id = $1;")
+pub async fn delete_flashcard(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM flashcards WHERE organization_id = $1 AND id = $2;")
+        .bind(organization_id)
         .bind(id)
-        .execute(&pool)
+        .execute(pool)
         .await?;
 
     Ok(result.rows_affected())
 }
 
-// function which queries the database, given a flashcard id, and updates that entry
+// function which queries the database, given a flashcard id, and updates that entry; fields
+// missing from the update keep their current value
 pub async fn update_flashcard(
-    pool: PgPool,
+    pool: &PgPool,
+    organization_id: Uuid,
     id: Uuid,
     updated_card: &UpdatedFlashCard,
 ) -> Result<FlashCard, sqlx::Error> {
-    let updated_flash_card: FlashCard = sqlx::query_as("UPDATE flashcards SET question = $1, answer = $2, topic = $3, tags = $4, difficulty = $5, updated_at = $6 WHERE id = $7 RETURNING *;")
+    let mut transaction = pool.begin().await?;
+    let updated_flash_card =
+        apply_flashcard_update(&mut transaction, organization_id, id, updated_card).await?;
+    transaction.commit().await?;
+
+    Ok(updated_flash_card)
+}
+
+// update a flash card within a transaction; fields missing from the update keep their value
+async fn apply_flashcard_update(
+    transaction: &mut Transaction<'_, Postgres>,
+    organization_id: Uuid,
+    id: Uuid,
+    updated_card: &UpdatedFlashCard,
+) -> Result<FlashCard, sqlx::Error> {
+    let topic_id = match &updated_card.topic {
+        Some(topic) => Some(upsert_topic(transaction, organization_id, topic).await?),
+        None => None,
+    };
+    let updated_id: Uuid = sqlx::query_scalar("UPDATE flashcards SET question = COALESCE($2, question), answer = COALESCE($3, answer), topic_id = COALESCE($4, topic_id), difficulty = COALESCE($5, difficulty), kind = COALESCE($6, kind), updated_at = $7 WHERE organization_id = $1 AND id = $8 RETURNING id;")
+        .bind(organization_id)
         .bind(&updated_card.question)
         .bind(&updated_card.answer)
-        .bind(&updated_card.topic)
-        .bind(&updated_card.tags)
+        .bind(topic_id)
         .bind(updated_card.difficulty)
+        .bind(updated_card.kind.as_ref().map(Json))
         .bind(Some(PavexTimestamp::now().to_sqlx()))
         .bind(id)
-        .fetch_one(&pool)
+        .fetch_one(&mut **transaction)
         .await?;
+    if let Some(tags) = &updated_card.tags {
+        set_flashcard_tags(transaction, organization_id, updated_id, tags).await?;
+    }
 
-    Ok(updated_flash_card)
+    sqlx::query_as(&format!("{} {};", SELECT_FLASHCARDS, BY_ID))
+        .bind(organization_id)
+        .bind(updated_id)
+        .fetch_one(&mut **transaction)
+        .await
+}
+
+// function which queries the database and returns the ids of up to `count` published flash cards
+// matching the random selection criteria, sampled by the database itself: every candidate gets
+// the key -ln(u) / weight, u being uniform in (0, 1], and the smallest keys are kept, which picks
+// each candidate with a likelihood following its weight (one for every card when unweighted)
+pub async fn random_flashcard_ids(
+    pool: &PgPool,
+    organization_id: Uuid,
+    selection: &RandomSelection,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let tags: Vec<String> = selection.tags.iter().map(|tag| slugify(tag)).collect();
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT f.id
+        FROM flashcards f
+        LEFT JOIN topics tp ON tp.id = f.topic_id
+        WHERE f.organization_id = $1 AND f.status = 'published'
+        AND ($2::TEXT IS NULL OR tp.slug = $2)
+        AND (cardinality($3::TEXT[]) = 0 OR EXISTS (
+            SELECT 1 FROM flashcard_tags ft JOIN tags t ON t.id = ft.tag_id
+            WHERE ft.flashcard_id = f.id AND t.slug = ANY($3)))
+        AND f.difficulty BETWEEN $4 AND $5
+        AND f.id <> ALL($6::UUID[])
+        ORDER BY -ln(1.0 - random()) / CASE $7::TEXT
+            WHEN 'harder' THEN f.difficulty
+            WHEN 'easier' THEN $8 - f.difficulty
+            ELSE 1 END
+        LIMIT $9;",
+    )
+    .bind(organization_id)
+    .bind(selection.topic.as_deref().map(slugify))
+    .bind(tags)
+    .bind(selection.min_difficulty)
+    .bind(selection.max_difficulty)
+    .bind(&selection.exclude)
+    .bind(selection.weighting.map(|weighting| weighting.as_str()))
+    .bind(MAX_DIFFICULTY + 1)
+    .bind(selection.count as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ids)
+}
+
+// function which queries the database and returns the card of the day for the selection; the
+// first request of the day picks the card and records it, so that later requests, and the next
+// days, see it. Concurrent first requests may pick at the same time: only one pick is recorded
+// and every request returns that one. A pick which is no longer published is replaced
+pub async fn daily_flashcard(
+    pool: &PgPool,
+    organization_id: Uuid,
+    selection: &DailySelection,
+) -> Result<Option<FlashCard>, sqlx::Error> {
+    let scope = selection.scope();
+    let day = selection.day_key();
+    let select_daily = format!(
+        "{} JOIN daily_cards d ON d.flashcard_id = f.id
+        WHERE d.organization_id = $1 AND d.scope = $2 AND d.day = $3 AND {};",
+        SELECT_FLASHCARDS, PUBLISHED
+    );
+    let picked: Option<FlashCard> = sqlx::query_as(&select_daily)
+        .bind(organization_id)
+        .bind(&scope)
+        .bind(&day)
+        .fetch_optional(pool)
+        .await?;
+    if picked.is_some() {
+        return Ok(picked);
+    }
+
+    let candidates: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT f.id FROM flashcards f
+        LEFT JOIN topics tp ON tp.id = f.topic_id
+        WHERE f.organization_id = $1 AND f.status = 'published' AND ($2 = '' OR tp.slug = $2);",
+    )
+    .bind(organization_id)
+    .bind(&scope)
+    .fetch_all(pool)
+    .await?;
+    let recent: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT flashcard_id FROM daily_cards
+        WHERE organization_id = $1 AND scope = $2 AND day >= $3 AND day < $4;",
+    )
+    .bind(organization_id)
+    .bind(&scope)
+    .bind(selection.window_start())
+    .bind(&day)
+    .fetch_all(pool)
+    .await?;
+    let Some(id) = selection.pick(&candidates, &recent) else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "INSERT INTO daily_cards (organization_id, day, scope, flashcard_id) VALUES ($1, $2, $3, $4)
+        ON CONFLICT (organization_id, scope, day) DO UPDATE SET flashcard_id = excluded.flashcard_id
+        WHERE NOT EXISTS (SELECT 1 FROM flashcards f
+            WHERE f.id = daily_cards.flashcard_id AND f.status = 'published');",
+    )
+    .bind(organization_id)
+    .bind(&day)
+    .bind(&scope)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_as(&select_daily)
+        .bind(organization_id)
+        .bind(&scope)
+        .bind(&day)
+        .fetch_optional(pool)
+        .await
+}
+
+// function which queries the database and returns the published flash cards with the given ids,
+// in no particular order
+pub async fn list_flashcards_by_ids(
+    pool: &PgPool,
+    organization_id: Uuid,
+    ids: &[Uuid],
+) -> Result<Vec<FlashCard>, sqlx::Error> {
+    let flash_cards: Vec<FlashCard> = sqlx::query_as(&format!(
+        "{} WHERE f.organization_id = $1 AND f.id = ANY($2) AND {};",
+        SELECT_FLASHCARDS, PUBLISHED
+    ))
+    .bind(organization_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(flash_cards)
+}
+
+// function which renames and/or describes a tag, given its slug; renaming a tag changes its
+// slug, and therefore applies to every flash card carrying it at once
+pub async fn update_tag(
+    pool: &PgPool,
+    organization_id: Uuid,
+    slug: &str,
+    updated_tag: &UpdatedTag,
+) -> Result<Tag, sqlx::Error> {
+    let name = updated_tag.name.as_deref().map(str::trim);
+    let new_slug: String = sqlx::query_scalar(
+        "UPDATE tags
+        SET name = COALESCE($2, name), slug = COALESCE($3, slug), description = COALESCE($4, description)
+        WHERE organization_id = $1 AND slug = $5
+        RETURNING slug;",
+    )
+    .bind(organization_id)
+    .bind(name)
+    .bind(name.map(slugify))
+    .bind(&updated_tag.description)
+    .bind(slug)
+    .fetch_one(pool)
+    .await?;
+
+    get_tag(pool, organization_id, &new_slug).await
+}
+
+// function which merges a tag into another one, in a single transaction: every flash card
+// carrying the source tag ends up carrying the target tag, and the source tag is deleted
+pub async fn merge_tags(
+    pool: &PgPool,
+    organization_id: Uuid,
+    source: &str,
+    target: &str,
+) -> Result<Tag, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    let select_tag = "SELECT id FROM tags WHERE organization_id = $1 AND slug = $2 FOR UPDATE;";
+    let source_id: Uuid = sqlx::query_scalar(select_tag)
+        .bind(organization_id)
+        .bind(source)
+        .fetch_one(&mut *transaction)
+        .await?;
+    let target_id: Uuid = sqlx::query_scalar(select_tag)
+        .bind(organization_id)
+        .bind(target)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+    if source_id != target_id {
+        sqlx::query(
+            "INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+            SELECT flashcard_id, $2, position
+            FROM flashcard_tags
+            WHERE tag_id = $1
+            ON CONFLICT DO NOTHING;",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM tags WHERE id = $1;")
+            .bind(source_id)
+            .execute(&mut *transaction)
+            .await?;
+    }
+
+    transaction.commit().await?;
+
+    get_tag(pool, organization_id, target).await
+}
+
+// function which stores a quiz, along with its questions
+pub async fn create_quiz(
+    pool: &PgPool,
+    organization_id: Uuid,
+    quiz: &Quiz,
+) -> Result<Quiz, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO quizzes (organization_id, id, topic, tag, created_at) VALUES ($1, $2, $3, $4, $5);",
+    )
+    .bind(organization_id)
+    .bind(quiz.id)
+        .bind(&quiz.topic)
+        .bind(&quiz.tag)
+        .bind(quiz.created_at)
+        .execute(&mut *transaction)
+        .await?;
+    for (position, question) in quiz.questions.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO quiz_questions (quiz_id, position, flashcard_id, question, options, correct_option)
+            VALUES ($1, $2, $3, $4, $5, $6);",
+        )
+        .bind(quiz.id)
+        .bind(position as i32)
+        .bind(question.flashcard_id)
+        .bind(&question.question)
+        .bind(&question.options)
+        .bind(question.correct_option as i32)
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await?;
+
+    Ok(quiz.clone())
+}
+
+// function which moves a flash card to a new status and records the review, provided the card is
+// still in the status the review moves it from; `None` otherwise
+pub async fn review_flashcard(
+    pool: &PgPool,
+    organization_id: Uuid,
+    review: &CardReview,
+) -> Result<Option<FlashCard>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    let result = sqlx::query(
+        "UPDATE flashcards SET status = $2, updated_at = $3
+        WHERE organization_id = $1 AND id = $4 AND status = $5;",
+    )
+    .bind(organization_id)
+    .bind(review.to_status.as_str())
+    .bind(review.reviewed_at)
+    .bind(review.flashcard_id)
+    .bind(review.from_status.as_str())
+    .execute(&mut *transaction)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    sqlx::query(
+        "INSERT INTO flashcard_reviews (id, flashcard_id, from_status, to_status, note, reviewed_at)
+        VALUES ($1, $2, $3, $4, $5, $6);",
+    )
+    .bind(review.id)
+    .bind(review.flashcard_id)
+    .bind(review.from_status.as_str())
+    .bind(review.to_status.as_str())
+    .bind(&review.note)
+    .bind(review.reviewed_at)
+    .execute(&mut *transaction)
+    .await?;
+
+    let reviewed: FlashCard = sqlx::query_as(&format!("{} {};", SELECT_FLASHCARDS, BY_ID))
+        .bind(organization_id)
+        .bind(review.flashcard_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+
+    Ok(Some(reviewed))
+}
+
+// function which queries the database and returns the reviews of a flash card, oldest first
+pub async fn list_reviews(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<Vec<CardReview>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE flashcard_id IN ({}) ORDER BY reviewed_at ASC;",
+        SELECT_REVIEWS, CARD_ID
+    ))
+    .bind(organization_id)
+    .bind(id)
+    .fetch_all(pool)
+    .await
+}
+
+// the columns of a draft card
+const SELECT_DRAFTS: &str =
+    "SELECT id, question, answer, topic, tags, difficulty, source, created_at
+    FROM flashcard_drafts";
+
+// function which stores draft cards, skipping those whose question is already asked by a draft
+// or a card, and returns the drafts stored
+pub async fn create_drafts(
+    pool: &PgPool,
+    organization_id: Uuid,
+    drafts: &[FlashcardDraft],
+) -> Result<Vec<FlashcardDraft>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    let mut created = Vec::with_capacity(drafts.len());
+    for draft in drafts {
+        let result = sqlx::query(
+            "INSERT INTO flashcard_drafts (organization_id, id, question, answer, topic, tags, difficulty, source, created_at)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9
+            WHERE NOT EXISTS (SELECT 1 FROM flashcards WHERE organization_id = $1 AND question = $3)
+            ON CONFLICT (organization_id, question) DO NOTHING;",
+        )
+        .bind(organization_id)
+        .bind(draft.id)
+        .bind(&draft.question)
+        .bind(&draft.answer)
+        .bind(&draft.topic)
+        .bind(&draft.tags)
+        .bind(draft.difficulty)
+        .bind(&draft.source)
+        .bind(draft.created_at)
+        .execute(&mut *transaction)
+        .await?;
+        if result.rows_affected() == 1 {
+            created.push(draft.clone());
+        }
+    }
+    transaction.commit().await?;
+
+    Ok(created)
+}
+
+// function which queries the database and returns every draft card
+pub async fn list_drafts(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<Vec<FlashcardDraft>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1 ORDER BY created_at ASC, source ASC;",
+        SELECT_DRAFTS
+    ))
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await
 }
 
-// function which queries the database and returns a random flash card
-pub async fn random_flashcard(pool: PgPool) -> Result<Option<FlashCard>, sqlx::Error> {
-    let random_card =
-        sqlx::query_as::<_, FlashCard>("SELECT * FROM flashcards ORDER BY RANDOM() LIMIT 1;")
-            .fetch_optional(&pool)
+// function which queries the database, given a draft id, and returns that draft
+pub async fn get_draft(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<FlashcardDraft, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1 AND id = $2;",
+        SELECT_DRAFTS
+    ))
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+// function which queries the database, given a draft id, and deletes that draft
+pub async fn delete_draft(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM flashcard_drafts WHERE organization_id = $1 AND id = $2;")
+            .bind(organization_id)
+            .bind(id)
+            .execute(pool)
             .await?;
 
-    Ok(random_card)
+    Ok(result.rows_affected())
+}
+
+// function which registers a user, along with the hash of their token
+pub async fn create_user(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user: &User,
+    token_hash: &str,
+) -> Result<User, sqlx::Error> {
+    sqlx::query_as(
+        "INSERT INTO users (organization_id, id, name, token_hash, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, name, created_at;",
+    )
+    .bind(organization_id)
+    .bind(user.id)
+    .bind(&user.name)
+    .bind(token_hash)
+    .bind(user.created_at)
+    .fetch_one(pool)
+    .await
+}
+
+// function which queries the database, given the hash of a token, and returns its user if any
+pub async fn get_user_by_token(
+    pool: &PgPool,
+    organization_id: Uuid,
+    token_hash: &str,
+) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, name, created_at FROM users WHERE organization_id = $1 AND token_hash = $2;",
+    )
+    .bind(organization_id)
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+// the columns of a suggestion
+const SELECT_SUGGESTIONS: &str =
+    "SELECT id, user_id, flashcard_id, changes, diff, status, note, created_at, reviewed_at
+    FROM card_suggestions";
+
+// function which stores a suggestion
+pub async fn create_suggestion(
+    pool: &PgPool,
+    organization_id: Uuid,
+    suggestion: &Suggestion,
+) -> Result<Suggestion, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO card_suggestions
+            (organization_id, id, user_id, flashcard_id, changes, diff, status, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8);",
+    )
+    .bind(organization_id)
+    .bind(suggestion.id)
+    .bind(suggestion.user_id)
+    .bind(suggestion.flashcard_id)
+    .bind(Json(&suggestion.changes))
+    .bind(Json(&suggestion.diff))
+    .bind(suggestion.status.as_str())
+    .bind(suggestion.created_at)
+    .execute(pool)
+    .await?;
+
+    get_suggestion(pool, organization_id, suggestion.id).await
+}
+
+// function which queries the database and returns the suggestions in the given status and/or
+// made by the given user, oldest first
+pub async fn list_suggestions(
+    pool: &PgPool,
+    organization_id: Uuid,
+    status: Option<SuggestionStatus>,
+    user_id: Option<Uuid>,
+) -> Result<Vec<Suggestion>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1
+            AND ($2::TEXT IS NULL OR status = $2) AND ($3::UUID IS NULL OR user_id = $3)
+        ORDER BY created_at ASC;",
+        SELECT_SUGGESTIONS
+    ))
+    .bind(organization_id)
+    .bind(status.map(|status| status.as_str()))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+// function which queries the database, given a suggestion id, and returns that suggestion
+pub async fn get_suggestion(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<Suggestion, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1 AND id = $2;",
+        SELECT_SUGGESTIONS
+    ))
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+// function which records the decision of a moderator on a suggestion, provided it is still
+// pending; `None` otherwise
+pub async fn close_suggestion(
+    pool: &PgPool,
+    organization_id: Uuid,
+    suggestion: &Suggestion,
+) -> Result<Option<Suggestion>, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE card_suggestions SET status = $2, note = $3, reviewed_at = $4
+        WHERE organization_id = $1 AND id = $5 AND status = 'pending';",
+    )
+    .bind(organization_id)
+    .bind(suggestion.status.as_str())
+    .bind(&suggestion.note)
+    .bind(suggestion.reviewed_at)
+    .bind(suggestion.id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        get_suggestion(pool, organization_id, suggestion.id).await?,
+    ))
+}
+
+// function which records the approval of a suggestion and applies it, in a single transaction:
+// the suggestion is closed first, provided it is still pending, so that concurrent approvals
+// apply it once; `new_card` is created for a suggested new card, the changes of a suggested edit
+// go to the card it is about otherwise. `None` when the suggestion was no longer pending, nothing
+// being applied then
+pub async fn approve_suggestion(
+    pool: &PgPool,
+    organization_id: Uuid,
+    suggestion: &Suggestion,
+    new_card: Option<&FlashCard>,
+) -> Result<Option<(Suggestion, FlashCard)>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    let result = sqlx::query(
+        "UPDATE card_suggestions SET status = $2, note = $3, reviewed_at = $4
+        WHERE organization_id = $1 AND id = $5 AND status = 'pending';",
+    )
+    .bind(organization_id)
+    .bind(suggestion.status.as_str())
+    .bind(&suggestion.note)
+    .bind(suggestion.reviewed_at)
+    .bind(suggestion.id)
+    .execute(&mut *transaction)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    let flash_card = match new_card {
+        Some(new_card) => insert_flashcard(&mut transaction, organization_id, new_card).await?,
+        None => {
+            let id = suggestion.flashcard_id.ok_or(sqlx::Error::RowNotFound)?;
+            apply_flashcard_update(&mut transaction, organization_id, id, &suggestion.changes)
+                .await?
+        }
+    };
+    let approved: Suggestion = sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1 AND id = $2;",
+        SELECT_SUGGESTIONS
+    ))
+    .bind(organization_id)
+    .bind(suggestion.id)
+    .fetch_one(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    Ok(Some((approved, flash_card)))
+}
+
+// the columns of a feedback entry
+const SELECT_FEEDBACK: &str =
+    "SELECT id, flashcard_id, kind, comment, client, created_at, resolved_at FROM card_feedback";
+
+// function which stores feedback left on a card, provided its client left fewer than `limit`
+// entries since the given time; `None` otherwise. The feedback of a client is stored one entry
+// at a time, under a lock held until the transaction ends, so that concurrent requests can't
+// both count below the limit
+pub async fn create_feedback(
+    pool: &PgPool,
+    organization_id: Uuid,
+    feedback: &CardFeedback,
+    limit: u32,
+    since: SqlxTimestamp,
+) -> Result<Option<CardFeedback>, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0));")
+        .bind(format!(
+            "card_feedback:{}:{}",
+            organization_id, feedback.client
+        ))
+        .execute(&mut *transaction)
+        .await?;
+    let created: Option<CardFeedback> = sqlx::query_as(
+        "INSERT INTO card_feedback (organization_id, id, flashcard_id, kind, comment, client, created_at)
+        SELECT $1, $2, $3, $4, $5, $6, $7
+        WHERE (SELECT COUNT(*) FROM card_feedback
+            WHERE organization_id = $1 AND client = $6 AND created_at >= $8) < $9
+        RETURNING id, flashcard_id, kind, comment, client, created_at, resolved_at;",
+    )
+    .bind(organization_id)
+    .bind(feedback.id)
+    .bind(feedback.flashcard_id)
+    .bind(feedback.kind.as_str())
+    .bind(&feedback.comment)
+    .bind(&feedback.client)
+    .bind(feedback.created_at)
+    .bind(since)
+    .bind(i64::from(limit))
+    .fetch_optional(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    Ok(created)
+}
+
+// function which queries the database and returns the cards with open flags, along with their
+// upvotes, the most flagged first
+pub async fn feedback_report(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<Vec<FlaggedCard>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT f.id AS flashcard_id, f.question,
+            COUNT(*) FILTER (WHERE fb.kind <> 'upvote' AND fb.resolved_at IS NULL) AS open_flags,
+            COUNT(*) FILTER (WHERE fb.kind = 'upvote') AS upvotes
+        FROM flashcards f
+        JOIN card_feedback fb ON fb.flashcard_id = f.id
+        WHERE f.organization_id = $1
+        GROUP BY f.id
+        HAVING COUNT(*) FILTER (WHERE fb.kind <> 'upvote' AND fb.resolved_at IS NULL) > 0
+        ORDER BY open_flags DESC, f.question ASC;",
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await
+}
+
+// function which queries the database, given a card id, and returns its open flags, oldest first
+pub async fn list_open_flags(
+    pool: &PgPool,
+    organization_id: Uuid,
+    flashcard_id: Uuid,
+) -> Result<Vec<CardFeedback>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} WHERE organization_id = $1 AND flashcard_id = $2
+            AND kind <> 'upvote' AND resolved_at IS NULL
+        ORDER BY created_at ASC;",
+        SELECT_FEEDBACK
+    ))
+    .bind(organization_id)
+    .bind(flashcard_id)
+    .fetch_all(pool)
+    .await
+}
+
+// function which resolves the open flags of a card, returning the number of flags resolved
+pub async fn resolve_flags(
+    pool: &PgPool,
+    organization_id: Uuid,
+    flashcard_id: Uuid,
+    resolved_at: SqlxTimestamp,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE card_feedback SET resolved_at = $2
+        WHERE organization_id = $1 AND flashcard_id = $3
+            AND kind <> 'upvote' AND resolved_at IS NULL;",
+    )
+    .bind(organization_id)
+    .bind(resolved_at)
+    .bind(flashcard_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+// function which records a card studied by a user
+pub async fn create_study_review(
+    pool: &PgPool,
+    organization_id: Uuid,
+    review: &StudyReview,
+) -> Result<StudyReview, sqlx::Error> {
+    sqlx::query_as(
+        "INSERT INTO study_reviews (organization_id, id, user_id, flashcard_id, grade, reviewed_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, user_id, flashcard_id, grade, reviewed_at;",
+    )
+    .bind(organization_id)
+    .bind(review.id)
+    .bind(review.user_id)
+    .bind(review.flashcard_id)
+    .bind(review.grade.as_str())
+    .bind(review.reviewed_at)
+    .fetch_one(pool)
+    .await
+}
+
+// function which queries the database, given a user id, and returns the cards they studied,
+// oldest first
+pub async fn list_study_reviews(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<StudyReview>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, user_id, flashcard_id, grade, reviewed_at FROM study_reviews
+        WHERE organization_id = $1 AND user_id = $2
+        ORDER BY reviewed_at ASC;",
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+// function which stores the compile-check of the snippets of a card, replacing the previous one
+pub async fn save_code_verification(
+    pool: &PgPool,
+    organization_id: Uuid,
+    verification: &CodeVerification,
+) -> Result<CodeVerification, sqlx::Error> {
+    sqlx::query(&format!(
+        "INSERT INTO code_verifications (flashcard_id, status, snippet_count, output, checked_at)
+        SELECT id, $3, $4, $5, $6 FROM ({}) card
+        ON CONFLICT (flashcard_id) DO UPDATE
+        SET status = EXCLUDED.status, snippet_count = EXCLUDED.snippet_count,
+            output = EXCLUDED.output, checked_at = EXCLUDED.checked_at;",
+        CARD_ID
+    ))
+    .bind(organization_id)
+    .bind(verification.flashcard_id)
+    .bind(verification.status.as_str())
+    .bind(verification.snippet_count as i32)
+    .bind(&verification.output)
+    .bind(verification.checked_at)
+    .execute(pool)
+    .await?;
+
+    Ok(verification.clone())
+}
+
+// function which queries the database, given a card id, and returns the last compile-check of
+// its snippets
+pub async fn get_code_verification(
+    pool: &PgPool,
+    organization_id: Uuid,
+    id: Uuid,
+) -> Result<CodeVerification, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT flashcard_id, status, snippet_count, output, checked_at
+        FROM code_verifications
+        WHERE flashcard_id IN ({});",
+        CARD_ID
+    ))
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+// function which queries the database, given a quiz id, and returns that quiz with its questions
+pub async fn get_quiz(pool: &PgPool, organization_id: Uuid, id: Uuid) -> Result<Quiz, sqlx::Error> {
+    let (topic, tag, created_at): (Option<String>, Option<String>, SqlxTimestamp) = sqlx::query_as(
+        "SELECT topic, tag, created_at FROM quizzes WHERE organization_id = $1 AND id = $2;",
+    )
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+    let questions: Vec<QuizQuestion> = sqlx::query_as(
+        "SELECT flashcard_id, question, options, correct_option
+        FROM quiz_questions
+        WHERE quiz_id = $1
+        ORDER BY position;",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Quiz {
+        id,
+        topic,
+        tag,
+        questions,
+        created_at,
+    })
+}
+
+// function which deletes the quizzes built before the given time; their questions go along
+pub async fn delete_quizzes_before(
+    pool: &PgPool,
+    organization_id: Uuid,
+    before: SqlxTimestamp,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM quizzes WHERE organization_id = $1 AND created_at < $2;")
+        .bind(organization_id)
+        .bind(before)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+// the columns of an organization, with the hosts it is served on; organizations are the only
+// rows which are not scoped to one
+const SELECT_ORGANIZATIONS: &str = "SELECT o.id, o.slug, o.name,
+    ARRAY(SELECT h.host
+        FROM organization_hosts h
+        WHERE h.organization_id = o.id
+        ORDER BY h.host) AS hosts,
+    o.created_at
+    FROM organizations o";
+
+// function which stores an organization, along with its hosts and its first API key
+pub async fn create_organization(
+    pool: &PgPool,
+    organization: &Organization,
+    key_hash: &str,
+) -> Result<Organization, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query("INSERT INTO organizations (id, slug, name, created_at) VALUES ($1, $2, $3, $4);")
+        .bind(organization.id)
+        .bind(&organization.slug)
+        .bind(&organization.name)
+        .bind(organization.created_at)
+        .execute(&mut *transaction)
+        .await?;
+    for host in &organization.hosts {
+        sqlx::query("INSERT INTO organization_hosts (host, organization_id) VALUES ($1, $2);")
+            .bind(host)
+            .bind(organization.id)
+            .execute(&mut *transaction)
+            .await?;
+    }
+    sqlx::query(
+        "INSERT INTO organization_api_keys (key_hash, organization_id, created_at)
+        VALUES ($1, $2, $3);",
+    )
+    .bind(key_hash)
+    .bind(organization.id)
+    .bind(organization.created_at)
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    get_organization(pool, &organization.slug).await
+}
+
+// function which queries the database and returns all the organizations, by slug
+pub async fn list_organizations(pool: &PgPool) -> Result<Vec<Organization>, sqlx::Error> {
+    sqlx::query_as(&format!("{} ORDER BY o.slug ASC;", SELECT_ORGANIZATIONS))
+        .fetch_all(pool)
+        .await
+}
+
+// function which queries the database, given a slug, and returns that organization
+pub async fn get_organization(pool: &PgPool, slug: &str) -> Result<Organization, sqlx::Error> {
+    sqlx::query_as(&format!("{} WHERE o.slug = $1;", SELECT_ORGANIZATIONS))
+        .bind(slug)
+        .fetch_one(pool)
+        .await
+}
+
+// function which stores another API key of an organization
+pub async fn add_api_key(
+    pool: &PgPool,
+    organization_id: Uuid,
+    key_hash: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO organization_api_keys (key_hash, organization_id, created_at)
+        VALUES ($1, $2, $3);",
+    )
+    .bind(key_hash)
+    .bind(organization_id)
+    .bind(PavexTimestamp::now().to_sqlx())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// function which queries the database, given the hash of an API key, and returns the
+// organization it belongs to
+pub async fn find_organization_by_api_key(
+    pool: &PgPool,
+    key_hash: &str,
+) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} JOIN organization_api_keys k ON k.organization_id = o.id WHERE k.key_hash = $1;",
+        SELECT_ORGANIZATIONS
+    ))
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+// function which queries the database, given a host name, and returns the organization served
+// on it
+pub async fn find_organization_by_host(
+    pool: &PgPool,
+    host: &str,
+) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "{} JOIN organization_hosts oh ON oh.organization_id = o.id WHERE oh.host = $1;",
+        SELECT_ORGANIZATIONS
+    ))
+    .bind(host)
+    .fetch_optional(pool)
+    .await
 }
@@ -0,0 +1,286 @@
+// app/src/rate_limit.rs
+
+// per-client rate limiting of the public and admin APIs: every client has a token bucket per API,
+// holding as many tokens as the configured number of requests and refilled with as many every
+// configured period; a request takes a token, and is refused while the bucket is empty. Clients
+// are told apart by their bearer token, else by their IP address. The buckets are spread over
+// shards, each behind its own lock, and the idle ones are dropped in sweeps of a single shard
+
+// dependencies
+use crate::configuration::RateLimit;
+use pavex::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+// number of shards the buckets are spread over, so that concurrent requests seldom wait for the
+// same lock
+const SHARDS: usize = 16;
+
+// number of buckets of a shard above which the full ones, those of idle clients, are dropped; the
+// next sweep waits for twice as many buckets as were kept, so that a shard of busy clients isn't
+// swept on every request
+const SWEEP_THRESHOLD: usize = 100_000 / SHARDS;
+
+/// The outcome of a request against the bucket of its client, as reported in the
+/// `RateLimit-*` headers of the response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quota {
+    /// The number of requests a client may send in a burst.
+    pub limit: u32,
+    /// The number of requests the client may still send right away.
+    pub remaining: u32,
+    /// The number of seconds until the bucket of the client is full again.
+    pub reset: u64,
+    /// The number of seconds until the client may send another request, if this one was refused.
+    pub retry_after: Option<u64>,
+}
+
+// methods for the Quota type
+impl Quota {
+    /// Add the `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers and, if the
+    /// request was refused, the `Retry-After` header.
+    pub fn insert_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            HeaderName::from_static("ratelimit-limit"),
+            HeaderValue::from(self.limit),
+        );
+        headers.insert(
+            HeaderName::from_static("ratelimit-remaining"),
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            HeaderName::from_static("ratelimit-reset"),
+            HeaderValue::from(self.reset),
+        );
+        if let Some(retry_after) = self.retry_after {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+    }
+}
+
+/// The quota of the request, once it went through the rate limiter; request-scoped, so that
+/// the headers can be added to the response.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitStatus(pub Option<Quota>);
+
+// methods for the RateLimitStatus type
+impl RateLimitStatus {
+    /// Request-scoped constructor, before the rate limiter ran.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// the tokens left in a bucket, as of its last update
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    full_at: Instant,
+}
+
+// a share of the buckets, and the number of them which triggers its next sweep
+#[derive(Debug)]
+struct Shard {
+    buckets: HashMap<String, Bucket>,
+    sweep_at: usize,
+}
+
+// methods for the Shard type
+impl Shard {
+    fn new() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            sweep_at: SWEEP_THRESHOLD,
+        }
+    }
+
+    // the bucket `key`, full if it wasn't tracked, after dropping the full buckets if there are
+    // enough of them to sweep
+    fn bucket(&mut self, key: String, capacity: f64, now: Instant) -> &mut Bucket {
+        if self.buckets.len() >= self.sweep_at {
+            self.buckets.retain(|_, bucket| bucket.full_at > now);
+            self.sweep_at = (self.buckets.len() * 2).max(SWEEP_THRESHOLD);
+        }
+
+        self.buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+            full_at: now,
+        })
+    }
+}
+
+/// The token buckets of the clients, shared by every request.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    shards: Arc<[Mutex<Shard>]>,
+    hasher: RandomState,
+}
+
+// implement the Default trait, with every bucket full
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::new(Shard::new())).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+}
+
+// methods for the RateLimiter type
+impl RateLimiter {
+    /// Singleton constructor, with every bucket full.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a token from the bucket `key` for a request limited by `limit`.
+    pub fn check(&self, key: String, limit: &RateLimit) -> Quota {
+        self.check_at(key, limit, Instant::now())
+    }
+
+    /// Take a token from the bucket `key` for a request limited by `limit`; a bucket which isn't
+    /// tracked yet is only handed out once a token was taken from the bucket `opener`, so that a
+    /// client can't dodge the limit of `opener` by sending new keys. The quota of `opener` is
+    /// returned if it refuses the request.
+    pub fn check_or_open(&self, key: String, opener: String, limit: &RateLimit) -> Quota {
+        self.check_or_open_at(key, opener, limit, Instant::now())
+    }
+
+    fn check_or_open_at(
+        &self,
+        key: String,
+        opener: String,
+        limit: &RateLimit,
+        now: Instant,
+    ) -> Quota {
+        if !self.shard(&key).buckets.contains_key(&key) {
+            let quota = self.check_at(opener, limit, now);
+            if quota.retry_after.is_some() {
+                return quota;
+            }
+        }
+
+        self.check_at(key, limit, now)
+    }
+
+    // the locked shard holding the bucket `key`
+    fn shard(&self, key: &str) -> MutexGuard<'_, Shard> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        self.shards[index].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn check_at(&self, key: String, limit: &RateLimit, now: Instant) -> Quota {
+        let capacity = f64::from(limit.requests);
+        let rate = capacity / limit.per.as_secs_f64();
+        let mut shard = self.shard(&key);
+
+        let bucket = shard.bucket(key, capacity, now);
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+        let retry_after = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64)
+        };
+        let until_full = (capacity - bucket.tokens) / rate;
+        bucket.full_at = now + std::time::Duration::from_secs_f64(until_full);
+
+        Quota {
+            limit: limit.requests,
+            remaining: bucket.tokens.floor() as u32,
+            reset: until_full.ceil() as u64,
+            retry_after,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limit(requests: u32, seconds: u64) -> RateLimit {
+        RateLimit {
+            requests,
+            per: Duration::from_secs(seconds),
+        }
+    }
+
+    #[test]
+    fn an_empty_bucket_refuses_requests_until_refilled() {
+        let limiter = RateLimiter::new();
+        let limit = limit(2, 10);
+        let start = Instant::now();
+
+        let first = limiter.check_at("client".to_string(), &limit, start);
+        let second = limiter.check_at("client".to_string(), &limit, start);
+        let refused = limiter.check_at("client".to_string(), &limit, start);
+        let refilled =
+            limiter.check_at("client".to_string(), &limit, start + Duration::from_secs(5));
+
+        assert_eq!(first.remaining, 1);
+        assert_eq!(first.retry_after, None);
+        assert_eq!(second.remaining, 0);
+        assert_eq!(second.reset, 10);
+        assert_eq!(refused.retry_after, Some(5));
+        assert_eq!(refilled.retry_after, None);
+        assert_eq!(refilled.remaining, 0);
+    }
+
+    #[test]
+    fn clients_have_their_own_buckets() {
+        let limiter = RateLimiter::new();
+        let limit = limit(1, 60);
+        let now = Instant::now();
+
+        limiter.check_at("ip:a".to_string(), &limit, now);
+        let other = limiter.check_at("ip:b".to_string(), &limit, now);
+
+        assert_eq!(other.retry_after, None);
+    }
+
+    #[test]
+    fn new_keys_take_a_token_from_their_opener() {
+        let limiter = RateLimiter::new();
+        let limit = limit(2, 60);
+        let now = Instant::now();
+
+        let check =
+            |key: &str| limiter.check_or_open_at(key.to_string(), "ip:a".to_string(), &limit, now);
+
+        let opened = check("token:a");
+        let emptied = limiter.check_at("ip:a".to_string(), &limit, now);
+        let refused = check("token:b");
+        let known = check("token:a");
+
+        assert_eq!(opened.remaining, 1);
+        assert_eq!(emptied.remaining, 0);
+        assert!(refused.retry_after.is_some());
+        assert!(!limiter.shard("token:b").buckets.contains_key("token:b"));
+        assert_eq!(known.retry_after, None);
+        assert_eq!(known.remaining, 0);
+    }
+
+    #[test]
+    fn full_buckets_are_swept_once_a_shard_holds_enough() {
+        let mut shard = Shard::new();
+        let start = Instant::now();
+        let later = start + Duration::from_secs(1);
+        for client in 0..SWEEP_THRESHOLD - 1 {
+            shard.bucket(format!("idle:{client}"), 1.0, start);
+        }
+        shard.bucket("busy".to_string(), 1.0, start).full_at = later + Duration::from_secs(60);
+
+        shard.bucket("new".to_string(), 1.0, later);
+
+        assert_eq!(shard.buckets.len(), 2);
+        assert!(shard.buckets.contains_key("busy"));
+        assert_eq!(shard.sweep_at, SWEEP_THRESHOLD);
+    }
+}
@@ -0,0 +1,1505 @@
+// app/src/repository/memory.rs
+
+// an in-memory storage backend, for unit tests and local demos; nothing is persisted
+
+// dependencies
+use super::{FlashcardRepository, OrganizationRepository, in_sampled_order, sample_ids};
+use crate::errors::ApiError;
+use crate::models::{
+    CardFeedback, CardReview, CardStatus, CodeVerification, DEFAULT_ORGANIZATION_ID,
+    DailySelection, DifficultyStats, FlaggedCard, FlashCard, FlashcardDraft, FlashcardStats,
+    NewTopic, Organization, Quiz, RandomSelection, StudyReview, Suggestion, SuggestionStatus, Tag,
+    TagStats, Topic, TopicStats, UpdatedFlashCard, UpdatedTag, UpdatedTopic, User, WeekStats,
+    slugify,
+};
+use crate::validation::topic_cycle_error;
+use jiff_sqlx::{Timestamp as SqlxTimestamp, ToSqlx};
+use pavex::time::Span;
+use pavex::time::Timestamp as PavexTimestamp;
+use pavex::time::tz::TimeZone;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
+
+// struct type to represent the display name and description of a tag
+#[derive(Clone, Debug)]
+struct TagDetails {
+    name: String,
+    description: Option<String>,
+}
+
+// struct type to represent the display name, description and parent slug of a topic
+#[derive(Clone, Debug)]
+struct TopicDetails {
+    name: String,
+    description: Option<String>,
+    parent: Option<String>,
+}
+
+// struct type to represent everything held by the in-memory backend
+#[derive(Debug, Default)]
+struct MemoryState {
+    cards: HashMap<Uuid, FlashCard>,
+    // tags, keyed by slug
+    tags: BTreeMap<String, TagDetails>,
+    // topics, keyed by slug
+    topics: BTreeMap<String, TopicDetails>,
+    // cards of the day, keyed by scope and day
+    daily: BTreeMap<(String, String), Uuid>,
+    quizzes: HashMap<Uuid, Quiz>,
+    // compile-checks of the card snippets, keyed by card id
+    code_verifications: HashMap<Uuid, CodeVerification>,
+    drafts: HashMap<Uuid, FlashcardDraft>,
+    // changes of status of the cards, oldest first
+    reviews: Vec<CardReview>,
+    // users, keyed by the hash of their token
+    users: HashMap<String, User>,
+    // suggestions made by the users, oldest first
+    suggestions: Vec<Suggestion>,
+    // feedback left on the cards, oldest first
+    feedback: Vec<CardFeedback>,
+    // cards studied by the users, oldest first
+    study_reviews: Vec<StudyReview>,
+}
+
+// methods for the MemoryState type
+impl MemoryState {
+    // store a new card, registering its topic and its tags
+    fn insert_card(&mut self, new_card: &FlashCard) -> Result<FlashCard, ApiError> {
+        if self
+            .cards
+            .values()
+            .any(|card| card.question == new_card.question)
+        {
+            return Err(ApiError::DuplicateQuestion);
+        }
+        let mut card = new_card.clone();
+        card.topic = self.canonical_topic(&new_card.topic);
+        card.tags = self.canonical_tags(&new_card.tags);
+        self.cards.insert(card.id, card.clone());
+        Ok(card)
+    }
+
+    // apply changes to a card; fields missing from the update keep their value
+    fn update_card(
+        &mut self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        let question_taken = updated_card.question.as_ref().is_some_and(|question| {
+            self.cards
+                .values()
+                .any(|card| card.id != id && &card.question == question)
+        });
+        if question_taken {
+            return Err(ApiError::DuplicateQuestion);
+        }
+        if !self.cards.contains_key(&id) {
+            return Err(InMemoryFlashcardRepository::not_found());
+        }
+
+        let topic = updated_card
+            .topic
+            .as_ref()
+            .map(|topic| self.canonical_topic(topic));
+        let tags = updated_card
+            .tags
+            .as_ref()
+            .map(|tags| self.canonical_tags(tags));
+        let card = self
+            .cards
+            .get_mut(&id)
+            .ok_or_else(InMemoryFlashcardRepository::not_found)?;
+        if let Some(question) = &updated_card.question {
+            card.question = question.clone();
+        }
+        if let Some(answer) = &updated_card.answer {
+            card.answer = answer.clone();
+        }
+        if let Some(topic) = topic {
+            card.topic = topic;
+        }
+        if let Some(tags) = tags {
+            card.tags = tags;
+        }
+        if let Some(difficulty) = updated_card.difficulty {
+            card.difficulty = difficulty;
+        }
+        if let Some(kind) = &updated_card.kind {
+            card.kind = kind.clone();
+        }
+        card.updated_at = Some(PavexTimestamp::now().to_sqlx());
+
+        Ok(card.clone())
+    }
+
+    // register the given tags, returning their canonical names, without duplicates
+    fn canonical_tags(&mut self, tags: &[String]) -> Vec<String> {
+        let mut names: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let details = self.tags.entry(slugify(tag)).or_insert_with(|| TagDetails {
+                name: tag.trim().to_string(),
+                description: None,
+            });
+            if !names.contains(&details.name) {
+                names.push(details.name.clone());
+            }
+        }
+        names
+    }
+
+    // register the given topic as a root topic if it is new, returning its canonical name
+    fn canonical_topic(&mut self, topic: &str) -> String {
+        self.topics
+            .entry(slugify(topic))
+            .or_insert_with(|| TopicDetails {
+                name: topic.trim().to_string(),
+                description: None,
+                parent: None,
+            })
+            .name
+            .clone()
+    }
+
+    // the published cards
+    fn published(&self) -> impl Iterator<Item = &FlashCard> {
+        self.cards.values().filter(|card| card.is_published())
+    }
+
+    // the topic with the given slug, along with its number of published cards
+    fn topic(&self, slug: &str) -> Result<Topic, ApiError> {
+        let details = self
+            .topics
+            .get(slug)
+            .ok_or_else(InMemoryFlashcardRepository::not_found)?;
+        let card_count = self
+            .published()
+            .filter(|card| slugify(&card.topic) == slug)
+            .count();
+
+        Ok(Topic {
+            slug: slug.to_string(),
+            name: details.name.clone(),
+            description: details.description.clone(),
+            parent: details.parent.clone(),
+            card_count: card_count as i64,
+        })
+    }
+
+    // the slugs of the given topic and of all its descendants
+    fn topic_subtree(&self, slug: &str) -> HashSet<String> {
+        let mut subtree = HashSet::from([slug.to_string()]);
+        let mut pending = vec![slug.to_string()];
+        while let Some(parent) = pending.pop() {
+            for (child, details) in &self.topics {
+                if details.parent.as_deref() == Some(parent.as_str())
+                    && subtree.insert(child.clone())
+                {
+                    pending.push(child.clone());
+                }
+            }
+        }
+        subtree
+    }
+
+    // the tag with the given slug, along with its number of published cards
+    fn tag(&self, slug: &str) -> Result<Tag, ApiError> {
+        let details = self
+            .tags
+            .get(slug)
+            .ok_or_else(InMemoryFlashcardRepository::not_found)?;
+        let card_count = self
+            .published()
+            .filter(|card| card.tags.iter().any(|tag| slugify(tag) == slug))
+            .count();
+
+        Ok(Tag {
+            slug: slug.to_string(),
+            name: details.name.clone(),
+            description: details.description.clone(),
+            card_count: card_count as i64,
+        })
+    }
+}
+
+// the Monday (UTC) of the week during which a card was created, formatted as `YYYY-MM-DD`
+fn week_start(created_at: &SqlxTimestamp) -> String {
+    let date = created_at.to_jiff().to_zoned(TimeZone::UTC).date();
+    let offset = date.weekday().to_monday_zero_offset();
+    date.checked_sub(Span::new().days(offset))
+        .unwrap_or(date)
+        .to_string()
+}
+
+// struct type to represent the organizations known to the in-memory backend, along with what
+// each of them holds
+#[derive(Debug, Default)]
+struct Organizations {
+    // organizations, keyed by id
+    organizations: HashMap<Uuid, Organization>,
+    // organization ids, keyed by the hash of their API keys
+    api_keys: HashMap<String, Uuid>,
+    // the state of each organization, keyed by organization id
+    states: HashMap<Uuid, Arc<RwLock<MemoryState>>>,
+}
+
+// struct type to represent the in-memory storage backend, scoped to an organization; clones
+// share the same cards
+#[derive(Clone, Debug)]
+pub struct InMemoryFlashcardRepository {
+    // the state of the organization the repository is scoped to
+    state: Arc<RwLock<MemoryState>>,
+    organizations: Arc<RwLock<Organizations>>,
+}
+
+// implement the Default trait as an empty repository, scoped to the default organization
+impl Default for InMemoryFlashcardRepository {
+    fn default() -> Self {
+        Self::with_cards([])
+    }
+}
+
+// methods for the InMemoryFlashcardRepository type
+impl InMemoryFlashcardRepository {
+    /// Build a repository pre-populated with the given cards, scoped to the default organization.
+    pub fn with_cards(cards: impl IntoIterator<Item = FlashCard>) -> Self {
+        let mut state = MemoryState::default();
+        for mut card in cards {
+            card.topic = state.canonical_topic(&card.topic);
+            card.tags = state.canonical_tags(&card.tags);
+            state.cards.insert(card.id, card);
+        }
+        let state = Arc::new(RwLock::new(state));
+        let default_organization = Organization {
+            id: DEFAULT_ORGANIZATION_ID,
+            slug: "default".to_string(),
+            name: "Default".to_string(),
+            hosts: Vec::new(),
+            created_at: PavexTimestamp::now().to_sqlx(),
+        };
+        let organizations = Organizations {
+            organizations: HashMap::from([(DEFAULT_ORGANIZATION_ID, default_organization)]),
+            api_keys: HashMap::new(),
+            states: HashMap::from([(DEFAULT_ORGANIZATION_ID, state.clone())]),
+        };
+        Self {
+            state,
+            organizations: Arc::new(RwLock::new(organizations)),
+        }
+    }
+
+    /// The same repository, scoped to the given organization.
+    pub fn scoped(&self, organization_id: Uuid) -> Self {
+        let state = self
+            .write_organizations()
+            .states
+            .entry(organization_id)
+            .or_default()
+            .clone();
+        Self {
+            state,
+            organizations: self.organizations.clone(),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, MemoryState> {
+        self.state.read().expect("flash card store lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MemoryState> {
+        self.state.write().expect("flash card store lock poisoned")
+    }
+
+    fn read_organizations(&self) -> RwLockReadGuard<'_, Organizations> {
+        self.organizations
+            .read()
+            .expect("organization store lock poisoned")
+    }
+
+    fn write_organizations(&self) -> RwLockWriteGuard<'_, Organizations> {
+        self.organizations
+            .write()
+            .expect("organization store lock poisoned")
+    }
+
+    // the published cards matching the given predicate, most recent first
+    fn filtered(&self, predicate: impl Fn(&FlashCard) -> bool) -> Vec<FlashCard> {
+        self.filtered_any_status(|card| card.is_published() && predicate(card))
+    }
+
+    // the cards matching the given predicate whatever their status, most recent first
+    fn filtered_any_status(&self, predicate: impl Fn(&FlashCard) -> bool) -> Vec<FlashCard> {
+        let mut matching: Vec<FlashCard> = self
+            .read()
+            .cards
+            .values()
+            .filter(|card| predicate(card))
+            .cloned()
+            .collect();
+        matching.sort_by_key(|card| std::cmp::Reverse(card.created_at.to_jiff()));
+        matching
+    }
+
+    // the error reported for a missing card or tag, mirroring the database backend
+    fn not_found() -> ApiError {
+        ApiError::NotFound("The requested resource does not exist.".to_string())
+    }
+}
+
+// implement the FlashcardRepository trait on top of a map of cards
+impl FlashcardRepository for InMemoryFlashcardRepository {
+    async fn list_flashcards(&self) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(self.filtered(|_| true))
+    }
+
+    async fn list_flashcards_by_topic(
+        &self,
+        topic: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        let slug = slugify(topic);
+        let slugs = if include_descendants {
+            self.read().topic_subtree(&slug)
+        } else {
+            HashSet::from([slug])
+        };
+        Ok(self.filtered(|card| slugs.contains(&slugify(&card.topic))))
+    }
+
+    async fn list_flashcards_by_tag(&self, tag: &str) -> Result<Vec<FlashCard>, ApiError> {
+        let slug = slugify(tag);
+        Ok(self.filtered(|card| card.tags.iter().any(|t| slugify(t) == slug)))
+    }
+
+    async fn get_flashcard(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        self.read()
+            .cards
+            .get(&id)
+            .filter(|card| card.is_published())
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn list_all_flashcards(
+        &self,
+        status: Option<CardStatus>,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(self.filtered_any_status(|card| status.is_none_or(|status| card.status == status)))
+    }
+
+    async fn get_flashcard_any_status(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        self.read()
+            .cards
+            .get(&id)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn review_flashcard(&self, review: &CardReview) -> Result<FlashCard, ApiError> {
+        let mut state = self.write();
+        let card = state
+            .cards
+            .get_mut(&review.flashcard_id)
+            .filter(|card| card.status == review.from_status)
+            .ok_or(ApiError::InvalidStatusTransition {
+                from: review.from_status,
+                to: review.to_status,
+            })?;
+        card.status = review.to_status;
+        card.updated_at = Some(review.reviewed_at);
+        let card = card.clone();
+        state.reviews.push(review.clone());
+
+        Ok(card)
+    }
+
+    async fn list_reviews(&self, id: Uuid) -> Result<Vec<CardReview>, ApiError> {
+        Ok(self
+            .read()
+            .reviews
+            .iter()
+            .filter(|review| review.flashcard_id == id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_flashcard(&self, new_card: &FlashCard) -> Result<FlashCard, ApiError> {
+        self.write().insert_card(new_card)
+    }
+
+    async fn update_flashcard(
+        &self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        self.write().update_card(id, updated_card)
+    }
+
+    async fn delete_flashcard(&self, id: Uuid) -> Result<u64, ApiError> {
+        let mut state = self.write();
+        state.code_verifications.remove(&id);
+        state.reviews.retain(|review| review.flashcard_id != id);
+        state
+            .suggestions
+            .retain(|suggestion| suggestion.flashcard_id != Some(id));
+        state
+            .feedback
+            .retain(|feedback| feedback.flashcard_id != id);
+        state
+            .study_reviews
+            .retain(|review| review.flashcard_id != id);
+        Ok(state.cards.remove(&id).map_or(0, |_| 1))
+    }
+
+    async fn random_flashcards(
+        &self,
+        selection: &RandomSelection,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        let cards = self.filtered(|card| selection.matches(card));
+        let candidates: Vec<(Uuid, i32)> = cards
+            .iter()
+            .map(|card| (card.id, card.difficulty))
+            .collect();
+        let ids = sample_ids(&candidates, selection.count, selection.weighting);
+        let sampled = cards
+            .into_iter()
+            .filter(|card| ids.contains(&card.id))
+            .collect();
+
+        Ok(in_sampled_order(sampled, &ids))
+    }
+
+    async fn daily_flashcard(
+        &self,
+        selection: &DailySelection,
+    ) -> Result<Option<FlashCard>, ApiError> {
+        let scope = selection.scope();
+        let key = (scope.clone(), selection.day_key());
+        let mut state = self.write();
+        let picked = state
+            .daily
+            .get(&key)
+            .and_then(|id| state.cards.get(id))
+            .filter(|card| card.is_published());
+        if let Some(card) = picked {
+            return Ok(Some(card.clone()));
+        }
+
+        let candidates: Vec<Uuid> = state
+            .published()
+            .filter(|card| scope.is_empty() || slugify(&card.topic) == scope)
+            .map(|card| card.id)
+            .collect();
+        let window = (scope.clone(), selection.window_start())..key.clone();
+        let recent: Vec<Uuid> = state.daily.range(window).map(|(_, id)| *id).collect();
+        let Some(id) = selection.pick(&candidates, &recent) else {
+            return Ok(None);
+        };
+        state.daily.insert(key, id);
+
+        Ok(state.cards.get(&id).cloned())
+    }
+
+    async fn create_quiz(&self, quiz: &Quiz) -> Result<Quiz, ApiError> {
+        self.write().quizzes.insert(quiz.id, quiz.clone());
+        Ok(quiz.clone())
+    }
+
+    async fn get_quiz(&self, id: Uuid) -> Result<Quiz, ApiError> {
+        self.read()
+            .quizzes
+            .get(&id)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn delete_quizzes_before(&self, before: SqlxTimestamp) -> Result<u64, ApiError> {
+        let mut state = self.write();
+        let count = state.quizzes.len();
+        state
+            .quizzes
+            .retain(|_, quiz| quiz.created_at.to_jiff() >= before.to_jiff());
+        Ok((count - state.quizzes.len()) as u64)
+    }
+
+    async fn save_code_verification(
+        &self,
+        verification: &CodeVerification,
+    ) -> Result<CodeVerification, ApiError> {
+        let mut state = self.write();
+        if !state.cards.contains_key(&verification.flashcard_id) {
+            return Err(Self::not_found());
+        }
+        state
+            .code_verifications
+            .insert(verification.flashcard_id, verification.clone());
+        Ok(verification.clone())
+    }
+
+    async fn get_code_verification(&self, id: Uuid) -> Result<CodeVerification, ApiError> {
+        self.read()
+            .code_verifications
+            .get(&id)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn create_drafts(
+        &self,
+        drafts: &[FlashcardDraft],
+    ) -> Result<Vec<FlashcardDraft>, ApiError> {
+        let mut state = self.write();
+        let mut created = Vec::with_capacity(drafts.len());
+        for draft in drafts {
+            let is_asked = state
+                .cards
+                .values()
+                .any(|card| card.question == draft.question)
+                || state
+                    .drafts
+                    .values()
+                    .any(|other| other.question == draft.question);
+            if !is_asked {
+                state.drafts.insert(draft.id, draft.clone());
+                created.push(draft.clone());
+            }
+        }
+
+        Ok(created)
+    }
+
+    async fn list_drafts(&self) -> Result<Vec<FlashcardDraft>, ApiError> {
+        let mut drafts: Vec<FlashcardDraft> = self.read().drafts.values().cloned().collect();
+        drafts.sort_by(|a, b| {
+            a.created_at
+                .to_jiff()
+                .cmp(&b.created_at.to_jiff())
+                .then_with(|| a.source.cmp(&b.source))
+        });
+
+        Ok(drafts)
+    }
+
+    async fn get_draft(&self, id: Uuid) -> Result<FlashcardDraft, ApiError> {
+        self.read()
+            .drafts
+            .get(&id)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn delete_draft(&self, id: Uuid) -> Result<u64, ApiError> {
+        Ok(self.write().drafts.remove(&id).map_or(0, |_| 1))
+    }
+
+    async fn create_user(&self, user: &User, token_hash: &str) -> Result<User, ApiError> {
+        self.write()
+            .users
+            .insert(token_hash.to_string(), user.clone());
+        Ok(user.clone())
+    }
+
+    async fn get_user_by_token(&self, token_hash: &str) -> Result<Option<User>, ApiError> {
+        Ok(self.read().users.get(token_hash).cloned())
+    }
+
+    async fn create_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        self.write().suggestions.push(suggestion.clone());
+        Ok(suggestion.clone())
+    }
+
+    async fn list_suggestions(
+        &self,
+        status: Option<SuggestionStatus>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Suggestion>, ApiError> {
+        Ok(self
+            .read()
+            .suggestions
+            .iter()
+            .filter(|suggestion| status.is_none_or(|status| suggestion.status == status))
+            .filter(|suggestion| user_id.is_none_or(|user_id| suggestion.user_id == user_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_suggestion(&self, id: Uuid) -> Result<Suggestion, ApiError> {
+        self.read()
+            .suggestions
+            .iter()
+            .find(|suggestion| suggestion.id == id)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn close_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        let mut state = self.write();
+        let stored = state
+            .suggestions
+            .iter_mut()
+            .find(|stored| stored.id == suggestion.id)
+            .ok_or_else(Self::not_found)?;
+        if stored.status != SuggestionStatus::Pending {
+            return Err(ApiError::SuggestionClosed);
+        }
+        stored.status = suggestion.status;
+        stored.note = suggestion.note.clone();
+        stored.reviewed_at = suggestion.reviewed_at;
+
+        Ok(stored.clone())
+    }
+
+    async fn approve_suggestion(
+        &self,
+        suggestion: &Suggestion,
+        new_card: Option<&FlashCard>,
+    ) -> Result<(Suggestion, FlashCard), ApiError> {
+        // the lock is held throughout, the suggestion is only closed once its change applied
+        let mut state = self.write();
+        let pending = state
+            .suggestions
+            .iter()
+            .find(|stored| stored.id == suggestion.id)
+            .ok_or_else(Self::not_found)?
+            .status
+            == SuggestionStatus::Pending;
+        if !pending {
+            return Err(ApiError::SuggestionClosed);
+        }
+        let card = match (new_card, suggestion.flashcard_id) {
+            (Some(new_card), _) => state.insert_card(new_card)?,
+            (None, Some(id)) => state.update_card(id, &suggestion.changes)?,
+            (None, None) => return Err(Self::not_found()),
+        };
+        let stored = state
+            .suggestions
+            .iter_mut()
+            .find(|stored| stored.id == suggestion.id)
+            .ok_or_else(Self::not_found)?;
+        stored.status = suggestion.status;
+        stored.note = suggestion.note.clone();
+        stored.reviewed_at = suggestion.reviewed_at;
+
+        Ok((stored.clone(), card))
+    }
+
+    async fn create_feedback(
+        &self,
+        feedback: &CardFeedback,
+        limit: u32,
+        since: SqlxTimestamp,
+    ) -> Result<CardFeedback, ApiError> {
+        let mut state = self.write();
+        let count = state
+            .feedback
+            .iter()
+            .filter(|stored| stored.client == feedback.client)
+            .filter(|stored| stored.created_at.to_jiff() >= since.to_jiff())
+            .count();
+        if count >= limit as usize {
+            return Err(ApiError::FeedbackLimitReached);
+        }
+        state.feedback.push(feedback.clone());
+        Ok(feedback.clone())
+    }
+
+    async fn feedback_report(&self) -> Result<Vec<FlaggedCard>, ApiError> {
+        let state = self.read();
+        let mut report: Vec<FlaggedCard> = state
+            .cards
+            .values()
+            .map(|card| {
+                let feedback = state
+                    .feedback
+                    .iter()
+                    .filter(|feedback| feedback.flashcard_id == card.id);
+                FlaggedCard {
+                    flashcard_id: card.id,
+                    question: card.question.clone(),
+                    open_flags: feedback
+                        .clone()
+                        .filter(|feedback| {
+                            feedback.kind.is_flag() && feedback.resolved_at.is_none()
+                        })
+                        .count() as i64,
+                    upvotes: feedback.filter(|feedback| !feedback.kind.is_flag()).count() as i64,
+                }
+            })
+            .filter(|flagged| flagged.open_flags > 0)
+            .collect();
+        report.sort_by(|a, b| {
+            b.open_flags
+                .cmp(&a.open_flags)
+                .then_with(|| a.question.cmp(&b.question))
+        });
+        Ok(report)
+    }
+
+    async fn list_open_flags(&self, flashcard_id: Uuid) -> Result<Vec<CardFeedback>, ApiError> {
+        Ok(self
+            .read()
+            .feedback
+            .iter()
+            .filter(|feedback| feedback.flashcard_id == flashcard_id)
+            .filter(|feedback| feedback.kind.is_flag() && feedback.resolved_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn resolve_flags(
+        &self,
+        flashcard_id: Uuid,
+        resolved_at: SqlxTimestamp,
+    ) -> Result<u64, ApiError> {
+        let mut state = self.write();
+        let mut resolved = 0;
+        for feedback in state.feedback.iter_mut().filter(|feedback| {
+            feedback.flashcard_id == flashcard_id
+                && feedback.kind.is_flag()
+                && feedback.resolved_at.is_none()
+        }) {
+            feedback.resolved_at = Some(resolved_at);
+            resolved += 1;
+        }
+        Ok(resolved)
+    }
+
+    async fn create_study_review(&self, review: &StudyReview) -> Result<StudyReview, ApiError> {
+        self.write().study_reviews.push(review.clone());
+        Ok(review.clone())
+    }
+
+    async fn list_study_reviews(&self, user_id: Uuid) -> Result<Vec<StudyReview>, ApiError> {
+        Ok(self
+            .read()
+            .study_reviews
+            .iter()
+            .filter(|review| review.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, ApiError> {
+        let state = self.read();
+        state.tags.keys().map(|slug| state.tag(slug)).collect()
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>, ApiError> {
+        let mut topics: Vec<String> = self
+            .read()
+            .published()
+            .map(|card| card.topic.clone())
+            .collect();
+        topics.sort();
+        topics.dedup();
+        Ok(topics)
+    }
+
+    async fn update_tag(&self, slug: &str, updated_tag: &UpdatedTag) -> Result<Tag, ApiError> {
+        let mut state = self.write();
+        let mut details = state.tags.get(slug).cloned().ok_or_else(Self::not_found)?;
+        let old_name = details.name.clone();
+
+        if let Some(name) = &updated_tag.name {
+            details.name = name.trim().to_string();
+        }
+        if let Some(description) = &updated_tag.description {
+            details.description = Some(description.clone());
+        }
+
+        let new_slug = slugify(&details.name);
+        if new_slug != slug && state.tags.contains_key(&new_slug) {
+            return Err(ApiError::Conflict("tags_slug_key".to_string()));
+        }
+
+        for card in state.cards.values_mut() {
+            for tag in card.tags.iter_mut().filter(|tag| **tag == old_name) {
+                *tag = details.name.clone();
+            }
+        }
+        state.tags.remove(slug);
+        state.tags.insert(new_slug.clone(), details);
+
+        state.tag(&new_slug)
+    }
+
+    async fn merge_tags(&self, source: &str, target: &str) -> Result<Tag, ApiError> {
+        let mut state = self.write();
+        let source_name = state
+            .tags
+            .get(source)
+            .ok_or_else(Self::not_found)?
+            .name
+            .clone();
+        let target_name = state
+            .tags
+            .get(target)
+            .ok_or_else(Self::not_found)?
+            .name
+            .clone();
+
+        if source != target {
+            for card in state.cards.values_mut() {
+                let Some(position) = card.tags.iter().position(|tag| *tag == source_name) else {
+                    continue;
+                };
+                if card.tags.contains(&target_name) {
+                    card.tags.remove(position);
+                } else {
+                    card.tags[position] = target_name.clone();
+                }
+            }
+            state.tags.remove(source);
+        }
+
+        state.tag(target)
+    }
+
+    async fn flashcard_stats(&self) -> Result<FlashcardStats, ApiError> {
+        let state = self.read();
+
+        let mut topics: Vec<TopicStats> = state
+            .topics
+            .iter()
+            .map(|(slug, details)| {
+                let difficulties: Vec<i32> = state
+                    .published()
+                    .filter(|card| slugify(&card.topic) == *slug)
+                    .map(|card| card.difficulty)
+                    .collect();
+                let average_difficulty = (!difficulties.is_empty()).then(|| {
+                    difficulties.iter().map(|&d| f64::from(d)).sum::<f64>()
+                        / difficulties.len() as f64
+                });
+                TopicStats {
+                    slug: slug.clone(),
+                    name: details.name.clone(),
+                    card_count: difficulties.len() as i64,
+                    average_difficulty,
+                }
+            })
+            .collect();
+        topics.sort_by(|a, b| b.card_count.cmp(&a.card_count).then(a.slug.cmp(&b.slug)));
+
+        let mut tags: Vec<TagStats> = state
+            .tags
+            .keys()
+            .map(|slug| state.tag(slug))
+            .map(|tag| {
+                tag.map(|tag| TagStats {
+                    slug: tag.slug,
+                    name: tag.name,
+                    card_count: tag.card_count,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        tags.sort_by(|a, b| b.card_count.cmp(&a.card_count).then(a.slug.cmp(&b.slug)));
+
+        let mut total = 0;
+        let mut difficulties: BTreeMap<i32, i64> = BTreeMap::new();
+        let mut weeks: BTreeMap<String, i64> = BTreeMap::new();
+        for card in state.published() {
+            total += 1;
+            *difficulties.entry(card.difficulty).or_default() += 1;
+            *weeks.entry(week_start(&card.created_at)).or_default() += 1;
+        }
+
+        Ok(FlashcardStats {
+            total,
+            topics,
+            tags,
+            difficulties: difficulties
+                .into_iter()
+                .map(|(difficulty, card_count)| DifficultyStats {
+                    difficulty,
+                    card_count,
+                })
+                .collect(),
+            weeks: weeks
+                .into_iter()
+                .map(|(week, card_count)| WeekStats { week, card_count })
+                .collect(),
+        })
+    }
+
+    async fn list_topic_details(&self) -> Result<Vec<Topic>, ApiError> {
+        let state = self.read();
+        state.topics.keys().map(|slug| state.topic(slug)).collect()
+    }
+
+    async fn create_topic(&self, new_topic: &NewTopic) -> Result<Topic, ApiError> {
+        let mut state = self.write();
+        let parent = new_topic.parent.as_deref().map(slugify);
+        if parent
+            .as_ref()
+            .is_some_and(|parent| !state.topics.contains_key(parent))
+        {
+            return Err(Self::not_found());
+        }
+        let slug = slugify(&new_topic.name);
+        if state.topics.contains_key(&slug) {
+            return Err(ApiError::Conflict("topics_slug_key".to_string()));
+        }
+
+        state.topics.insert(
+            slug.clone(),
+            TopicDetails {
+                name: new_topic.name.trim().to_string(),
+                description: new_topic.description.clone(),
+                parent,
+            },
+        );
+
+        state.topic(&slug)
+    }
+
+    async fn update_topic(
+        &self,
+        slug: &str,
+        updated_topic: &UpdatedTopic,
+    ) -> Result<Topic, ApiError> {
+        let mut state = self.write();
+        let mut details = state
+            .topics
+            .get(slug)
+            .cloned()
+            .ok_or_else(Self::not_found)?;
+        let old_name = details.name.clone();
+
+        if let Some(parent) = &updated_topic.parent {
+            let parent = parent.as_deref().map(slugify);
+            if let Some(parent) = &parent {
+                if !state.topics.contains_key(parent) {
+                    return Err(Self::not_found());
+                }
+                if state.topic_subtree(slug).contains(parent) {
+                    return Err(topic_cycle_error().into());
+                }
+            }
+            details.parent = parent;
+        }
+        if let Some(name) = &updated_topic.name {
+            details.name = name.trim().to_string();
+        }
+        if let Some(description) = &updated_topic.description {
+            details.description = Some(description.clone());
+        }
+
+        let new_slug = slugify(&details.name);
+        if new_slug != slug && state.topics.contains_key(&new_slug) {
+            return Err(ApiError::Conflict("topics_slug_key".to_string()));
+        }
+
+        for card in state
+            .cards
+            .values_mut()
+            .filter(|card| card.topic == old_name)
+        {
+            card.topic = details.name.clone();
+        }
+        for child in state.topics.values_mut() {
+            if child.parent.as_deref() == Some(slug) {
+                child.parent = Some(new_slug.clone());
+            }
+        }
+        state.topics.remove(slug);
+        state.topics.insert(new_slug.clone(), details);
+
+        state.topic(&new_slug)
+    }
+}
+
+// implement the OrganizationRepository trait on top of a map of organizations
+impl OrganizationRepository for InMemoryFlashcardRepository {
+    async fn create_organization(
+        &self,
+        organization: &Organization,
+        key_hash: &str,
+    ) -> Result<Organization, ApiError> {
+        let mut organizations = self.write_organizations();
+        let taken = |other: &Organization| {
+            if other.slug == organization.slug {
+                Some("organizations_slug_key")
+            } else if other
+                .hosts
+                .iter()
+                .any(|host| organization.hosts.contains(host))
+            {
+                Some("organization_hosts_pkey")
+            } else {
+                None
+            }
+        };
+        if let Some(constraint) = organizations.organizations.values().find_map(taken) {
+            return Err(ApiError::Conflict(constraint.to_string()));
+        }
+        organizations
+            .organizations
+            .insert(organization.id, organization.clone());
+        organizations
+            .api_keys
+            .insert(key_hash.to_string(), organization.id);
+        organizations.states.entry(organization.id).or_default();
+
+        Ok(organization.clone())
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, ApiError> {
+        let mut organizations: Vec<Organization> = self
+            .read_organizations()
+            .organizations
+            .values()
+            .cloned()
+            .collect();
+        organizations.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+        Ok(organizations)
+    }
+
+    async fn get_organization(&self, slug: &str) -> Result<Organization, ApiError> {
+        self.read_organizations()
+            .organizations
+            .values()
+            .find(|organization| organization.slug == slug)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    async fn add_api_key(&self, organization_id: Uuid, key_hash: &str) -> Result<(), ApiError> {
+        let mut organizations = self.write_organizations();
+        if !organizations.organizations.contains_key(&organization_id) {
+            return Err(Self::not_found());
+        }
+        organizations
+            .api_keys
+            .insert(key_hash.to_string(), organization_id);
+
+        Ok(())
+    }
+
+    async fn find_organization_by_api_key(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        let organizations = self.read_organizations();
+        Ok(organizations
+            .api_keys
+            .get(key_hash)
+            .and_then(|id| organizations.organizations.get(id))
+            .cloned())
+    }
+
+    async fn find_organization_by_host(
+        &self,
+        host: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        Ok(self
+            .read_organizations()
+            .organizations
+            .values()
+            .find(|organization| organization.hosts.iter().any(|known| known == host))
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CardKind, DifficultyWeighting, FeedbackKind, NewFeedback, NewFlashCard, NewOrganization,
+    };
+
+    // a published card
+    fn card(question: &str, topic: &str, tags: &[&str]) -> FlashCard {
+        FlashCard {
+            status: CardStatus::Published,
+            ..FlashCard::try_from(NewFlashCard {
+                question: question.to_string(),
+                answer: "an answer".to_string(),
+                topic: topic.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                difficulty: 1,
+                kind: CardKind::FreeText,
+            })
+            .unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn duplicate_questions_are_rejected() {
+        let repo = InMemoryFlashcardRepository::default();
+        repo.create_flashcard(&card("What is Rust?", "intro", &["basics"]))
+            .await
+            .unwrap();
+
+        let result = repo
+            .create_flashcard(&card("What is Rust?", "intro", &["basics"]))
+            .await;
+
+        assert!(matches!(result, Err(ApiError::DuplicateQuestion)));
+    }
+
+    #[tokio::test]
+    async fn topics_match_case_insensitively() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "Memory", &["ownership"]),
+            card("What is a trait?", "traits", &["traits"]),
+        ]);
+
+        let cards = repo
+            .list_flashcards_by_topic("memory", false)
+            .await
+            .unwrap();
+
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].question, "What is ownership?");
+    }
+
+    #[tokio::test]
+    async fn updates_keep_missing_fields() {
+        let original = card("What is Rust?", "intro", &["basics"]);
+        let repo = InMemoryFlashcardRepository::with_cards([original.clone()]);
+        let update = UpdatedFlashCard {
+            answer: Some("A fast language.".to_string()),
+            ..Default::default()
+        };
+
+        let updated = repo.update_flashcard(original.id, &update).await.unwrap();
+
+        assert_eq!(updated.question, original.question);
+        assert_eq!(updated.answer, "A fast language.");
+        assert!(updated.updated_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn tags_are_listed_with_their_usage_counts() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["ownership", "basics"]),
+            card("What is borrowing?", "memory", &["borrowing", "Basics"]),
+        ]);
+
+        let tags = repo.list_tags().await.unwrap();
+        let counts: Vec<(&str, i64)> = tags
+            .iter()
+            .map(|tag| (tag.slug.as_str(), tag.card_count))
+            .collect();
+
+        assert_eq!(
+            counts,
+            vec![("basics", 2), ("borrowing", 1), ("ownership", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn renaming_a_tag_applies_to_every_card() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["memory"]),
+            card("What is borrowing?", "memory", &["memory"]),
+        ]);
+        let update = UpdatedTag {
+            name: Some("Memory Safety".to_string()),
+            description: Some("How Rust manages memory".to_string()),
+        };
+
+        let tag = repo.update_tag("memory", &update).await.unwrap();
+
+        assert_eq!(tag.slug, "memory-safety");
+        assert_eq!(tag.card_count, 2);
+        let cards = repo.list_flashcards_by_tag("memory-safety").await.unwrap();
+        assert!(cards.iter().all(|card| card.tags == vec!["Memory Safety"]));
+    }
+
+    #[tokio::test]
+    async fn merging_tags_moves_every_card_to_the_target() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["ownership", "memory"]),
+            card("What is borrowing?", "memory", &["ownership"]),
+        ]);
+
+        let tag = repo.merge_tags("ownership", "memory").await.unwrap();
+
+        assert_eq!(tag.card_count, 2);
+        let slugs: Vec<String> = repo
+            .list_tags()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.slug)
+            .collect();
+        assert_eq!(slugs, vec!["memory"]);
+    }
+
+    #[tokio::test]
+    async fn topic_filters_can_include_descendants() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "ownership", &["basics"]),
+            card("What is borrowing?", "borrowing", &["basics"]),
+            card("What is a lifetime?", "lifetimes", &["basics"]),
+        ]);
+        for (topic, parent) in [("borrowing", "ownership"), ("lifetimes", "borrowing")] {
+            let update = UpdatedTopic {
+                parent: Some(Some(parent.to_string())),
+                ..Default::default()
+            };
+            repo.update_topic(topic, &update).await.unwrap();
+        }
+
+        let direct = repo
+            .list_flashcards_by_topic("ownership", false)
+            .await
+            .unwrap();
+        let nested = repo
+            .list_flashcards_by_topic("ownership", true)
+            .await
+            .unwrap();
+
+        assert_eq!(direct.len(), 1);
+        assert_eq!(nested.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn topics_cannot_be_moved_under_their_descendants() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "ownership", &["basics"]),
+            card("What is borrowing?", "borrowing", &["basics"]),
+        ]);
+        let nest = UpdatedTopic {
+            parent: Some(Some("ownership".to_string())),
+            ..Default::default()
+        };
+        repo.update_topic("borrowing", &nest).await.unwrap();
+        let cycle = UpdatedTopic {
+            parent: Some(Some("borrowing".to_string())),
+            ..Default::default()
+        };
+
+        let result = repo.update_topic("ownership", &cycle).await;
+
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn stats_count_cards_per_topic_tag_and_difficulty() {
+        let mut hard = card("What is a lifetime?", "memory", &["lifetimes"]);
+        hard.difficulty = 4;
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["ownership"]),
+            hard,
+            card("What is a trait?", "traits", &["ownership"]),
+        ]);
+
+        let stats = repo.flashcard_stats().await.unwrap();
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.topics[0].slug, "memory");
+        assert_eq!(stats.topics[0].card_count, 2);
+        assert_eq!(stats.topics[0].average_difficulty, Some(2.5));
+        assert_eq!(stats.tags[0].slug, "ownership");
+        assert_eq!(stats.tags[0].card_count, 2);
+        let difficulties: Vec<(i32, i64)> = stats
+            .difficulties
+            .iter()
+            .map(|d| (d.difficulty, d.card_count))
+            .collect();
+        assert_eq!(difficulties, vec![(1, 2), (4, 1)]);
+        assert_eq!(stats.weeks.iter().map(|w| w.card_count).sum::<i64>(), 3);
+    }
+
+    #[tokio::test]
+    async fn random_cards_are_distinct_and_match_the_selection() {
+        let mut hard = card("What is a lifetime?", "memory", &["lifetimes"]);
+        hard.difficulty = 4;
+        let seen = card("What is borrowing?", "memory", &["ownership"]);
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["ownership"]),
+            hard,
+            seen.clone(),
+            card("What is a trait?", "traits", &["ownership"]),
+        ]);
+        let selection = RandomSelection {
+            topic: Some("Memory".to_string()),
+            tags: vec!["ownership".to_string(), "lifetimes".to_string()],
+            max_difficulty: 3,
+            count: 5,
+            exclude: vec![seen.id],
+            ..Default::default()
+        };
+
+        let cards = repo.random_flashcards(&selection).await.unwrap();
+
+        let questions: Vec<&str> = cards.iter().map(|c| c.question.as_str()).collect();
+        assert_eq!(questions, vec!["What is ownership?"]);
+    }
+
+    #[tokio::test]
+    async fn weighted_random_cards_never_repeat() {
+        let repo = InMemoryFlashcardRepository::with_cards((1..=5).map(|difficulty| {
+            let mut card = card(&format!("Question {}?", difficulty), "intro", &["basics"]);
+            card.difficulty = difficulty;
+            card
+        }));
+        let selection = RandomSelection {
+            count: 5,
+            weighting: Some(DifficultyWeighting::Harder),
+            ..Default::default()
+        };
+
+        let cards = repo.random_flashcards(&selection).await.unwrap();
+
+        let ids: HashSet<Uuid> = cards.iter().map(|c| c.id).collect();
+        assert_eq!(cards.len(), 5);
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn the_card_of_the_day_does_not_repeat_within_the_window() {
+        let repo = InMemoryFlashcardRepository::with_cards([
+            card("What is ownership?", "memory", &["ownership"]),
+            card("What is a lifetime?", "memory", &["lifetimes"]),
+            card("What is a trait?", "traits", &["traits"]),
+        ]);
+        let day = pavex::time::civil::Date::new(2026, 10, 19).unwrap();
+        let selection = |day, topic: Option<&str>| DailySelection {
+            day,
+            topic: topic.map(str::to_string),
+            repeat_window: 1,
+        };
+
+        let today = repo.daily_flashcard(&selection(day, None)).await.unwrap();
+        let again = repo.daily_flashcard(&selection(day, None)).await.unwrap();
+        let tomorrow = repo
+            .daily_flashcard(&selection(day.tomorrow().unwrap(), None))
+            .await
+            .unwrap();
+        let memory = repo
+            .daily_flashcard(&selection(day, Some("Memory")))
+            .await
+            .unwrap();
+
+        assert_eq!(today, again);
+        assert_ne!(today.unwrap().id, tomorrow.unwrap().id);
+        assert_eq!(memory.unwrap().topic, "memory");
+    }
+
+    #[tokio::test]
+    async fn only_published_cards_are_listed() {
+        let draft = FlashCard {
+            status: CardStatus::Draft,
+            ..card("What is a draft?", "intro", &["drafts"])
+        };
+        let repo = InMemoryFlashcardRepository::with_cards([
+            draft.clone(),
+            card("What is Rust?", "intro", &["basics"]),
+        ]);
+
+        assert_eq!(repo.list_flashcards().await.unwrap().len(), 1);
+        assert!(repo.get_flashcard(draft.id).await.is_err());
+        assert!(
+            repo.list_flashcards_by_tag("drafts")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let submit = CardReview::of(&draft, CardStatus::InReview, None).unwrap();
+        let submitted = repo.review_flashcard(&submit).await.unwrap();
+        let approve = CardReview::of(&submitted, CardStatus::Published, Some(" Clear ")).unwrap();
+        repo.review_flashcard(&approve).await.unwrap();
+
+        assert_eq!(repo.get_flashcard(draft.id).await.unwrap().id, draft.id);
+        let reviews = repo.list_reviews(draft.id).await.unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[1].note.as_deref(), Some("Clear"));
+        // the same review can't be applied twice
+        assert!(matches!(
+            repo.review_flashcard(&approve).await,
+            Err(ApiError::InvalidStatusTransition { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn the_feedback_report_lists_the_most_flagged_cards_first() {
+        let once = card("What is a trait?", "traits", &["traits"]);
+        let twice = card("What is ownership?", "memory", &["ownership"]);
+        let upvoted = card("What is a slice?", "memory", &["slices"]);
+        let repo =
+            InMemoryFlashcardRepository::with_cards([once.clone(), twice.clone(), upvoted.clone()]);
+        let feedback = |card: &FlashCard, kind| {
+            CardFeedback::new(
+                card.id,
+                "client",
+                &NewFeedback {
+                    kind,
+                    comment: None,
+                },
+            )
+        };
+        for feedback in [
+            feedback(&once, FeedbackKind::Unclear),
+            feedback(&twice, FeedbackKind::WrongAnswer),
+            feedback(&twice, FeedbackKind::Outdated),
+            feedback(&twice, FeedbackKind::Upvote),
+            feedback(&upvoted, FeedbackKind::Upvote),
+        ] {
+            repo.create_feedback(&feedback, 10, feedback.created_at)
+                .await
+                .unwrap();
+        }
+
+        let report = repo.feedback_report().await.unwrap();
+        repo.resolve_flags(once.id, PavexTimestamp::now().to_sqlx())
+            .await
+            .unwrap();
+        let resolved_report = repo.feedback_report().await.unwrap();
+
+        let flagged: Vec<Uuid> = report.iter().map(|flagged| flagged.flashcard_id).collect();
+        assert_eq!(flagged, vec![twice.id, once.id]);
+        assert_eq!((report[0].open_flags, report[0].upvotes), (2, 1));
+        assert_eq!(resolved_report.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn organizations_only_see_their_own_cards() {
+        let repo = InMemoryFlashcardRepository::with_cards([card(
+            "What is ownership?",
+            "memory",
+            &["ownership"],
+        )]);
+        let organization = Organization::new(&NewOrganization {
+            name: "Platform team".to_string(),
+            hosts: vec!["Flash.Platform.example.com".to_string()],
+        });
+        repo.create_organization(&organization, "key hash")
+            .await
+            .unwrap();
+        let team = repo.scoped(organization.id);
+
+        team.create_flashcard(&card("What is ownership?", "memory", &["ownership"]))
+            .await
+            .unwrap();
+        team.create_flashcard(&card("What is a pod?", "kubernetes", &["pods"]))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.list_flashcards().await.unwrap().len(), 1);
+        assert_eq!(team.list_flashcards().await.unwrap().len(), 2);
+        assert_eq!(
+            repo.scoped(organization.id)
+                .list_tags()
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let found = repo
+            .find_organization_by_host("flash.platform.example.com")
+            .await
+            .unwrap();
+        assert_eq!(
+            found.map(|found| found.slug),
+            Some("platform-team".to_string())
+        );
+        let found = repo.find_organization_by_api_key("key hash").await.unwrap();
+        assert_eq!(found.map(|found| found.id), Some(organization.id));
+    }
+}
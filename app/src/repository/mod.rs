@@ -0,0 +1,950 @@
+// app/src/repository/mod.rs
+
+// the storage layer of the API; handlers talk to a `FlashcardRepository` rather than
+// to the database directly, so that the backend can be swapped out
+
+// modules into scope
+pub mod memory;
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+// dependencies
+use crate::configuration::{DatabaseConfig, StorageBackend};
+use crate::errors::ApiError;
+use crate::models::{
+    CardFeedback, CardReview, CardStatus, CodeVerification, DailySelection, DifficultyWeighting,
+    FlaggedCard, FlashCard, FlashcardDraft, FlashcardStats, NewTopic, Organization, Quiz,
+    RandomSelection, StudyReview, Suggestion, SuggestionStatus, Tag, Topic, UpdatedFlashCard,
+    UpdatedTag, UpdatedTopic, User,
+};
+use crate::tenancy::Tenant;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use rand::seq::{IndexedRandom, SliceRandom};
+use std::future::Future;
+use uuid::Uuid;
+
+// re-exports
+pub use memory::InMemoryFlashcardRepository;
+pub use postgres::PostgresFlashcardRepository;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteFlashcardRepository;
+
+/// The operations every flash card storage backend must support.
+///
+/// The listings and lookups meant for learners only see published cards; the review workflow
+/// goes through `list_all_flashcards` and `get_flashcard_any_status`. A repository is scoped to
+/// an organization, and never sees the content of the others.
+pub trait FlashcardRepository: Send + Sync {
+    /// All the published flash cards, most recent first.
+    fn list_flashcards(&self) -> impl Future<Output = Result<Vec<FlashCard>, ApiError>> + Send;
+
+    /// The published flash cards filed under the given topic (matched on its slug), most recent
+    /// first; with `include_descendants`, the cards filed under its subtopics are included too.
+    fn list_flashcards_by_topic(
+        &self,
+        topic: &str,
+        include_descendants: bool,
+    ) -> impl Future<Output = Result<Vec<FlashCard>, ApiError>> + Send;
+
+    /// The published flash cards carrying the given tag (matched on its slug), most recent first.
+    fn list_flashcards_by_tag(
+        &self,
+        tag: &str,
+    ) -> impl Future<Output = Result<Vec<FlashCard>, ApiError>> + Send;
+
+    /// A single published flash card, `ApiError::NotFound` if there is no such card.
+    fn get_flashcard(&self, id: Uuid) -> impl Future<Output = Result<FlashCard, ApiError>> + Send;
+
+    /// Every flash card whatever its status, or only those in the given status, most recent first.
+    fn list_all_flashcards(
+        &self,
+        status: Option<CardStatus>,
+    ) -> impl Future<Output = Result<Vec<FlashCard>, ApiError>> + Send;
+
+    /// A single flash card whatever its status, `ApiError::NotFound` if there is no card with
+    /// this id.
+    fn get_flashcard_any_status(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<FlashCard, ApiError>> + Send;
+
+    /// Move a flash card to the status of the review and record the review,
+    /// `ApiError::InvalidStatusTransition` if the card is no longer in the status the review
+    /// moves it from.
+    fn review_flashcard(
+        &self,
+        review: &CardReview,
+    ) -> impl Future<Output = Result<FlashCard, ApiError>> + Send;
+
+    /// The reviews of a flash card, oldest first.
+    fn list_reviews(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Vec<CardReview>, ApiError>> + Send;
+
+    /// Store a new flash card, `ApiError::DuplicateQuestion` if the question is taken.
+    fn create_flashcard(
+        &self,
+        new_card: &FlashCard,
+    ) -> impl Future<Output = Result<FlashCard, ApiError>> + Send;
+
+    /// Update the fields present in `updated_card`, leaving the others untouched.
+    fn update_flashcard(
+        &self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> impl Future<Output = Result<FlashCard, ApiError>> + Send;
+
+    /// Delete a flash card, returning the number of deleted cards.
+    fn delete_flashcard(&self, id: Uuid) -> impl Future<Output = Result<u64, ApiError>> + Send;
+
+    /// Up to `selection.count` distinct random published flash cards matching the selection;
+    /// empty if no card matches.
+    fn random_flashcards(
+        &self,
+        selection: &RandomSelection,
+    ) -> impl Future<Output = Result<Vec<FlashCard>, ApiError>> + Send;
+
+    /// The card of the day for the selection, picking and recording it on the first request of
+    /// the day; `None` if there are no cards to pick from.
+    fn daily_flashcard(
+        &self,
+        selection: &DailySelection,
+    ) -> impl Future<Output = Result<Option<FlashCard>, ApiError>> + Send;
+
+    /// Store a quiz, along with its questions and answer key.
+    fn create_quiz(&self, quiz: &Quiz) -> impl Future<Output = Result<Quiz, ApiError>> + Send;
+
+    /// A single quiz, `ApiError::NotFound` if there is no quiz with this id.
+    fn get_quiz(&self, id: Uuid) -> impl Future<Output = Result<Quiz, ApiError>> + Send;
+
+    /// Delete the quizzes built before the given time, along with their questions; returns the
+    /// number of quizzes deleted.
+    fn delete_quizzes_before(
+        &self,
+        before: SqlxTimestamp,
+    ) -> impl Future<Output = Result<u64, ApiError>> + Send;
+
+    /// Store the outcome of compile-checking the snippets of a card, replacing the previous one.
+    fn save_code_verification(
+        &self,
+        verification: &CodeVerification,
+    ) -> impl Future<Output = Result<CodeVerification, ApiError>> + Send;
+
+    /// The last compile-check of the snippets of a card, `ApiError::NotFound` if the card was
+    /// never checked.
+    fn get_code_verification(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<CodeVerification, ApiError>> + Send;
+
+    /// Store draft cards, skipping those whose question is already asked by a draft or a card;
+    /// returns the drafts stored.
+    fn create_drafts(
+        &self,
+        drafts: &[FlashcardDraft],
+    ) -> impl Future<Output = Result<Vec<FlashcardDraft>, ApiError>> + Send;
+
+    /// Every draft card, oldest first, then by source.
+    fn list_drafts(&self) -> impl Future<Output = Result<Vec<FlashcardDraft>, ApiError>> + Send;
+
+    /// A single draft card, `ApiError::NotFound` if there is no draft with this id.
+    fn get_draft(&self, id: Uuid) -> impl Future<Output = Result<FlashcardDraft, ApiError>> + Send;
+
+    /// Delete a draft card, returning the number of drafts deleted.
+    fn delete_draft(&self, id: Uuid) -> impl Future<Output = Result<u64, ApiError>> + Send;
+
+    /// Register a user, along with the hash of the token authenticating them.
+    fn create_user(
+        &self,
+        user: &User,
+        token_hash: &str,
+    ) -> impl Future<Output = Result<User, ApiError>> + Send;
+
+    /// The user authenticated by the token with the given hash, if any.
+    fn get_user_by_token(
+        &self,
+        token_hash: &str,
+    ) -> impl Future<Output = Result<Option<User>, ApiError>> + Send;
+
+    /// Store a suggestion made by a user.
+    fn create_suggestion(
+        &self,
+        suggestion: &Suggestion,
+    ) -> impl Future<Output = Result<Suggestion, ApiError>> + Send;
+
+    /// The suggestions in the given status and/or made by the given user, oldest first.
+    fn list_suggestions(
+        &self,
+        status: Option<SuggestionStatus>,
+        user_id: Option<Uuid>,
+    ) -> impl Future<Output = Result<Vec<Suggestion>, ApiError>> + Send;
+
+    /// A single suggestion, `ApiError::NotFound` if there is no suggestion with this id.
+    fn get_suggestion(&self, id: Uuid)
+    -> impl Future<Output = Result<Suggestion, ApiError>> + Send;
+
+    /// Record the decision of a moderator on a pending suggestion,
+    /// `ApiError::SuggestionClosed` if it was already moderated.
+    fn close_suggestion(
+        &self,
+        suggestion: &Suggestion,
+    ) -> impl Future<Output = Result<Suggestion, ApiError>> + Send;
+
+    /// Record the approval of a pending suggestion and apply it, all or nothing: `new_card` is
+    /// created for a suggested new card, the changes of a suggested edit go to its card
+    /// otherwise. `ApiError::SuggestionClosed` if it was already moderated, nothing being
+    /// applied then.
+    fn approve_suggestion(
+        &self,
+        suggestion: &Suggestion,
+        new_card: Option<&FlashCard>,
+    ) -> impl Future<Output = Result<(Suggestion, FlashCard), ApiError>> + Send;
+
+    /// Store feedback left on a card, provided its client left fewer than `limit` entries since
+    /// the given time, `ApiError::FeedbackLimitReached` otherwise; the limit holds for
+    /// concurrent requests too.
+    fn create_feedback(
+        &self,
+        feedback: &CardFeedback,
+        limit: u32,
+        since: SqlxTimestamp,
+    ) -> impl Future<Output = Result<CardFeedback, ApiError>> + Send;
+
+    /// The cards with open flags, the most flagged first.
+    fn feedback_report(&self) -> impl Future<Output = Result<Vec<FlaggedCard>, ApiError>> + Send;
+
+    /// The open flags of a card, oldest first.
+    fn list_open_flags(
+        &self,
+        flashcard_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<CardFeedback>, ApiError>> + Send;
+
+    /// Resolve the open flags of a card, returning the number of flags resolved.
+    fn resolve_flags(
+        &self,
+        flashcard_id: Uuid,
+        resolved_at: SqlxTimestamp,
+    ) -> impl Future<Output = Result<u64, ApiError>> + Send;
+
+    /// Record a card studied by a user.
+    fn create_study_review(
+        &self,
+        review: &StudyReview,
+    ) -> impl Future<Output = Result<StudyReview, ApiError>> + Send;
+
+    /// The cards studied by a user, oldest first.
+    fn list_study_reviews(
+        &self,
+        user_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<StudyReview>, ApiError>> + Send;
+
+    /// Every tag with the number of published cards carrying it, sorted by slug.
+    fn list_tags(&self) -> impl Future<Output = Result<Vec<Tag>, ApiError>> + Send;
+
+    /// Rename and/or describe a tag, across every flash card carrying it.
+    fn update_tag(
+        &self,
+        slug: &str,
+        updated_tag: &UpdatedTag,
+    ) -> impl Future<Output = Result<Tag, ApiError>> + Send;
+
+    /// Merge the `source` tag into the `target` tag, atomically, deleting the source tag.
+    fn merge_tags(
+        &self,
+        source: &str,
+        target: &str,
+    ) -> impl Future<Output = Result<Tag, ApiError>> + Send;
+
+    /// Every distinct topic in use by published cards, sorted alphabetically.
+    fn list_topics(&self) -> impl Future<Output = Result<Vec<String>, ApiError>> + Send;
+
+    /// Aggregate statistics over the published cards: totals per topic, tag, difficulty and
+    /// creation week.
+    fn flashcard_stats(&self) -> impl Future<Output = Result<FlashcardStats, ApiError>> + Send;
+
+    /// Every topic with its parent and number of published cards, sorted by slug.
+    fn list_topic_details(&self) -> impl Future<Output = Result<Vec<Topic>, ApiError>> + Send;
+
+    /// Create a topic, nested under its parent if it has one.
+    fn create_topic(
+        &self,
+        new_topic: &NewTopic,
+    ) -> impl Future<Output = Result<Topic, ApiError>> + Send;
+
+    /// Rename, describe and/or move a topic; moving a topic under itself or one of its
+    /// descendants is a validation error.
+    fn update_topic(
+        &self,
+        slug: &str,
+        updated_topic: &UpdatedTopic,
+    ) -> impl Future<Output = Result<Topic, ApiError>> + Send;
+}
+
+/// The operations on the organizations sharing the instance, which every storage backend must
+/// support; unlike the `FlashcardRepository` ones, they aren't scoped to an organization.
+pub trait OrganizationRepository: Send + Sync {
+    /// Create an organization, along with its first API key, given its hash; a slug or a host
+    /// already taken is an `ApiError::Conflict`.
+    fn create_organization(
+        &self,
+        organization: &Organization,
+        key_hash: &str,
+    ) -> impl Future<Output = Result<Organization, ApiError>> + Send;
+
+    /// Every organization, the default one included, sorted by slug.
+    fn list_organizations(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Organization>, ApiError>> + Send;
+
+    /// A single organization, given its slug.
+    fn get_organization(
+        &self,
+        slug: &str,
+    ) -> impl Future<Output = Result<Organization, ApiError>> + Send;
+
+    /// Add an API key to an organization, given its hash.
+    fn add_api_key(
+        &self,
+        organization_id: Uuid,
+        key_hash: &str,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send;
+
+    /// The organization owning the API key with the given hash, if any.
+    fn find_organization_by_api_key(
+        &self,
+        key_hash: &str,
+    ) -> impl Future<Output = Result<Option<Organization>, ApiError>> + Send;
+
+    /// The organization served at the given host, if any.
+    fn find_organization_by_host(
+        &self,
+        host: &str,
+    ) -> impl Future<Output = Result<Option<Organization>, ApiError>> + Send;
+}
+
+/// The storage backend selected in the configuration, scoped to an organization.
+///
+/// Handlers receive this type, scoped to the organization of the request; it forwards every call
+/// to the selected backend.
+#[derive(Clone, Debug)]
+pub enum FlashcardStore {
+    Postgres(PostgresFlashcardRepository),
+    InMemory(InMemoryFlashcardRepository),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteFlashcardRepository),
+}
+
+// methods for the FlashcardStore type
+impl FlashcardStore {
+    /// Build the store for the backend selected in the database configuration; only the
+    /// Postgres backend builds a connection pool, so the others run without a Postgres server.
+    pub async fn new(config: &DatabaseConfig) -> Result<Self, sqlx::Error> {
+        let store = match config.backend {
+            StorageBackend::Postgres => {
+                FlashcardStore::Postgres(PostgresFlashcardRepository::new(config.get_pool().await))
+            }
+            StorageBackend::Memory => {
+                FlashcardStore::InMemory(InMemoryFlashcardRepository::default())
+            }
+            #[cfg(feature = "sqlite")]
+            StorageBackend::Sqlite => {
+                FlashcardStore::Sqlite(SqliteFlashcardRepository::connect(config).await?)
+            }
+        };
+
+        Ok(store)
+    }
+
+    /// Request-scoped constructor for the store of the organization the request is made for.
+    pub fn for_tenant(shared: &SharedStore, tenant: &Tenant) -> Self {
+        shared.scoped(tenant.organization_id)
+    }
+
+    /// The same storage, scoped to the given organization.
+    pub fn scoped(&self, organization_id: Uuid) -> Self {
+        match self {
+            FlashcardStore::Postgres(repo) => {
+                FlashcardStore::Postgres(repo.scoped(organization_id))
+            }
+            FlashcardStore::InMemory(repo) => {
+                FlashcardStore::InMemory(repo.scoped(organization_id))
+            }
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => FlashcardStore::Sqlite(repo.scoped(organization_id)),
+        }
+    }
+}
+
+/// The storage shared by every organization, built once when the application starts.
+///
+/// Requests work on a `FlashcardStore` scoped to their organization; the organizations
+/// themselves are managed through this type.
+#[derive(Clone, Debug)]
+pub struct SharedStore(FlashcardStore);
+
+// methods for the SharedStore type
+impl SharedStore {
+    /// Build the storage for the backend selected in the database configuration.
+    pub async fn new(config: &DatabaseConfig) -> Result<Self, sqlx::Error> {
+        Ok(Self(FlashcardStore::new(config).await?))
+    }
+
+    /// The store of the given organization.
+    pub fn scoped(&self, organization_id: Uuid) -> FlashcardStore {
+        self.0.scoped(organization_id)
+    }
+}
+
+// implement the From trait to share an existing store, e.g. an in-memory one in unit tests
+impl From<FlashcardStore> for SharedStore {
+    fn from(store: FlashcardStore) -> Self {
+        Self(store)
+    }
+}
+
+// forward every operation on the organizations to the selected backend
+impl OrganizationRepository for SharedStore {
+    async fn create_organization(
+        &self,
+        organization: &Organization,
+        key_hash: &str,
+    ) -> Result<Organization, ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => {
+                repo.create_organization(organization, key_hash).await
+            }
+            FlashcardStore::InMemory(repo) => {
+                repo.create_organization(organization, key_hash).await
+            }
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_organization(organization, key_hash).await,
+        }
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => repo.list_organizations().await,
+            FlashcardStore::InMemory(repo) => repo.list_organizations().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_organizations().await,
+        }
+    }
+
+    async fn get_organization(&self, slug: &str) -> Result<Organization, ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => repo.get_organization(slug).await,
+            FlashcardStore::InMemory(repo) => repo.get_organization(slug).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_organization(slug).await,
+        }
+    }
+
+    async fn add_api_key(&self, organization_id: Uuid, key_hash: &str) -> Result<(), ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => repo.add_api_key(organization_id, key_hash).await,
+            FlashcardStore::InMemory(repo) => repo.add_api_key(organization_id, key_hash).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.add_api_key(organization_id, key_hash).await,
+        }
+    }
+
+    async fn find_organization_by_api_key(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => repo.find_organization_by_api_key(key_hash).await,
+            FlashcardStore::InMemory(repo) => repo.find_organization_by_api_key(key_hash).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.find_organization_by_api_key(key_hash).await,
+        }
+    }
+
+    async fn find_organization_by_host(
+        &self,
+        host: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        match &self.0 {
+            FlashcardStore::Postgres(repo) => repo.find_organization_by_host(host).await,
+            FlashcardStore::InMemory(repo) => repo.find_organization_by_host(host).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.find_organization_by_host(host).await,
+        }
+    }
+}
+
+// forward every operation to the selected backend
+impl FlashcardRepository for FlashcardStore {
+    async fn list_flashcards(&self) -> Result<Vec<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_flashcards().await,
+            FlashcardStore::InMemory(repo) => repo.list_flashcards().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_flashcards().await,
+        }
+    }
+
+    async fn list_flashcards_by_topic(
+        &self,
+        topic: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => {
+                repo.list_flashcards_by_topic(topic, include_descendants)
+                    .await
+            }
+            FlashcardStore::InMemory(repo) => {
+                repo.list_flashcards_by_topic(topic, include_descendants)
+                    .await
+            }
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => {
+                repo.list_flashcards_by_topic(topic, include_descendants)
+                    .await
+            }
+        }
+    }
+
+    async fn list_flashcards_by_tag(&self, tag: &str) -> Result<Vec<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_flashcards_by_tag(tag).await,
+            FlashcardStore::InMemory(repo) => repo.list_flashcards_by_tag(tag).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_flashcards_by_tag(tag).await,
+        }
+    }
+
+    async fn get_flashcard(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_flashcard(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_flashcard(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_flashcard(id).await,
+        }
+    }
+
+    async fn list_all_flashcards(
+        &self,
+        status: Option<CardStatus>,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_all_flashcards(status).await,
+            FlashcardStore::InMemory(repo) => repo.list_all_flashcards(status).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_all_flashcards(status).await,
+        }
+    }
+
+    async fn get_flashcard_any_status(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_flashcard_any_status(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_flashcard_any_status(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_flashcard_any_status(id).await,
+        }
+    }
+
+    async fn review_flashcard(&self, review: &CardReview) -> Result<FlashCard, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.review_flashcard(review).await,
+            FlashcardStore::InMemory(repo) => repo.review_flashcard(review).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.review_flashcard(review).await,
+        }
+    }
+
+    async fn list_reviews(&self, id: Uuid) -> Result<Vec<CardReview>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_reviews(id).await,
+            FlashcardStore::InMemory(repo) => repo.list_reviews(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_reviews(id).await,
+        }
+    }
+
+    async fn create_flashcard(&self, new_card: &FlashCard) -> Result<FlashCard, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_flashcard(new_card).await,
+            FlashcardStore::InMemory(repo) => repo.create_flashcard(new_card).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_flashcard(new_card).await,
+        }
+    }
+
+    async fn update_flashcard(
+        &self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.update_flashcard(id, updated_card).await,
+            FlashcardStore::InMemory(repo) => repo.update_flashcard(id, updated_card).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.update_flashcard(id, updated_card).await,
+        }
+    }
+
+    async fn delete_flashcard(&self, id: Uuid) -> Result<u64, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.delete_flashcard(id).await,
+            FlashcardStore::InMemory(repo) => repo.delete_flashcard(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.delete_flashcard(id).await,
+        }
+    }
+
+    async fn random_flashcards(
+        &self,
+        selection: &RandomSelection,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.random_flashcards(selection).await,
+            FlashcardStore::InMemory(repo) => repo.random_flashcards(selection).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.random_flashcards(selection).await,
+        }
+    }
+
+    async fn daily_flashcard(
+        &self,
+        selection: &DailySelection,
+    ) -> Result<Option<FlashCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.daily_flashcard(selection).await,
+            FlashcardStore::InMemory(repo) => repo.daily_flashcard(selection).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.daily_flashcard(selection).await,
+        }
+    }
+
+    async fn create_quiz(&self, quiz: &Quiz) -> Result<Quiz, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_quiz(quiz).await,
+            FlashcardStore::InMemory(repo) => repo.create_quiz(quiz).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_quiz(quiz).await,
+        }
+    }
+
+    async fn get_quiz(&self, id: Uuid) -> Result<Quiz, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_quiz(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_quiz(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_quiz(id).await,
+        }
+    }
+
+    async fn delete_quizzes_before(&self, before: SqlxTimestamp) -> Result<u64, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.delete_quizzes_before(before).await,
+            FlashcardStore::InMemory(repo) => repo.delete_quizzes_before(before).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.delete_quizzes_before(before).await,
+        }
+    }
+
+    async fn save_code_verification(
+        &self,
+        verification: &CodeVerification,
+    ) -> Result<CodeVerification, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.save_code_verification(verification).await,
+            FlashcardStore::InMemory(repo) => repo.save_code_verification(verification).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.save_code_verification(verification).await,
+        }
+    }
+
+    async fn get_code_verification(&self, id: Uuid) -> Result<CodeVerification, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_code_verification(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_code_verification(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_code_verification(id).await,
+        }
+    }
+
+    async fn create_drafts(
+        &self,
+        drafts: &[FlashcardDraft],
+    ) -> Result<Vec<FlashcardDraft>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_drafts(drafts).await,
+            FlashcardStore::InMemory(repo) => repo.create_drafts(drafts).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_drafts(drafts).await,
+        }
+    }
+
+    async fn list_drafts(&self) -> Result<Vec<FlashcardDraft>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_drafts().await,
+            FlashcardStore::InMemory(repo) => repo.list_drafts().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_drafts().await,
+        }
+    }
+
+    async fn get_draft(&self, id: Uuid) -> Result<FlashcardDraft, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_draft(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_draft(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_draft(id).await,
+        }
+    }
+
+    async fn delete_draft(&self, id: Uuid) -> Result<u64, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.delete_draft(id).await,
+            FlashcardStore::InMemory(repo) => repo.delete_draft(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.delete_draft(id).await,
+        }
+    }
+
+    async fn create_user(&self, user: &User, token_hash: &str) -> Result<User, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_user(user, token_hash).await,
+            FlashcardStore::InMemory(repo) => repo.create_user(user, token_hash).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_user(user, token_hash).await,
+        }
+    }
+
+    async fn get_user_by_token(&self, token_hash: &str) -> Result<Option<User>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_user_by_token(token_hash).await,
+            FlashcardStore::InMemory(repo) => repo.get_user_by_token(token_hash).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_user_by_token(token_hash).await,
+        }
+    }
+
+    async fn create_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_suggestion(suggestion).await,
+            FlashcardStore::InMemory(repo) => repo.create_suggestion(suggestion).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_suggestion(suggestion).await,
+        }
+    }
+
+    async fn list_suggestions(
+        &self,
+        status: Option<SuggestionStatus>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Suggestion>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_suggestions(status, user_id).await,
+            FlashcardStore::InMemory(repo) => repo.list_suggestions(status, user_id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_suggestions(status, user_id).await,
+        }
+    }
+
+    async fn get_suggestion(&self, id: Uuid) -> Result<Suggestion, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.get_suggestion(id).await,
+            FlashcardStore::InMemory(repo) => repo.get_suggestion(id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.get_suggestion(id).await,
+        }
+    }
+
+    async fn close_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.close_suggestion(suggestion).await,
+            FlashcardStore::InMemory(repo) => repo.close_suggestion(suggestion).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.close_suggestion(suggestion).await,
+        }
+    }
+
+    async fn approve_suggestion(
+        &self,
+        suggestion: &Suggestion,
+        new_card: Option<&FlashCard>,
+    ) -> Result<(Suggestion, FlashCard), ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.approve_suggestion(suggestion, new_card).await,
+            FlashcardStore::InMemory(repo) => repo.approve_suggestion(suggestion, new_card).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.approve_suggestion(suggestion, new_card).await,
+        }
+    }
+
+    async fn create_feedback(
+        &self,
+        feedback: &CardFeedback,
+        limit: u32,
+        since: SqlxTimestamp,
+    ) -> Result<CardFeedback, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_feedback(feedback, limit, since).await,
+            FlashcardStore::InMemory(repo) => repo.create_feedback(feedback, limit, since).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_feedback(feedback, limit, since).await,
+        }
+    }
+
+    async fn feedback_report(&self) -> Result<Vec<FlaggedCard>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.feedback_report().await,
+            FlashcardStore::InMemory(repo) => repo.feedback_report().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.feedback_report().await,
+        }
+    }
+
+    async fn list_open_flags(&self, flashcard_id: Uuid) -> Result<Vec<CardFeedback>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_open_flags(flashcard_id).await,
+            FlashcardStore::InMemory(repo) => repo.list_open_flags(flashcard_id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_open_flags(flashcard_id).await,
+        }
+    }
+
+    async fn resolve_flags(
+        &self,
+        flashcard_id: Uuid,
+        resolved_at: SqlxTimestamp,
+    ) -> Result<u64, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.resolve_flags(flashcard_id, resolved_at).await,
+            FlashcardStore::InMemory(repo) => repo.resolve_flags(flashcard_id, resolved_at).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.resolve_flags(flashcard_id, resolved_at).await,
+        }
+    }
+
+    async fn create_study_review(&self, review: &StudyReview) -> Result<StudyReview, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_study_review(review).await,
+            FlashcardStore::InMemory(repo) => repo.create_study_review(review).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_study_review(review).await,
+        }
+    }
+
+    async fn list_study_reviews(&self, user_id: Uuid) -> Result<Vec<StudyReview>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_study_reviews(user_id).await,
+            FlashcardStore::InMemory(repo) => repo.list_study_reviews(user_id).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_study_reviews(user_id).await,
+        }
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_tags().await,
+            FlashcardStore::InMemory(repo) => repo.list_tags().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_tags().await,
+        }
+    }
+
+    async fn update_tag(&self, slug: &str, updated_tag: &UpdatedTag) -> Result<Tag, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.update_tag(slug, updated_tag).await,
+            FlashcardStore::InMemory(repo) => repo.update_tag(slug, updated_tag).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.update_tag(slug, updated_tag).await,
+        }
+    }
+
+    async fn merge_tags(&self, source: &str, target: &str) -> Result<Tag, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.merge_tags(source, target).await,
+            FlashcardStore::InMemory(repo) => repo.merge_tags(source, target).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.merge_tags(source, target).await,
+        }
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_topics().await,
+            FlashcardStore::InMemory(repo) => repo.list_topics().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_topics().await,
+        }
+    }
+
+    async fn flashcard_stats(&self) -> Result<FlashcardStats, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.flashcard_stats().await,
+            FlashcardStore::InMemory(repo) => repo.flashcard_stats().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.flashcard_stats().await,
+        }
+    }
+
+    async fn list_topic_details(&self) -> Result<Vec<Topic>, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.list_topic_details().await,
+            FlashcardStore::InMemory(repo) => repo.list_topic_details().await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.list_topic_details().await,
+        }
+    }
+
+    async fn create_topic(&self, new_topic: &NewTopic) -> Result<Topic, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.create_topic(new_topic).await,
+            FlashcardStore::InMemory(repo) => repo.create_topic(new_topic).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.create_topic(new_topic).await,
+        }
+    }
+
+    async fn update_topic(
+        &self,
+        slug: &str,
+        updated_topic: &UpdatedTopic,
+    ) -> Result<Topic, ApiError> {
+        match self {
+            FlashcardStore::Postgres(repo) => repo.update_topic(slug, updated_topic).await,
+            FlashcardStore::InMemory(repo) => repo.update_topic(slug, updated_topic).await,
+            #[cfg(feature = "sqlite")]
+            FlashcardStore::Sqlite(repo) => repo.update_topic(slug, updated_topic).await,
+        }
+    }
+}
+
+/// Pick up to `count` distinct ids among the `(id, difficulty)` candidates, in random order;
+/// with a weighting, the likelihood of each candidate follows its difficulty.
+pub(crate) fn sample_ids(
+    candidates: &[(Uuid, i32)],
+    count: usize,
+    weighting: Option<DifficultyWeighting>,
+) -> Vec<Uuid> {
+    let mut rng = rand::rng();
+    let mut ids: Vec<Uuid> = match weighting {
+        Some(weighting) => candidates
+            .choose_multiple_weighted(&mut rng, count, |(_, difficulty)| {
+                weighting.weight(*difficulty)
+            })
+            .map(|chosen| chosen.map(|(id, _)| *id).collect())
+            .unwrap_or_default(),
+        None => candidates
+            .choose_multiple(&mut rng, count)
+            .map(|(id, _)| *id)
+            .collect(),
+    };
+    ids.shuffle(&mut rng);
+
+    ids
+}
+
+/// Sort the fetched flash cards in the order of the sampled ids.
+pub(crate) fn in_sampled_order(mut cards: Vec<FlashCard>, ids: &[Uuid]) -> Vec<FlashCard> {
+    cards.sort_by_key(|card| ids.iter().position(|id| *id == card.id));
+    cards
+}
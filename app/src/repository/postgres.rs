@@ -0,0 +1,329 @@
+// app/src/repository/postgres.rs
+
+// the Postgres storage backend, built on top of the queries in `crate::queries`
+
+// dependencies
+use super::{FlashcardRepository, OrganizationRepository, in_sampled_order};
+use crate::errors::ApiError;
+use crate::models::{
+    CardFeedback, CardReview, CardStatus, CodeVerification, DEFAULT_ORGANIZATION_ID,
+    DailySelection, FlaggedCard, FlashCard, FlashcardDraft, FlashcardStats, NewTopic, Organization,
+    Quiz, RandomSelection, StudyReview, Suggestion, SuggestionStatus, Tag, Topic, UpdatedFlashCard,
+    UpdatedTag, UpdatedTopic, User,
+};
+use crate::queries;
+use crate::validation::topic_cycle_error;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// struct type to represent the Postgres storage backend, scoped to an organization
+#[derive(Clone, Debug)]
+pub struct PostgresFlashcardRepository {
+    pool: PgPool,
+    organization_id: Uuid,
+}
+
+// methods for the PostgresFlashcardRepository type
+impl PostgresFlashcardRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            organization_id: DEFAULT_ORGANIZATION_ID,
+        }
+    }
+
+    // the same pool, scoped to the given organization
+    pub fn scoped(&self, organization_id: Uuid) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            organization_id,
+        }
+    }
+}
+
+// implement the FlashcardRepository trait by delegating to the sqlx queries
+impl FlashcardRepository for PostgresFlashcardRepository {
+    async fn list_flashcards(&self) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(queries::list_flashcards(&self.pool, self.organization_id).await?)
+    }
+
+    async fn list_flashcards_by_topic(
+        &self,
+        topic: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(queries::list_flashcards_by_topic(
+            &self.pool,
+            self.organization_id,
+            topic,
+            include_descendants,
+        )
+        .await?)
+    }
+
+    async fn list_flashcards_by_tag(&self, tag: &str) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(queries::list_flashcards_by_tag(&self.pool, self.organization_id, tag).await?)
+    }
+
+    async fn get_flashcard(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        Ok(queries::list_flashcard(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn list_all_flashcards(
+        &self,
+        status: Option<CardStatus>,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        Ok(queries::list_all_flashcards(&self.pool, self.organization_id, status).await?)
+    }
+
+    async fn get_flashcard_any_status(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        Ok(queries::get_flashcard_any_status(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn review_flashcard(&self, review: &CardReview) -> Result<FlashCard, ApiError> {
+        queries::review_flashcard(&self.pool, self.organization_id, review)
+            .await?
+            .ok_or(ApiError::InvalidStatusTransition {
+                from: review.from_status,
+                to: review.to_status,
+            })
+    }
+
+    async fn list_reviews(&self, id: Uuid) -> Result<Vec<CardReview>, ApiError> {
+        Ok(queries::list_reviews(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn create_flashcard(&self, new_card: &FlashCard) -> Result<FlashCard, ApiError> {
+        Ok(queries::create_flashcard(&self.pool, self.organization_id, new_card).await?)
+    }
+
+    async fn update_flashcard(
+        &self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        Ok(queries::update_flashcard(&self.pool, self.organization_id, id, updated_card).await?)
+    }
+
+    async fn delete_flashcard(&self, id: Uuid) -> Result<u64, ApiError> {
+        Ok(queries::delete_flashcard(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn random_flashcards(
+        &self,
+        selection: &RandomSelection,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        let ids =
+            queries::random_flashcard_ids(&self.pool, self.organization_id, selection).await?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let cards = queries::list_flashcards_by_ids(&self.pool, self.organization_id, &ids).await?;
+
+        Ok(in_sampled_order(cards, &ids))
+    }
+
+    async fn daily_flashcard(
+        &self,
+        selection: &DailySelection,
+    ) -> Result<Option<FlashCard>, ApiError> {
+        Ok(queries::daily_flashcard(&self.pool, self.organization_id, selection).await?)
+    }
+
+    async fn create_quiz(&self, quiz: &Quiz) -> Result<Quiz, ApiError> {
+        Ok(queries::create_quiz(&self.pool, self.organization_id, quiz).await?)
+    }
+
+    async fn get_quiz(&self, id: Uuid) -> Result<Quiz, ApiError> {
+        Ok(queries::get_quiz(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn delete_quizzes_before(&self, before: SqlxTimestamp) -> Result<u64, ApiError> {
+        Ok(queries::delete_quizzes_before(&self.pool, self.organization_id, before).await?)
+    }
+
+    async fn save_code_verification(
+        &self,
+        verification: &CodeVerification,
+    ) -> Result<CodeVerification, ApiError> {
+        Ok(queries::save_code_verification(&self.pool, self.organization_id, verification).await?)
+    }
+
+    async fn get_code_verification(&self, id: Uuid) -> Result<CodeVerification, ApiError> {
+        Ok(queries::get_code_verification(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn create_drafts(
+        &self,
+        drafts: &[FlashcardDraft],
+    ) -> Result<Vec<FlashcardDraft>, ApiError> {
+        Ok(queries::create_drafts(&self.pool, self.organization_id, drafts).await?)
+    }
+
+    async fn list_drafts(&self) -> Result<Vec<FlashcardDraft>, ApiError> {
+        Ok(queries::list_drafts(&self.pool, self.organization_id).await?)
+    }
+
+    async fn get_draft(&self, id: Uuid) -> Result<FlashcardDraft, ApiError> {
+        Ok(queries::get_draft(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn delete_draft(&self, id: Uuid) -> Result<u64, ApiError> {
+        Ok(queries::delete_draft(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn create_user(&self, user: &User, token_hash: &str) -> Result<User, ApiError> {
+        Ok(queries::create_user(&self.pool, self.organization_id, user, token_hash).await?)
+    }
+
+    async fn get_user_by_token(&self, token_hash: &str) -> Result<Option<User>, ApiError> {
+        Ok(queries::get_user_by_token(&self.pool, self.organization_id, token_hash).await?)
+    }
+
+    async fn create_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        Ok(queries::create_suggestion(&self.pool, self.organization_id, suggestion).await?)
+    }
+
+    async fn list_suggestions(
+        &self,
+        status: Option<SuggestionStatus>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Suggestion>, ApiError> {
+        Ok(queries::list_suggestions(&self.pool, self.organization_id, status, user_id).await?)
+    }
+
+    async fn get_suggestion(&self, id: Uuid) -> Result<Suggestion, ApiError> {
+        Ok(queries::get_suggestion(&self.pool, self.organization_id, id).await?)
+    }
+
+    async fn close_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        queries::close_suggestion(&self.pool, self.organization_id, suggestion)
+            .await?
+            .ok_or(ApiError::SuggestionClosed)
+    }
+
+    async fn approve_suggestion(
+        &self,
+        suggestion: &Suggestion,
+        new_card: Option<&FlashCard>,
+    ) -> Result<(Suggestion, FlashCard), ApiError> {
+        queries::approve_suggestion(&self.pool, self.organization_id, suggestion, new_card)
+            .await?
+            .ok_or(ApiError::SuggestionClosed)
+    }
+
+    async fn create_feedback(
+        &self,
+        feedback: &CardFeedback,
+        limit: u32,
+        since: SqlxTimestamp,
+    ) -> Result<CardFeedback, ApiError> {
+        queries::create_feedback(&self.pool, self.organization_id, feedback, limit, since)
+            .await?
+            .ok_or(ApiError::FeedbackLimitReached)
+    }
+
+    async fn feedback_report(&self) -> Result<Vec<FlaggedCard>, ApiError> {
+        Ok(queries::feedback_report(&self.pool, self.organization_id).await?)
+    }
+
+    async fn list_open_flags(&self, flashcard_id: Uuid) -> Result<Vec<CardFeedback>, ApiError> {
+        Ok(queries::list_open_flags(&self.pool, self.organization_id, flashcard_id).await?)
+    }
+
+    async fn resolve_flags(
+        &self,
+        flashcard_id: Uuid,
+        resolved_at: SqlxTimestamp,
+    ) -> Result<u64, ApiError> {
+        Ok(
+            queries::resolve_flags(&self.pool, self.organization_id, flashcard_id, resolved_at)
+                .await?,
+        )
+    }
+
+    async fn create_study_review(&self, review: &StudyReview) -> Result<StudyReview, ApiError> {
+        Ok(queries::create_study_review(&self.pool, self.organization_id, review).await?)
+    }
+
+    async fn list_study_reviews(&self, user_id: Uuid) -> Result<Vec<StudyReview>, ApiError> {
+        Ok(queries::list_study_reviews(&self.pool, self.organization_id, user_id).await?)
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, ApiError> {
+        Ok(queries::list_tags(&self.pool, self.organization_id).await?)
+    }
+
+    async fn update_tag(&self, slug: &str, updated_tag: &UpdatedTag) -> Result<Tag, ApiError> {
+        Ok(queries::update_tag(&self.pool, self.organization_id, slug, updated_tag).await?)
+    }
+
+    async fn merge_tags(&self, source: &str, target: &str) -> Result<Tag, ApiError> {
+        Ok(queries::merge_tags(&self.pool, self.organization_id, source, target).await?)
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>, ApiError> {
+        Ok(queries::list_topics(&self.pool, self.organization_id).await?)
+    }
+
+    async fn flashcard_stats(&self) -> Result<FlashcardStats, ApiError> {
+        Ok(queries::flashcard_stats(&self.pool, self.organization_id).await?)
+    }
+
+    async fn list_topic_details(&self) -> Result<Vec<Topic>, ApiError> {
+        Ok(queries::list_topic_details(&self.pool, self.organization_id).await?)
+    }
+
+    async fn create_topic(&self, new_topic: &NewTopic) -> Result<Topic, ApiError> {
+        Ok(queries::create_topic(&self.pool, self.organization_id, new_topic).await?)
+    }
+
+    async fn update_topic(
+        &self,
+        slug: &str,
+        updated_topic: &UpdatedTopic,
+    ) -> Result<Topic, ApiError> {
+        let topic =
+            queries::update_topic(&self.pool, self.organization_id, slug, updated_topic).await?;
+
+        topic.ok_or_else(|| topic_cycle_error().into())
+    }
+}
+
+// implement the OrganizationRepository trait by delegating to the sqlx queries
+impl OrganizationRepository for PostgresFlashcardRepository {
+    async fn create_organization(
+        &self,
+        organization: &Organization,
+        key_hash: &str,
+    ) -> Result<Organization, ApiError> {
+        Ok(queries::create_organization(&self.pool, organization, key_hash).await?)
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, ApiError> {
+        Ok(queries::list_organizations(&self.pool).await?)
+    }
+
+    async fn get_organization(&self, slug: &str) -> Result<Organization, ApiError> {
+        Ok(queries::get_organization(&self.pool, slug).await?)
+    }
+
+    async fn add_api_key(&self, organization_id: Uuid, key_hash: &str) -> Result<(), ApiError> {
+        Ok(queries::add_api_key(&self.pool, organization_id, key_hash).await?)
+    }
+
+    async fn find_organization_by_api_key(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        Ok(queries::find_organization_by_api_key(&self.pool, key_hash).await?)
+    }
+
+    async fn find_organization_by_host(
+        &self,
+        host: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        Ok(queries::find_organization_by_host(&self.pool, host).await?)
+    }
+}
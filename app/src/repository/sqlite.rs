@@ -0,0 +1,1550 @@
+// app/src/repository/sqlite.rs
+
+// the SQLite storage backend, for single-binary installs without a Postgres server;
+// only compiled with the `sqlite` cargo feature
+
+// dependencies
+use super::{FlashcardRepository, OrganizationRepository, in_sampled_order};
+use crate::configuration::DatabaseConfig;
+use crate::errors::ApiError;
+use crate::models::{
+    CardFeedback, CardReview, CardStatus, CodeVerification, DEFAULT_ORGANIZATION_ID,
+    DailySelection, DifficultyStats, FlaggedCard, FlashCard, FlashcardDraft, FlashcardStats,
+    NewTopic, Organization, Quiz, QuizQuestion, RandomSelection, StudyReview, Suggestion,
+    SuggestionStatus, Tag, TagStats, Topic, TopicStats, UpdatedFlashCard, UpdatedTag, UpdatedTopic,
+    User, WeekStats, slugify,
+};
+use crate::validation::{MAX_DIFFICULTY, topic_cycle_error};
+use jiff_sqlx::{Timestamp as SqlxTimestamp, ToSqlx};
+use pavex::time::Timestamp as PavexTimestamp;
+use serde::de::DeserializeOwned;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::{FromRow, Sqlite, Transaction};
+use uuid::Uuid;
+
+// the columns of a flash card, with its topic name and its tag names gathered from the tags
+// table as a JSON array
+const SELECT_FLASHCARDS: &str =
+    "SELECT f.id, f.question, f.answer, COALESCE(tp.name, '') AS topic, f.difficulty, f.kind, f.status, f.created_at, f.updated_at,
+    (SELECT json_group_array(name)
+        FROM (SELECT t.name FROM flashcard_tags ft JOIN tags t ON t.id = ft.tag_id
+            WHERE ft.flashcard_id = f.id ORDER BY ft.position)) AS tags
+    FROM flashcards f
+    LEFT JOIN topics tp ON tp.id = f.topic_id";
+
+// the condition met by the flash cards shown on the public API
+const PUBLISHED: &str = "f.status = 'published'";
+
+// the columns of a tag, with the number of published flash cards carrying it
+const SELECT_TAGS: &str =
+    "SELECT t.slug, t.name, t.description, COUNT(ft.flashcard_id) AS card_count
+    FROM tags t
+    LEFT JOIN (SELECT ft.tag_id, ft.flashcard_id
+        FROM flashcard_tags ft
+        JOIN flashcards f ON f.id = ft.flashcard_id
+        WHERE f.status = 'published') ft ON ft.tag_id = t.id";
+
+// the columns of a topic, with the slug of its parent and the number of published flash cards
+// filed under it
+const SELECT_TOPICS: &str =
+    "SELECT t.slug, t.name, t.description, p.slug AS parent, COUNT(f.id) AS card_count
+    FROM topics t
+    LEFT JOIN topics p ON p.id = t.parent_id
+    LEFT JOIN flashcards f ON f.topic_id = t.id AND f.status = 'published'";
+
+// the columns of a review of a flash card
+const SELECT_REVIEWS: &str =
+    "SELECT id, flashcard_id, from_status, to_status, note, reviewed_at FROM flashcard_reviews";
+
+// the ids of the topic of the organization ?1 matching the slug ?2 and of all its descendants
+const TOPIC_SUBTREE: &str = "WITH RECURSIVE subtree AS (
+        SELECT id FROM topics WHERE organization_id = ?1 AND slug = ?2
+        UNION
+        SELECT t.id FROM topics t JOIN subtree s ON t.parent_id = s.id
+    )
+    SELECT id FROM subtree";
+
+// decode a column holding JSON; a value which doesn't decode is an error, not a default
+fn decode_json<T: DeserializeOwned>(column: &str) -> Result<T, sqlx::Error> {
+    serde_json::from_str(column).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+// decode a column holding the name of a status, as the Postgres rows do with `try_from`
+fn decode_text<T: TryFrom<String, Error = String>>(column: String) -> Result<T, sqlx::Error> {
+    T::try_from(column).map_err(|e| sqlx::Error::Decode(e.into()))
+}
+
+// struct type to represent a flash card row, as returned by SQLite
+#[derive(FromRow)]
+struct FlashCardRow {
+    id: Uuid,
+    question: String,
+    answer: String,
+    topic: String,
+    tags: String,
+    difficulty: i32,
+    kind: String,
+    status: String,
+    created_at: SqlxTimestamp,
+    updated_at: Option<SqlxTimestamp>,
+}
+
+// implement the TryFrom trait to convert a SQLite row into the domain data model
+impl TryFrom<FlashCardRow> for FlashCard {
+    type Error = sqlx::Error;
+
+    fn try_from(row: FlashCardRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            question: row.question,
+            answer: row.answer,
+            topic: row.topic,
+            tags: decode_json(&row.tags)?,
+            difficulty: row.difficulty,
+            kind: decode_json(&row.kind)?,
+            status: decode_text(row.status)?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+// the columns of a draft card, tags as a JSON array
+const SELECT_DRAFTS: &str =
+    "SELECT id, question, answer, topic, tags, difficulty, source, created_at
+    FROM flashcard_drafts";
+
+// struct type to represent a draft card as read from SQLite, tags as a JSON array
+#[derive(FromRow)]
+struct DraftRow {
+    id: Uuid,
+    question: String,
+    answer: String,
+    topic: String,
+    tags: String,
+    difficulty: i32,
+    source: String,
+    created_at: SqlxTimestamp,
+}
+
+// implement the TryFrom trait to convert a SQLite row into the domain data model
+impl TryFrom<DraftRow> for FlashcardDraft {
+    type Error = sqlx::Error;
+
+    fn try_from(row: DraftRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            question: row.question,
+            answer: row.answer,
+            topic: row.topic,
+            tags: decode_json(&row.tags)?,
+            difficulty: row.difficulty,
+            source: row.source,
+            created_at: row.created_at,
+        })
+    }
+}
+
+// the columns of a suggestion, its changes and diff as JSON
+const SELECT_SUGGESTIONS: &str =
+    "SELECT id, user_id, flashcard_id, changes, diff, status, note, created_at, reviewed_at
+    FROM card_suggestions";
+
+// struct type to represent a suggestion as read from SQLite, its changes and diff as JSON
+#[derive(FromRow)]
+struct SuggestionRow {
+    id: Uuid,
+    user_id: Uuid,
+    flashcard_id: Option<Uuid>,
+    changes: String,
+    diff: String,
+    status: String,
+    note: Option<String>,
+    created_at: SqlxTimestamp,
+    reviewed_at: Option<SqlxTimestamp>,
+}
+
+// implement the TryFrom trait to convert a SQLite row into the domain data model
+impl TryFrom<SuggestionRow> for Suggestion {
+    type Error = sqlx::Error;
+
+    fn try_from(row: SuggestionRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            flashcard_id: row.flashcard_id,
+            changes: decode_json(&row.changes)?,
+            diff: decode_json(&row.diff)?,
+            status: decode_text(row.status)?,
+            note: row.note,
+            created_at: row.created_at,
+            reviewed_at: row.reviewed_at,
+        })
+    }
+}
+
+// the columns of a feedback entry
+const SELECT_FEEDBACK: &str =
+    "SELECT id, flashcard_id, kind, comment, client, created_at, resolved_at FROM card_feedback";
+
+// the columns of an organization, its hosts gathered as a JSON array
+const SELECT_ORGANIZATIONS: &str = "SELECT o.id, o.slug, o.name, o.created_at,
+    (SELECT json_group_array(host)
+        FROM (SELECT h.host FROM organization_hosts h
+            WHERE h.organization_id = o.id ORDER BY h.host)) AS hosts
+    FROM organizations o";
+
+// struct type to represent an organization as read from SQLite, its hosts as a JSON array
+#[derive(FromRow)]
+struct OrganizationRow {
+    id: Uuid,
+    slug: String,
+    name: String,
+    hosts: String,
+    created_at: SqlxTimestamp,
+}
+
+// implement the TryFrom trait to convert a SQLite row into the domain data model
+impl TryFrom<OrganizationRow> for Organization {
+    type Error = sqlx::Error;
+
+    fn try_from(row: OrganizationRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            slug: row.slug,
+            name: row.name,
+            hosts: decode_json(&row.hosts)?,
+            created_at: row.created_at,
+        })
+    }
+}
+
+// struct type to represent the SQLite storage backend, scoped to an organization
+#[derive(Clone, Debug)]
+pub struct SqliteFlashcardRepository {
+    pool: SqlitePool,
+    organization_id: Uuid,
+}
+
+// methods for the SqliteFlashcardRepository type
+impl SqliteFlashcardRepository {
+    /// Open (or create) the SQLite database file and bring its schema up to date.
+    pub async fn connect(config: &DatabaseConfig) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::new()
+            .filename(&config.sqlite_path)
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let pool = SqlitePoolOptions::new()
+            .min_connections(config.min_connections)
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .idle_timeout(config.idle_timeout)
+            .max_lifetime(config.max_lifetime)
+            .connect_with(options)
+            .await?;
+
+        sqlx::migrate!("../migrations_sqlite").run(&pool).await?;
+
+        Ok(Self {
+            pool,
+            organization_id: DEFAULT_ORGANIZATION_ID,
+        })
+    }
+
+    // the same pool, scoped to the given organization
+    pub fn scoped(&self, organization_id: Uuid) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            organization_id,
+        }
+    }
+
+    // fetch the flash cards of the organization ?1 meeting the given condition, whatever their
+    // status, most recent first; the condition may refer to the bound value as ?2
+    async fn fetch_all(
+        &self,
+        condition: &str,
+        bind: Option<&str>,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        let sql = format!(
+            "{} WHERE f.organization_id = ?1 {} ORDER BY f.created_at DESC;",
+            SELECT_FLASHCARDS, condition
+        );
+        let mut query = sqlx::query_as::<_, FlashCardRow>(&sql).bind(self.organization_id);
+        if let Some(value) = bind {
+            query = query.bind(value);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(FlashCard::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    // fetch a single flash card of an organization within a transaction
+    async fn fetch_one(
+        transaction: &mut Transaction<'_, Sqlite>,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> Result<FlashCard, ApiError> {
+        let sql = format!(
+            "{} WHERE f.organization_id = ? AND f.id = ?;",
+            SELECT_FLASHCARDS
+        );
+        let row: FlashCardRow = sqlx::query_as(&sql)
+            .bind(organization_id)
+            .bind(id)
+            .fetch_one(&mut **transaction)
+            .await?;
+
+        Ok(row.try_into()?)
+    }
+
+    // insert a flash card of an organization, along with its topic and its tags, within a
+    // transaction
+    async fn insert_flashcard(
+        transaction: &mut Transaction<'_, Sqlite>,
+        organization_id: Uuid,
+        new_card: &FlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        let topic_id = Self::upsert_topic(transaction, organization_id, &new_card.topic).await?;
+        sqlx::query("INSERT INTO flashcards (organization_id, id, question, answer, topic_id, difficulty, kind, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);")
+            .bind(organization_id)
+            .bind(new_card.id)
+            .bind(&new_card.question)
+            .bind(&new_card.answer)
+            .bind(topic_id)
+            .bind(new_card.difficulty)
+            .bind(serde_json::to_string(&new_card.kind).unwrap_or_default())
+            .bind(new_card.status.as_str())
+            .bind(new_card.created_at)
+            .bind(new_card.updated_at)
+            .execute(&mut **transaction)
+            .await?;
+        Self::replace_tags(transaction, organization_id, new_card.id, &new_card.tags).await?;
+
+        Self::fetch_one(transaction, organization_id, new_card.id).await
+    }
+
+    // update a flash card of an organization within a transaction; fields missing from the
+    // update keep their value
+    async fn apply_update(
+        transaction: &mut Transaction<'_, Sqlite>,
+        organization_id: Uuid,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        let topic_id = match &updated_card.topic {
+            Some(topic) => Some(Self::upsert_topic(transaction, organization_id, topic).await?),
+            None => None,
+        };
+        let result = sqlx::query("UPDATE flashcards SET question = COALESCE(?, question), answer = COALESCE(?, answer), topic_id = COALESCE(?, topic_id), difficulty = COALESCE(?, difficulty), kind = COALESCE(?, kind), updated_at = ? WHERE organization_id = ? AND id = ?;")
+            .bind(&updated_card.question)
+            .bind(&updated_card.answer)
+            .bind(topic_id)
+            .bind(updated_card.difficulty)
+            .bind(
+                updated_card
+                    .kind
+                    .as_ref()
+                    .and_then(|kind| serde_json::to_string(kind).ok()),
+            )
+            .bind(Some(PavexTimestamp::now().to_sqlx()))
+            .bind(organization_id)
+            .bind(id)
+            .execute(&mut **transaction)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        if let Some(tags) = &updated_card.tags {
+            Self::replace_tags(transaction, organization_id, id, tags).await?;
+        }
+
+        Self::fetch_one(transaction, organization_id, id).await
+    }
+
+    // replace the tags of a flash card within a transaction, creating the tags which don't exist yet
+    async fn replace_tags(
+        transaction: &mut Transaction<'_, Sqlite>,
+        organization_id: Uuid,
+        id: Uuid,
+        tags: &[String],
+    ) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM flashcard_tags WHERE flashcard_id = ?;")
+            .bind(id)
+            .execute(&mut **transaction)
+            .await?;
+
+        for (position, tag) in tags.iter().enumerate() {
+            let slug = slugify(tag);
+            sqlx::query(
+                "INSERT INTO tags (organization_id, id, slug, name) VALUES (?, ?, ?, ?)
+                ON CONFLICT (organization_id, slug) DO NOTHING;",
+            )
+            .bind(organization_id)
+            .bind(Uuid::new_v4())
+            .bind(&slug)
+            .bind(tag.trim())
+            .execute(&mut **transaction)
+            .await?;
+            sqlx::query(
+                "INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+                SELECT ?, id, ? FROM tags WHERE organization_id = ? AND slug = ?
+                ON CONFLICT DO NOTHING;",
+            )
+            .bind(id)
+            .bind(position as i64)
+            .bind(organization_id)
+            .bind(&slug)
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    // return the id of the topic with the given name, creating it as a root topic if it
+    // doesn't exist yet
+    async fn upsert_topic(
+        transaction: &mut Transaction<'_, Sqlite>,
+        organization_id: Uuid,
+        name: &str,
+    ) -> Result<Uuid, ApiError> {
+        let name = name.trim();
+        sqlx::query(
+            "INSERT INTO topics (organization_id, id, slug, name) VALUES (?, ?, ?, ?)
+            ON CONFLICT (organization_id, slug) DO NOTHING;",
+        )
+        .bind(organization_id)
+        .bind(Uuid::new_v4())
+        .bind(slugify(name))
+        .bind(name)
+        .execute(&mut **transaction)
+        .await?;
+        let id: Uuid =
+            sqlx::query_scalar("SELECT id FROM topics WHERE organization_id = ? AND slug = ?;")
+                .bind(organization_id)
+                .bind(slugify(name))
+                .fetch_one(&mut **transaction)
+                .await?;
+
+        Ok(id)
+    }
+
+    // return the id of a topic given its slug, `None` if no slug is given
+    async fn find_topic_id(&self, slug: Option<&str>) -> Result<Option<Uuid>, ApiError> {
+        let Some(slug) = slug else {
+            return Ok(None);
+        };
+        let id: Uuid =
+            sqlx::query_scalar("SELECT id FROM topics WHERE organization_id = ? AND slug = ?;")
+                .bind(self.organization_id)
+                .bind(slug)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(Some(id))
+    }
+
+    // fetch a single topic, given its slug
+    async fn fetch_topic(&self, slug: &str) -> Result<Topic, ApiError> {
+        let sql = format!(
+            "{} WHERE t.organization_id = ? AND t.slug = ? GROUP BY t.id, p.slug;",
+            SELECT_TOPICS
+        );
+        let topic: Topic = sqlx::query_as(&sql)
+            .bind(self.organization_id)
+            .bind(slug)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(topic)
+    }
+
+    // fetch a single tag, given its slug
+    async fn fetch_tag(&self, slug: &str) -> Result<Tag, ApiError> {
+        let sql = format!(
+            "{} WHERE t.organization_id = ? AND t.slug = ? GROUP BY t.id;",
+            SELECT_TAGS
+        );
+        let tag: Tag = sqlx::query_as(&sql)
+            .bind(self.organization_id)
+            .bind(slug)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(tag)
+    }
+}
+
+// implement the FlashcardRepository trait with SQLite queries
+impl FlashcardRepository for SqliteFlashcardRepository {
+    async fn list_flashcards(&self) -> Result<Vec<FlashCard>, ApiError> {
+        self.fetch_all(&format!("AND {}", PUBLISHED), None).await
+    }
+
+    async fn list_flashcards_by_topic(
+        &self,
+        topic: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        let slug = slugify(topic);
+        if include_descendants {
+            let condition = format!("AND f.topic_id IN ({}) AND {}", TOPIC_SUBTREE, PUBLISHED);
+            self.fetch_all(&condition, Some(&slug)).await
+        } else {
+            let condition = format!("AND tp.slug = ?2 AND {}", PUBLISHED);
+            self.fetch_all(&condition, Some(&slug)).await
+        }
+    }
+
+    async fn list_flashcards_by_tag(&self, tag: &str) -> Result<Vec<FlashCard>, ApiError> {
+        let slug = slugify(tag);
+        let condition = format!(
+            "AND {} AND EXISTS (SELECT 1 FROM flashcard_tags ft JOIN tags t ON t.id = ft.tag_id WHERE ft.flashcard_id = f.id AND t.slug = ?2)",
+            PUBLISHED
+        );
+        self.fetch_all(&condition, Some(&slug)).await
+    }
+
+    async fn get_flashcard(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        let sql = format!(
+            "{} WHERE f.organization_id = ? AND f.id = ? AND {};",
+            SELECT_FLASHCARDS, PUBLISHED
+        );
+        let row: FlashCardRow = sqlx::query_as(&sql)
+            .bind(self.organization_id)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.try_into()?)
+    }
+
+    async fn list_all_flashcards(
+        &self,
+        status: Option<CardStatus>,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        match status {
+            Some(status) => {
+                self.fetch_all("AND f.status = ?2", Some(status.as_str()))
+                    .await
+            }
+            None => self.fetch_all("", None).await,
+        }
+    }
+
+    async fn get_flashcard_any_status(&self, id: Uuid) -> Result<FlashCard, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+        let card = Self::fetch_one(&mut transaction, self.organization_id, id).await?;
+        transaction.commit().await?;
+
+        Ok(card)
+    }
+
+    async fn review_flashcard(&self, review: &CardReview) -> Result<FlashCard, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE flashcards SET status = ?, updated_at = ?
+            WHERE organization_id = ? AND id = ? AND status = ?;",
+        )
+        .bind(review.to_status.as_str())
+        .bind(review.reviewed_at)
+        .bind(self.organization_id)
+        .bind(review.flashcard_id)
+        .bind(review.from_status.as_str())
+        .execute(&mut *transaction)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ApiError::InvalidStatusTransition {
+                from: review.from_status,
+                to: review.to_status,
+            });
+        }
+        sqlx::query(
+            "INSERT INTO flashcard_reviews (id, flashcard_id, from_status, to_status, note, reviewed_at)
+            VALUES (?, ?, ?, ?, ?, ?);",
+        )
+        .bind(review.id)
+        .bind(review.flashcard_id)
+        .bind(review.from_status.as_str())
+        .bind(review.to_status.as_str())
+        .bind(&review.note)
+        .bind(review.reviewed_at)
+        .execute(&mut *transaction)
+        .await?;
+        let card =
+            Self::fetch_one(&mut transaction, self.organization_id, review.flashcard_id).await?;
+        transaction.commit().await?;
+
+        Ok(card)
+    }
+
+    async fn list_reviews(&self, id: Uuid) -> Result<Vec<CardReview>, ApiError> {
+        let reviews: Vec<CardReview> = sqlx::query_as(&format!(
+            "{} WHERE flashcard_id IN (SELECT id FROM flashcards WHERE organization_id = ? AND id = ?)
+            ORDER BY reviewed_at ASC;",
+            SELECT_REVIEWS
+        ))
+        .bind(self.organization_id)
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reviews)
+    }
+
+    async fn create_flashcard(&self, new_card: &FlashCard) -> Result<FlashCard, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+        let card = Self::insert_flashcard(&mut transaction, self.organization_id, new_card).await?;
+        transaction.commit().await?;
+
+        Ok(card)
+    }
+
+    async fn update_flashcard(
+        &self,
+        id: Uuid,
+        updated_card: &UpdatedFlashCard,
+    ) -> Result<FlashCard, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+        let card =
+            Self::apply_update(&mut transaction, self.organization_id, id, updated_card).await?;
+        transaction.commit().await?;
+
+        Ok(card)
+    }
+
+    async fn delete_flashcard(&self, id: Uuid) -> Result<u64, ApiError> {
+        let result = sqlx::query("DELETE FROM flashcards WHERE organization_id = ? AND id = ?;")
+            .bind(self.organization_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn random_flashcards(
+        &self,
+        selection: &RandomSelection,
+    ) -> Result<Vec<FlashCard>, ApiError> {
+        // the database samples the cards, SQLite builds may lack ln() though: every candidate
+        // is repeated as many times as its weight, and the smallest of the random numbers drawn
+        // for its copies orders it, which picks it with a likelihood following its weight. The
+        // excluded cards are dropped from a sample large enough to leave `count` cards
+        let tags: Vec<String> = selection.tags.iter().map(|tag| slugify(tag)).collect();
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "WITH RECURSIVE copies(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM copies WHERE n < ?7)
+            SELECT f.id
+            FROM flashcards f
+            LEFT JOIN topics tp ON tp.id = f.topic_id
+            JOIN copies c ON c.n <= CASE ?6
+                WHEN 'harder' THEN f.difficulty
+                WHEN 'easier' THEN ?7 - f.difficulty
+                ELSE 1 END
+            WHERE f.organization_id = ?5 AND f.status = 'published'
+            AND (?1 IS NULL OR tp.slug = ?1)
+            AND (json_array_length(?2) = 0 OR EXISTS (
+                SELECT 1 FROM flashcard_tags ft JOIN tags t ON t.id = ft.tag_id
+                WHERE ft.flashcard_id = f.id AND t.slug IN (SELECT value FROM json_each(?2))))
+            AND f.difficulty BETWEEN ?3 AND ?4
+            GROUP BY f.id
+            ORDER BY MIN(random())
+            LIMIT ?8;",
+        )
+        .bind(selection.topic.as_deref().map(slugify))
+        .bind(serde_json::to_string(&tags).unwrap_or_default())
+        .bind(selection.min_difficulty)
+        .bind(selection.max_difficulty)
+        .bind(self.organization_id)
+        .bind(selection.weighting.map(|weighting| weighting.as_str()))
+        .bind(MAX_DIFFICULTY + 1)
+        .bind((selection.count + selection.exclude.len()) as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        let ids: Vec<Uuid> = ids
+            .into_iter()
+            .filter(|id| !selection.exclude.contains(id))
+            .take(selection.count)
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!(
+            "{} WHERE f.organization_id = ? AND f.id IN ({}) AND {};",
+            SELECT_FLASHCARDS, placeholders, PUBLISHED
+        );
+        let mut query = sqlx::query_as::<_, FlashCardRow>(&sql).bind(self.organization_id);
+        for id in &ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(in_sampled_order(
+            rows.into_iter()
+                .map(FlashCard::try_from)
+                .collect::<Result<_, _>>()?,
+            &ids,
+        ))
+    }
+
+    async fn daily_flashcard(
+        &self,
+        selection: &DailySelection,
+    ) -> Result<Option<FlashCard>, ApiError> {
+        let scope = selection.scope();
+        let day = selection.day_key();
+        let select_daily = format!(
+            "{} JOIN daily_cards d ON d.flashcard_id = f.id
+            WHERE d.organization_id = ? AND d.scope = ? AND d.day = ? AND {};",
+            SELECT_FLASHCARDS, PUBLISHED
+        );
+        let picked: Option<FlashCardRow> = sqlx::query_as(&select_daily)
+            .bind(self.organization_id)
+            .bind(&scope)
+            .bind(&day)
+            .fetch_optional(&self.pool)
+            .await?;
+        if let Some(row) = picked {
+            return Ok(Some(row.try_into()?));
+        }
+
+        let candidates: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT f.id FROM flashcards f
+            LEFT JOIN topics tp ON tp.id = f.topic_id
+            WHERE f.organization_id = ?2 AND f.status = 'published' AND (?1 = '' OR tp.slug = ?1);",
+        )
+        .bind(&scope)
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let recent: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT flashcard_id FROM daily_cards
+            WHERE organization_id = ? AND scope = ? AND day >= ? AND day < ?;",
+        )
+        .bind(self.organization_id)
+        .bind(&scope)
+        .bind(selection.window_start())
+        .bind(&day)
+        .fetch_all(&self.pool)
+        .await?;
+        let Some(id) = selection.pick(&candidates, &recent) else {
+            return Ok(None);
+        };
+
+        // a concurrent first request may have recorded its pick in the meantime, which wins;
+        // a pick which is no longer published is replaced
+        sqlx::query(
+            "INSERT INTO daily_cards (organization_id, day, scope, flashcard_id) VALUES (?, ?, ?, ?)
+            ON CONFLICT (organization_id, scope, day) DO UPDATE SET flashcard_id = excluded.flashcard_id
+            WHERE NOT EXISTS (SELECT 1 FROM flashcards f
+                WHERE f.id = daily_cards.flashcard_id AND f.status = 'published');",
+        )
+        .bind(self.organization_id)
+        .bind(&day)
+        .bind(&scope)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        let picked: Option<FlashCardRow> = sqlx::query_as(&select_daily)
+            .bind(self.organization_id)
+            .bind(&scope)
+            .bind(&day)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(picked.map(FlashCard::try_from).transpose()?)
+    }
+
+    async fn create_quiz(&self, quiz: &Quiz) -> Result<Quiz, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO quizzes (organization_id, id, topic, tag, created_at) VALUES (?, ?, ?, ?, ?);",
+        )
+        .bind(self.organization_id)
+        .bind(quiz.id)
+            .bind(&quiz.topic)
+            .bind(&quiz.tag)
+            .bind(quiz.created_at)
+            .execute(&mut *transaction)
+            .await?;
+        for (position, question) in quiz.questions.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO quiz_questions (quiz_id, position, flashcard_id, question, options, correct_option)
+                VALUES (?, ?, ?, ?, ?, ?);",
+            )
+            .bind(quiz.id)
+            .bind(position as i64)
+            .bind(question.flashcard_id)
+            .bind(&question.question)
+            .bind(serde_json::to_string(&question.options).unwrap_or_default())
+            .bind(question.correct_option as i64)
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(quiz.clone())
+    }
+
+    async fn get_quiz(&self, id: Uuid) -> Result<Quiz, ApiError> {
+        let (topic, tag, created_at): (Option<String>, Option<String>, SqlxTimestamp) =
+            sqlx::query_as(
+                "SELECT topic, tag, created_at FROM quizzes WHERE organization_id = ? AND id = ?;",
+            )
+            .bind(self.organization_id)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        let rows: Vec<(Uuid, String, String, i64)> = sqlx::query_as(
+            "SELECT flashcard_id, question, options, correct_option
+            FROM quiz_questions
+            WHERE quiz_id = ?
+            ORDER BY position;",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        let questions = rows
+            .into_iter()
+            .map(|(flashcard_id, question, options, correct_option)| {
+                Ok(QuizQuestion {
+                    flashcard_id,
+                    question,
+                    options: decode_json(&options)?,
+                    correct_option: correct_option as usize,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()?;
+
+        Ok(Quiz {
+            id,
+            topic,
+            tag,
+            questions,
+            created_at,
+        })
+    }
+
+    async fn delete_quizzes_before(&self, before: SqlxTimestamp) -> Result<u64, ApiError> {
+        let result = sqlx::query(
+            "DELETE FROM quizzes
+            WHERE organization_id = ? AND julianday(created_at) < julianday(?);",
+        )
+        .bind(self.organization_id)
+        .bind(before)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn save_code_verification(
+        &self,
+        verification: &CodeVerification,
+    ) -> Result<CodeVerification, ApiError> {
+        sqlx::query(
+            "INSERT INTO code_verifications (flashcard_id, status, snippet_count, output, checked_at)
+            SELECT id, ?2, ?3, ?4, ?5 FROM flashcards WHERE organization_id = ?6 AND id = ?1
+            ON CONFLICT (flashcard_id) DO UPDATE
+            SET status = excluded.status, snippet_count = excluded.snippet_count,
+                output = excluded.output, checked_at = excluded.checked_at;",
+        )
+        .bind(verification.flashcard_id)
+        .bind(verification.status.as_str())
+        .bind(verification.snippet_count as i64)
+        .bind(&verification.output)
+        .bind(verification.checked_at)
+        .bind(self.organization_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(verification.clone())
+    }
+
+    async fn get_code_verification(&self, id: Uuid) -> Result<CodeVerification, ApiError> {
+        let verification: CodeVerification = sqlx::query_as(
+            "SELECT flashcard_id, status, snippet_count, output, checked_at
+            FROM code_verifications
+            WHERE flashcard_id IN (SELECT id FROM flashcards WHERE organization_id = ? AND id = ?);",
+        )
+        .bind(self.organization_id)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(verification)
+    }
+
+    async fn create_drafts(
+        &self,
+        drafts: &[FlashcardDraft],
+    ) -> Result<Vec<FlashcardDraft>, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+
+        let mut created = Vec::with_capacity(drafts.len());
+        for draft in drafts {
+            let result = sqlx::query(
+                "INSERT INTO flashcard_drafts (organization_id, id, question, answer, topic, tags, difficulty, source, created_at)
+                SELECT ?9, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+                WHERE NOT EXISTS (SELECT 1 FROM flashcards WHERE organization_id = ?9 AND question = ?2)
+                ON CONFLICT (organization_id, question) DO NOTHING;",
+            )
+            .bind(draft.id)
+            .bind(&draft.question)
+            .bind(&draft.answer)
+            .bind(&draft.topic)
+            .bind(serde_json::to_string(&draft.tags).unwrap_or_default())
+            .bind(draft.difficulty)
+            .bind(&draft.source)
+            .bind(draft.created_at)
+            .bind(self.organization_id)
+            .execute(&mut *transaction)
+            .await?;
+            if result.rows_affected() == 1 {
+                created.push(draft.clone());
+            }
+        }
+        transaction.commit().await?;
+
+        Ok(created)
+    }
+
+    async fn list_drafts(&self) -> Result<Vec<FlashcardDraft>, ApiError> {
+        let rows: Vec<DraftRow> = sqlx::query_as(&format!(
+            "{} WHERE organization_id = ? ORDER BY created_at ASC, source ASC;",
+            SELECT_DRAFTS
+        ))
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(FlashcardDraft::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_draft(&self, id: Uuid) -> Result<FlashcardDraft, ApiError> {
+        let row: DraftRow = sqlx::query_as(&format!(
+            "{} WHERE organization_id = ? AND id = ?;",
+            SELECT_DRAFTS
+        ))
+        .bind(self.organization_id)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(FlashcardDraft::try_from(row)?)
+    }
+
+    async fn delete_draft(&self, id: Uuid) -> Result<u64, ApiError> {
+        let result =
+            sqlx::query("DELETE FROM flashcard_drafts WHERE organization_id = ? AND id = ?;")
+                .bind(self.organization_id)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn create_user(&self, user: &User, token_hash: &str) -> Result<User, ApiError> {
+        sqlx::query(
+            "INSERT INTO users (organization_id, id, name, token_hash, created_at)
+            VALUES (?, ?, ?, ?, ?);",
+        )
+        .bind(self.organization_id)
+        .bind(user.id)
+        .bind(&user.name)
+        .bind(token_hash)
+        .bind(user.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(user.clone())
+    }
+
+    async fn get_user_by_token(&self, token_hash: &str) -> Result<Option<User>, ApiError> {
+        let user: Option<User> = sqlx::query_as(
+            "SELECT id, name, created_at FROM users WHERE organization_id = ? AND token_hash = ?;",
+        )
+        .bind(self.organization_id)
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn create_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        sqlx::query(
+            "INSERT INTO card_suggestions
+                (organization_id, id, user_id, flashcard_id, changes, diff, status, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?);",
+        )
+        .bind(self.organization_id)
+        .bind(suggestion.id)
+        .bind(suggestion.user_id)
+        .bind(suggestion.flashcard_id)
+        .bind(serde_json::to_string(&suggestion.changes).unwrap_or_default())
+        .bind(serde_json::to_string(&suggestion.diff).unwrap_or_default())
+        .bind(suggestion.status.as_str())
+        .bind(suggestion.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_suggestion(suggestion.id).await
+    }
+
+    async fn list_suggestions(
+        &self,
+        status: Option<SuggestionStatus>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Suggestion>, ApiError> {
+        let rows: Vec<SuggestionRow> = sqlx::query_as(&format!(
+            "{} WHERE organization_id = ?3
+                AND (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR user_id = ?2)
+            ORDER BY created_at ASC;",
+            SELECT_SUGGESTIONS
+        ))
+        .bind(status.map(|status| status.as_str()))
+        .bind(user_id)
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(Suggestion::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_suggestion(&self, id: Uuid) -> Result<Suggestion, ApiError> {
+        let row: SuggestionRow = sqlx::query_as(&format!(
+            "{} WHERE organization_id = ? AND id = ?;",
+            SELECT_SUGGESTIONS
+        ))
+        .bind(self.organization_id)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.try_into()?)
+    }
+
+    async fn close_suggestion(&self, suggestion: &Suggestion) -> Result<Suggestion, ApiError> {
+        let result = sqlx::query(
+            "UPDATE card_suggestions SET status = ?, note = ?, reviewed_at = ?
+            WHERE organization_id = ? AND id = ? AND status = 'pending';",
+        )
+        .bind(suggestion.status.as_str())
+        .bind(&suggestion.note)
+        .bind(suggestion.reviewed_at)
+        .bind(self.organization_id)
+        .bind(suggestion.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ApiError::SuggestionClosed);
+        }
+
+        self.get_suggestion(suggestion.id).await
+    }
+
+    async fn approve_suggestion(
+        &self,
+        suggestion: &Suggestion,
+        new_card: Option<&FlashCard>,
+    ) -> Result<(Suggestion, FlashCard), ApiError> {
+        // the suggestion is closed first, so that concurrent approvals apply it once
+        let mut transaction = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE card_suggestions SET status = ?, note = ?, reviewed_at = ?
+            WHERE organization_id = ? AND id = ? AND status = 'pending';",
+        )
+        .bind(suggestion.status.as_str())
+        .bind(&suggestion.note)
+        .bind(suggestion.reviewed_at)
+        .bind(self.organization_id)
+        .bind(suggestion.id)
+        .execute(&mut *transaction)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ApiError::SuggestionClosed);
+        }
+
+        let card = match new_card {
+            Some(new_card) => {
+                Self::insert_flashcard(&mut transaction, self.organization_id, new_card).await?
+            }
+            None => {
+                let id = suggestion.flashcard_id.ok_or(sqlx::Error::RowNotFound)?;
+                Self::apply_update(
+                    &mut transaction,
+                    self.organization_id,
+                    id,
+                    &suggestion.changes,
+                )
+                .await?
+            }
+        };
+        transaction.commit().await?;
+
+        Ok((self.get_suggestion(suggestion.id).await?, card))
+    }
+
+    async fn create_feedback(
+        &self,
+        feedback: &CardFeedback,
+        limit: u32,
+        since: SqlxTimestamp,
+    ) -> Result<CardFeedback, ApiError> {
+        // a single statement, SQLite running one writer at a time, so the count holds until the
+        // entry is stored; timestamps are stored as text, compared as julian days so that their
+        // precision doesn't matter
+        let result = sqlx::query(
+            "INSERT INTO card_feedback (organization_id, id, flashcard_id, kind, comment, client, created_at)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+            WHERE (SELECT COUNT(*) FROM card_feedback
+                WHERE organization_id = ?1 AND client = ?6 AND julianday(created_at) >= julianday(?8)) < ?9;",
+        )
+        .bind(self.organization_id)
+        .bind(feedback.id)
+        .bind(feedback.flashcard_id)
+        .bind(feedback.kind.as_str())
+        .bind(&feedback.comment)
+        .bind(&feedback.client)
+        .bind(feedback.created_at)
+        .bind(since)
+        .bind(i64::from(limit))
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ApiError::FeedbackLimitReached);
+        }
+
+        Ok(feedback.clone())
+    }
+
+    async fn feedback_report(&self) -> Result<Vec<FlaggedCard>, ApiError> {
+        let report: Vec<FlaggedCard> = sqlx::query_as(
+            "SELECT f.id AS flashcard_id, f.question,
+                COUNT(*) FILTER (WHERE fb.kind <> 'upvote' AND fb.resolved_at IS NULL) AS open_flags,
+                COUNT(*) FILTER (WHERE fb.kind = 'upvote') AS upvotes
+            FROM flashcards f
+            JOIN card_feedback fb ON fb.flashcard_id = f.id
+            WHERE f.organization_id = ?
+            GROUP BY f.id
+            HAVING open_flags > 0
+            ORDER BY open_flags DESC, f.question ASC;",
+        )
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    async fn list_open_flags(&self, flashcard_id: Uuid) -> Result<Vec<CardFeedback>, ApiError> {
+        let flags: Vec<CardFeedback> = sqlx::query_as(&format!(
+            "{} WHERE organization_id = ? AND flashcard_id = ?
+                AND kind <> 'upvote' AND resolved_at IS NULL
+            ORDER BY created_at ASC;",
+            SELECT_FEEDBACK
+        ))
+        .bind(self.organization_id)
+        .bind(flashcard_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    async fn resolve_flags(
+        &self,
+        flashcard_id: Uuid,
+        resolved_at: SqlxTimestamp,
+    ) -> Result<u64, ApiError> {
+        let result = sqlx::query(
+            "UPDATE card_feedback SET resolved_at = ?
+            WHERE organization_id = ? AND flashcard_id = ?
+                AND kind <> 'upvote' AND resolved_at IS NULL;",
+        )
+        .bind(resolved_at)
+        .bind(self.organization_id)
+        .bind(flashcard_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn create_study_review(&self, review: &StudyReview) -> Result<StudyReview, ApiError> {
+        sqlx::query(
+            "INSERT INTO study_reviews (organization_id, id, user_id, flashcard_id, grade, reviewed_at)
+            VALUES (?, ?, ?, ?, ?, ?);",
+        )
+        .bind(self.organization_id)
+        .bind(review.id)
+        .bind(review.user_id)
+        .bind(review.flashcard_id)
+        .bind(review.grade.as_str())
+        .bind(review.reviewed_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(review.clone())
+    }
+
+    async fn list_study_reviews(&self, user_id: Uuid) -> Result<Vec<StudyReview>, ApiError> {
+        let reviews: Vec<StudyReview> = sqlx::query_as(
+            "SELECT id, user_id, flashcard_id, grade, reviewed_at FROM study_reviews
+            WHERE organization_id = ? AND user_id = ?
+            ORDER BY julianday(reviewed_at) ASC;",
+        )
+        .bind(self.organization_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reviews)
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, ApiError> {
+        let sql = format!(
+            "{} WHERE t.organization_id = ? GROUP BY t.id ORDER BY t.slug ASC;",
+            SELECT_TAGS
+        );
+        let tags: Vec<Tag> = sqlx::query_as(&sql)
+            .bind(self.organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(tags)
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>, ApiError> {
+        let topics: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT tp.name FROM flashcards f JOIN topics tp ON tp.id = f.topic_id WHERE f.organization_id = ? AND f.status = 'published' ORDER BY tp.name ASC;",
+        )
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(topics)
+    }
+
+    async fn update_tag(&self, slug: &str, updated_tag: &UpdatedTag) -> Result<Tag, ApiError> {
+        let name = updated_tag.name.as_deref().map(str::trim);
+        let new_slug: String = sqlx::query_scalar(
+            "UPDATE tags
+            SET name = COALESCE(?, name), slug = COALESCE(?, slug), description = COALESCE(?, description)
+            WHERE organization_id = ? AND slug = ?
+            RETURNING slug;",
+        )
+        .bind(name)
+        .bind(name.map(slugify))
+        .bind(&updated_tag.description)
+        .bind(self.organization_id)
+        .bind(slug)
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.fetch_tag(&new_slug).await
+    }
+
+    async fn merge_tags(&self, source: &str, target: &str) -> Result<Tag, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+
+        let select_tag = "SELECT id FROM tags WHERE organization_id = ? AND slug = ?;";
+        let source_id: Uuid = sqlx::query_scalar(select_tag)
+            .bind(self.organization_id)
+            .bind(source)
+            .fetch_one(&mut *transaction)
+            .await?;
+        let target_id: Uuid = sqlx::query_scalar(select_tag)
+            .bind(self.organization_id)
+            .bind(target)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        if source_id != target_id {
+            sqlx::query(
+                "INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+                SELECT flashcard_id, ?, position
+                FROM flashcard_tags
+                WHERE tag_id = ?
+                ON CONFLICT DO NOTHING;",
+            )
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query("DELETE FROM tags WHERE id = ?;")
+                .bind(source_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        self.fetch_tag(target).await
+    }
+
+    async fn flashcard_stats(&self) -> Result<FlashcardStats, ApiError> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM flashcards WHERE organization_id = ? AND status = 'published';",
+        )
+        .bind(self.organization_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let topics: Vec<TopicStats> = sqlx::query_as(
+            "SELECT t.slug, t.name, COUNT(f.id) AS card_count, AVG(f.difficulty) AS average_difficulty
+            FROM topics t
+            LEFT JOIN flashcards f ON f.topic_id = t.id AND f.status = 'published'
+            WHERE t.organization_id = ?
+            GROUP BY t.id
+            ORDER BY card_count DESC, t.slug ASC;",
+        )
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let tags: Vec<TagStats> = sqlx::query_as(&format!(
+            "SELECT slug, name, card_count FROM ({} WHERE t.organization_id = ? GROUP BY t.id)
+            ORDER BY card_count DESC, slug ASC;",
+            SELECT_TAGS
+        ))
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let difficulties: Vec<DifficultyStats> = sqlx::query_as(
+            "SELECT difficulty, COUNT(*) AS card_count
+            FROM flashcards
+            WHERE organization_id = ? AND difficulty IS NOT NULL AND status = 'published'
+            GROUP BY difficulty
+            ORDER BY difficulty ASC;",
+        )
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // timestamps are stored as RFC 3339 text; the Monday of the week is found by moving
+        // to the following Sunday (or staying on it) and going back six days
+        let weeks: Vec<WeekStats> = sqlx::query_as(
+            "SELECT date(substr(created_at, 1, 10), 'weekday 0', '-6 days') AS week,
+                COUNT(*) AS card_count
+            FROM flashcards
+            WHERE organization_id = ? AND status = 'published'
+            GROUP BY week
+            ORDER BY week ASC;",
+        )
+        .bind(self.organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(FlashcardStats {
+            total,
+            topics,
+            tags,
+            difficulties,
+            weeks,
+        })
+    }
+
+    async fn list_topic_details(&self) -> Result<Vec<Topic>, ApiError> {
+        let sql = format!(
+            "{} WHERE t.organization_id = ? GROUP BY t.id, p.slug ORDER BY t.slug ASC;",
+            SELECT_TOPICS
+        );
+        let topics: Vec<Topic> = sqlx::query_as(&sql)
+            .bind(self.organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(topics)
+    }
+
+    async fn create_topic(&self, new_topic: &NewTopic) -> Result<Topic, ApiError> {
+        let parent = new_topic.parent.as_deref().map(slugify);
+        let parent_id = self.find_topic_id(parent.as_deref()).await?;
+        let name = new_topic.name.trim();
+        let slug: String = sqlx::query_scalar(
+            "INSERT INTO topics (organization_id, id, slug, name, description, parent_id)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING slug;",
+        )
+        .bind(self.organization_id)
+        .bind(Uuid::new_v4())
+        .bind(slugify(name))
+        .bind(name)
+        .bind(&new_topic.description)
+        .bind(parent_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.fetch_topic(&slug).await
+    }
+
+    async fn update_topic(
+        &self,
+        slug: &str,
+        updated_topic: &UpdatedTopic,
+    ) -> Result<Topic, ApiError> {
+        let parent = match &updated_topic.parent {
+            Some(parent) => {
+                let parent = parent.as_deref().map(slugify);
+                let sql = format!(
+                    "SELECT EXISTS (SELECT 1 FROM topics
+                        WHERE organization_id = ?1 AND slug = ?3 AND id IN ({}));",
+                    TOPIC_SUBTREE
+                );
+                let moves_under_itself: bool = sqlx::query_scalar(&sql)
+                    .bind(self.organization_id)
+                    .bind(slug)
+                    .bind(&parent)
+                    .fetch_one(&self.pool)
+                    .await?;
+                if moves_under_itself {
+                    return Err(topic_cycle_error().into());
+                }
+                Some(self.find_topic_id(parent.as_deref()).await?)
+            }
+            None => None,
+        };
+
+        let name = updated_topic.name.as_deref().map(str::trim);
+        let new_slug: String = sqlx::query_scalar(
+            "UPDATE topics
+            SET name = COALESCE(?, name), slug = COALESCE(?, slug), description = COALESCE(?, description),
+                parent_id = CASE WHEN ? THEN ? ELSE parent_id END
+            WHERE organization_id = ? AND slug = ?
+            RETURNING slug;",
+        )
+        .bind(name)
+        .bind(name.map(slugify))
+        .bind(&updated_topic.description)
+        .bind(parent.is_some())
+        .bind(parent.flatten())
+        .bind(self.organization_id)
+        .bind(slug)
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.fetch_topic(&new_slug).await
+    }
+}
+
+// implement the OrganizationRepository trait with SQLite queries
+impl OrganizationRepository for SqliteFlashcardRepository {
+    async fn create_organization(
+        &self,
+        organization: &Organization,
+        key_hash: &str,
+    ) -> Result<Organization, ApiError> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query("INSERT INTO organizations (id, slug, name, created_at) VALUES (?, ?, ?, ?);")
+            .bind(organization.id)
+            .bind(&organization.slug)
+            .bind(&organization.name)
+            .bind(organization.created_at)
+            .execute(&mut *transaction)
+            .await?;
+        for host in &organization.hosts {
+            sqlx::query("INSERT INTO organization_hosts (host, organization_id) VALUES (?, ?);")
+                .bind(host)
+                .bind(organization.id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        sqlx::query(
+            "INSERT INTO organization_api_keys (key_hash, organization_id, created_at)
+            VALUES (?, ?, ?);",
+        )
+        .bind(key_hash)
+        .bind(organization.id)
+        .bind(organization.created_at)
+        .execute(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+
+        self.get_organization(&organization.slug).await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, ApiError> {
+        let rows: Vec<OrganizationRow> =
+            sqlx::query_as(&format!("{} ORDER BY o.slug ASC;", SELECT_ORGANIZATIONS))
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(Organization::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_organization(&self, slug: &str) -> Result<Organization, ApiError> {
+        let row: OrganizationRow =
+            sqlx::query_as(&format!("{} WHERE o.slug = ?;", SELECT_ORGANIZATIONS))
+                .bind(slug)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(row.try_into()?)
+    }
+
+    async fn add_api_key(&self, organization_id: Uuid, key_hash: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO organization_api_keys (key_hash, organization_id, created_at)
+            VALUES (?, ?, ?);",
+        )
+        .bind(key_hash)
+        .bind(organization_id)
+        .bind(PavexTimestamp::now().to_sqlx())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_organization_by_api_key(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        let row: Option<OrganizationRow> = sqlx::query_as(&format!(
+            "{} JOIN organization_api_keys k ON k.organization_id = o.id WHERE k.key_hash = ?;",
+            SELECT_ORGANIZATIONS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Organization::try_from).transpose()?)
+    }
+
+    async fn find_organization_by_host(
+        &self,
+        host: &str,
+    ) -> Result<Option<Organization>, ApiError> {
+        let row: Option<OrganizationRow> = sqlx::query_as(&format!(
+            "{} JOIN organization_hosts oh ON oh.organization_id = o.id WHERE oh.host = ?;",
+            SELECT_ORGANIZATIONS
+        ))
+        .bind(host)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Organization::try_from).transpose()?)
+    }
+}
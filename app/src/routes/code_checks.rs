@@ -0,0 +1,125 @@
+// app/src/routes/code_checks.rs
+
+// dependencies
+use crate::code_check::verify_flashcard;
+use crate::configuration::CodeCheckConfig;
+use crate::errors::ApiError;
+use crate::models::{CodeStatus, CodeVerification};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::FlashCardParams;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent the last compile-check of the snippets of a flash card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CodeVerificationContent {
+    pub flashcard_id: Uuid,
+    pub status: CodeStatus,
+    pub snippet_count: usize,
+    pub output: String,
+    pub checked_at: String,
+}
+
+// implement the From trait to convert the CodeVerification type into a CodeVerificationContent type
+impl From<CodeVerification> for CodeVerificationContent {
+    fn from(verification: CodeVerification) -> Self {
+        Self {
+            flashcard_id: verification.flashcard_id,
+            status: verification.status,
+            snippet_count: verification.snippet_count,
+            output: verification.output,
+            checked_at: verification.checked_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a response wrapping the compile-check of a flash card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CodeVerificationResponse {
+    pub msg: String,
+    pub content: CodeVerificationContent,
+}
+
+// struct type to represent the outcome of compile-checking every flash card, along with the
+// details of the cards whose snippets failed
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CodeCheckReport {
+    pub checked: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub no_code: usize,
+    pub failures: Vec<CodeVerificationContent>,
+}
+
+// struct type to represent a response wrapping the compile-check report
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CodeCheckReportResponse {
+    pub msg: String,
+    pub content: CodeCheckReport,
+}
+
+// handler which compile-checks the Rust snippets of a flash card and stores the outcome
+pub async fn verify_code_handler(
+    repo: &FlashcardStore,
+    config: &CodeCheckConfig,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let flash_card = repo.get_flashcard_any_status(id).await?;
+    let verification = verify_flashcard(config, &flash_card).await?;
+    let saved = repo.save_code_verification(&verification).await?;
+
+    let response_body = CodeVerificationResponse {
+        msg: "success".to_string(),
+        content: CodeVerificationContent::from(saved),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the last compile-check of the snippets of a flash card
+pub async fn get_code_verification_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let verification = repo.get_code_verification(id).await?;
+
+    let response_body = CodeVerificationResponse {
+        msg: "success".to_string(),
+        content: CodeVerificationContent::from(verification),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which runs the validation job: the snippets of every flash card, whatever its status,
+// are compile-checked, one card after the other, and the outcomes stored
+pub async fn verify_all_code_handler(
+    repo: &FlashcardStore,
+    config: &CodeCheckConfig,
+) -> Result<Response, ApiError> {
+    let mut report = CodeCheckReport::default();
+    for flash_card in repo.list_all_flashcards(None).await? {
+        let verification = verify_flashcard(config, &flash_card).await?;
+        let saved = repo.save_code_verification(&verification).await?;
+        report.checked += 1;
+        match saved.status {
+            CodeStatus::Passed => report.passed += 1,
+            CodeStatus::NoCode => report.no_code += 1,
+            CodeStatus::Failed => {
+                report.failed += 1;
+                report.failures.push(CodeVerificationContent::from(saved));
+            }
+        }
+    }
+
+    let response_body = CodeCheckReportResponse {
+        msg: "success".to_string(),
+        content: report,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
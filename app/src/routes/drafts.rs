@@ -0,0 +1,145 @@
+// app/src/routes/drafts.rs
+
+// dependencies
+use crate::configuration::ImportConfig;
+use crate::errors::ApiError;
+use crate::models::{FlashCard, FlashcardDraft, RustdocImport};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::{FlashCardContent, FlashCardParams, FlashCardResponse};
+use crate::rustdoc::draft_cards;
+use crate::validation::{MIN_DIFFICULTY, rustdoc_error, validate_rustdoc_import};
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent a draft flash card waiting for review
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DraftContent {
+    pub id: Uuid,
+    pub question: String,
+    pub answer: String,
+    pub topic: String,
+    pub tags: Vec<String>,
+    pub difficulty: i32,
+    pub source: String,
+    pub created_at: String,
+}
+
+// implement the From trait to convert the FlashcardDraft type into a DraftContent type
+impl From<FlashcardDraft> for DraftContent {
+    fn from(draft: FlashcardDraft) -> Self {
+        Self {
+            id: draft.id,
+            question: draft.question,
+            answer: draft.answer,
+            topic: draft.topic,
+            tags: draft.tags,
+            difficulty: draft.difficulty,
+            source: draft.source,
+            created_at: draft.created_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a response wrapping a list of draft flash cards
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DraftsResponse {
+    pub msg: String,
+    pub content: Vec<DraftContent>,
+}
+
+// struct type to represent the outcome of an import: the number of documented items found, and
+// the drafts created for those whose question isn't asked yet
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DraftImport {
+    pub documented: usize,
+    pub created: usize,
+    pub drafts: Vec<DraftContent>,
+}
+
+// struct type to represent a response wrapping the outcome of an import
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DraftImportResponse {
+    pub msg: String,
+    pub content: DraftImport,
+}
+
+// handler which generates draft flash cards from the rustdoc JSON output of a crate
+pub async fn import_rustdoc_handler(
+    repo: &FlashcardStore,
+    config: &ImportConfig,
+    body: &JsonBody<RustdocImport>,
+) -> Result<Response, ApiError> {
+    validate_rustdoc_import(&body.0)?;
+    let contents = tokio::fs::read_to_string(config.rustdoc_dir.join(&body.0.path))
+        .await
+        .map_err(|_| rustdoc_error())?;
+    let document: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|_| rustdoc_error())?;
+    let topic = body.0.topic.as_deref().map(str::trim);
+    let difficulty = body.0.difficulty.unwrap_or(MIN_DIFFICULTY);
+    let drafts = draft_cards(&document, topic, difficulty).ok_or_else(rustdoc_error)?;
+    let created = repo.create_drafts(&drafts).await?;
+
+    let response_body = DraftImportResponse {
+        msg: "success".to_string(),
+        content: DraftImport {
+            documented: drafts.len(),
+            created: created.len(),
+            drafts: created.into_iter().map(DraftContent::from).collect(),
+        },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves every draft flash card
+pub async fn list_drafts_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let drafts = repo.list_drafts().await?;
+
+    let response_body = DraftsResponse {
+        msg: "success".to_string(),
+        content: drafts.into_iter().map(DraftContent::from).collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which turns a draft into a flash card, once an editor has reviewed it; like any new
+// card, it still has to go through the review workflow before it is published
+pub async fn accept_draft_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let draft = repo.get_draft(id).await?;
+    let new_flash_card = FlashCard::try_from(draft.to_new_flashcard())?;
+    let created_flash_card = repo.create_flashcard(&new_flash_card).await?;
+    repo.delete_draft(id).await?;
+
+    let response_body = FlashCardResponse {
+        msg: "success".to_string(),
+        content: FlashCardContent::from(created_flash_card),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which discards a draft flash card, given an id
+pub async fn delete_draft_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let deleted = repo.delete_draft(id).await?;
+    if deleted == 0 {
+        return Err(ApiError::NotFound(format!(
+            "Draft with id {} not found",
+            id
+        )));
+    }
+
+    Ok(Response::no_content())
+}
@@ -0,0 +1,155 @@
+// app/src/routes/feedback.rs
+
+// dependencies
+use crate::configuration::{FeedbackConfig, ProxyConfig};
+use crate::errors::ApiError;
+use crate::identity::client_id;
+use crate::models::{CardFeedback, FeedbackKind, FlaggedCard, NewFeedback};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::FlashCardParams;
+use crate::validation::validate_new_feedback;
+use jiff_sqlx::ToSqlx;
+use pavex::connection::ConnectionInfo;
+use pavex::request::RequestHead;
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent feedback left on a card; the client who left it is not shown
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeedbackContent {
+    pub id: Uuid,
+    pub flashcard_id: Uuid,
+    pub kind: FeedbackKind,
+    pub comment: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+// implement the From trait to convert the CardFeedback type into a FeedbackContent type
+impl From<CardFeedback> for FeedbackContent {
+    fn from(feedback: CardFeedback) -> Self {
+        Self {
+            id: feedback.id,
+            flashcard_id: feedback.flashcard_id,
+            kind: feedback.kind,
+            comment: feedback.comment,
+            created_at: feedback.created_at.to_jiff().to_string(),
+            resolved_at: feedback
+                .resolved_at
+                .map(|resolved_at| resolved_at.to_jiff().to_string()),
+        }
+    }
+}
+
+// struct type to represent a feedback response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeedbackResponse {
+    pub msg: String,
+    pub content: FeedbackContent,
+}
+
+// struct type to represent a response wrapping a list of feedback entries
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeedbackListResponse {
+    pub msg: String,
+    pub content: Vec<FeedbackContent>,
+}
+
+// struct type to represent a response wrapping the feedback report
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct FeedbackReportResponse {
+    pub msg: String,
+    pub content: Vec<FlaggedCard>,
+}
+
+// struct type to represent the number of flags resolved on a card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ResolvedFlags {
+    pub resolved: u64,
+}
+
+// struct type to represent a response wrapping the number of flags resolved on a card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ResolvedFlagsResponse {
+    pub msg: String,
+    pub content: ResolvedFlags,
+}
+
+// handler which records a flag or an upvote left on a published card, provided the client
+// hasn't sent too much feedback within the configured window
+pub async fn leave_feedback_handler(
+    repo: &FlashcardStore,
+    config: &FeedbackConfig,
+    request: &RequestHead,
+    connection_info: &ConnectionInfo,
+    proxy_config: &ProxyConfig,
+    body: &JsonBody<NewFeedback>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    validate_new_feedback(&body.0)?;
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let flash_card = repo.get_flashcard(id).await?;
+
+    let client = client_id(request, connection_info, proxy_config);
+    let since = (Timestamp::now() - config.window).to_sqlx();
+    let feedback = CardFeedback::new(flash_card.id, &client, &body.0);
+    let created = repo.create_feedback(&feedback, config.limit, since).await?;
+
+    let response_body = FeedbackResponse {
+        msg: "success".to_string(),
+        content: FeedbackContent::from(created),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the cards with open flags, the most flagged first
+pub async fn feedback_report_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let report = repo.feedback_report().await?;
+
+    let response_body = FeedbackReportResponse {
+        msg: "success".to_string(),
+        content: report,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the open flags of a card, oldest first
+pub async fn list_flags_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    // a missing card is reported, rather than an empty list
+    repo.get_flashcard_any_status(id).await?;
+    let flags = repo.list_open_flags(id).await?;
+
+    let response_body = FeedbackListResponse {
+        msg: "success".to_string(),
+        content: flags.into_iter().map(FeedbackContent::from).collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which resolves the open flags of a card, once an editor has dealt with them
+pub async fn resolve_flags_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    repo.get_flashcard_any_status(id).await?;
+    let resolved = repo.resolve_flags(id, Timestamp::now().to_sqlx()).await?;
+
+    let response_body = ResolvedFlagsResponse {
+        msg: "success".to_string(),
+        content: ResolvedFlags { resolved },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
@@ -1,19 +1,26 @@
 // app/src/routes/flashcards.rs
 
 // dependencies
-use crate::configuration::DatabaseConfig;
+use crate::configuration::DailyConfig;
 use crate::errors::ApiError;
-use crate::models::{FlashCard, NewFlashCard, UpdatedFlashCard};
-use crate::queries::{
-    create_flashcard, delete_flashcard, list_flashcard, list_flashcards, list_flashcards_by_tag,
-    list_flashcards_by_topic, list_tags, list_topics, random_flashcard, update_flashcard,
+use crate::markdown::render_markdown;
+use crate::models::{
+    AnswerAttempt, AnswerCheck, CardKind, CardStatus, ClozeCard, DailySelection,
+    DifficultyWeighting, FlashCard, FlashcardStats, NewFlashCard, RandomSelection, Tag,
+    UpdatedFlashCard, cloze_cards,
+};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::{
+    cloze_number_error, time_zone_error, validate_answer_attempt, validate_card_kind,
+    validate_random_selection, validate_updated_flashcard,
 };
 use pavex::request::body::JsonBody;
 use pavex::request::path::PathParams;
 use pavex::request::query::QueryParams;
 use pavex::response::{Response, body::Json};
+use pavex::time::Timestamp;
+use pavex::time::tz::TimeZone;
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgDatabaseError;
 use uuid::Uuid;
 
 // struct type to represent the path parameters of an incoming request
@@ -22,30 +29,114 @@ pub struct FlashCardParams {
     pub id: String,
 }
 
-// struct type to represent the query parameters of an incoming request
+// struct type to represent the query parameters of an incoming request; with `descendants`,
+// a topic search includes the flash cards filed under its subtopics
 #[derive(Deserialize)]
 pub struct SearchParams {
     pub topic: Option<String>,
     pub tag: Option<String>,
+    #[serde(default)]
+    pub descendants: bool,
+}
+
+// struct type to represent the query parameters of a random selection; `tags` and `exclude`
+// are comma separated lists, and `count` switches the response to a list of distinct cards
+#[derive(Debug, Default, Deserialize)]
+pub struct RandomParams {
+    pub topic: Option<String>,
+    pub tags: Option<String>,
+    pub min_difficulty: Option<i32>,
+    pub max_difficulty: Option<i32>,
+    pub count: Option<usize>,
+    pub exclude: Option<String>,
+    pub weighting: Option<DifficultyWeighting>,
 }
 
-// struct type to represent the data for a flash card
+// implement the TryFrom trait to turn the query parameters into the selection criteria,
+// filling in the defaults
+impl TryFrom<&RandomParams> for RandomSelection {
+    type Error = ApiError;
+
+    fn try_from(params: &RandomParams) -> Result<Self, Self::Error> {
+        let defaults = RandomSelection::default();
+        let exclude = comma_separated(params.exclude.as_deref())
+            .map(Uuid::parse_str)
+            .collect::<Result<Vec<Uuid>, _>>()
+            .map_err(ApiError::UuidError)?;
+        let selection = RandomSelection {
+            topic: params.topic.clone(),
+            tags: comma_separated(params.tags.as_deref())
+                .map(str::to_string)
+                .collect(),
+            min_difficulty: params.min_difficulty.unwrap_or(defaults.min_difficulty),
+            max_difficulty: params.max_difficulty.unwrap_or(defaults.max_difficulty),
+            count: params.count.unwrap_or(defaults.count),
+            exclude,
+            weighting: params.weighting,
+        };
+        validate_random_selection(&selection)?;
+
+        Ok(selection)
+    }
+}
+
+// the non-empty items of an optional comma separated list
+fn comma_separated(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+// struct type to represent the query parameters of the card of the day; `tz` is the IANA time
+// zone deciding when the day starts, UTC by default
+#[derive(Debug, Default, Deserialize)]
+pub struct DailyParams {
+    pub tz: Option<String>,
+    pub topic: Option<String>,
+}
+
+// struct type to represent the data for a flash card; the question and answer are Markdown,
+// also rendered to sanitized HTML, and a cloze card comes with its sub-cards, one per deletion
+// number. Only published cards are shown on the public API, the status matters to editors
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 pub struct FlashCardContent {
     pub id: Uuid,
     pub question: String,
     pub answer: String,
+    #[serde(default)]
+    pub question_html: String,
+    #[serde(default)]
+    pub answer_markdown: String,
+    #[serde(default)]
+    pub answer_html: String,
     pub topic: String,
+    #[serde(default)]
+    pub kind: CardKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cloze_cards: Vec<ClozeCard>,
+    #[serde(default)]
+    pub status: CardStatus,
 }
 
 // implement the From trait to convert the FlashCard type into a FlashCardResponse type
 impl From<FlashCard> for FlashCardContent {
     fn from(card: FlashCard) -> Self {
+        let cloze_cards = match card.kind {
+            CardKind::Cloze => cloze_cards(&card.question),
+            _ => Vec::new(),
+        };
         Self {
             id: card.id,
+            question_html: render_markdown(&card.question),
+            answer_markdown: card.answer.clone(),
+            answer_html: render_markdown(&card.answer),
             question: card.question,
             answer: card.answer,
             topic: card.topic,
+            kind: card.kind,
+            cloze_cards,
+            status: card.status,
         }
     }
 }
@@ -57,11 +148,11 @@ pub struct FlashCardResponse {
     pub content: FlashCardContent,
 }
 
-// struct type to represent a response wrapping the list of topics
+// struct type to represent a response wrapping the list of tags, with their usage counts
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 pub struct TagsResponse {
     pub msg: String,
-    pub content: Vec<String>,
+    pub content: Vec<Tag>,
 }
 
 // struct type to represent a response wrapping the list of topics
@@ -71,19 +162,34 @@ pub struct TopicsResponse {
     pub content: Vec<String>,
 }
 
+// struct type to represent a response wrapping the verdict on a typed answer
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct AnswerCheckResponse {
+    pub msg: String,
+    pub content: AnswerCheck,
+}
+
+// struct type to represent a response wrapping the flash card statistics
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct StatsResponse {
+    pub msg: String,
+    pub content: FlashcardStats,
+}
+
 // handler which lists all the flash cards in the database; accepts query parameters
-// either topic or tag and returns the appropriate results
+// either topic (optionally with its descendants) or tag and returns the appropriate results
 pub async fn list_flashcards_handler(
-    db: &DatabaseConfig,
+    repo: &FlashcardStore,
     params: &QueryParams<SearchParams>,
 ) -> Result<Response, ApiError> {
-    let pool = db.get_pool().await;
-
     let flash_cards = match &params.0.topic {
-        Some(topic) => list_flashcards_by_topic(pool, topic).await?,
+        Some(topic) => {
+            repo.list_flashcards_by_topic(topic, params.0.descendants)
+                .await?
+        }
         None => match &params.0.tag {
-            Some(tag) => list_flashcards_by_tag(pool, tag).await?,
-            None => list_flashcards(pool).await?,
+            Some(tag) => repo.list_flashcards_by_tag(tag).await?,
+            None => repo.list_flashcards().await?,
         },
     };
 
@@ -100,12 +206,11 @@ pub async fn list_flashcards_handler(
 
 // handler which retrieves a flash card by id from the database
 pub async fn list_flashcard_handler(
-    db: &DatabaseConfig,
+    repo: &FlashcardStore,
     params: &PathParams<FlashCardParams>,
 ) -> Result<Response, ApiError> {
     let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
-    let pool = db.get_pool().await;
-    let flash_card = list_flashcard(pool, id).await?;
+    let flash_card = repo.get_flashcard(id).await?;
     let response_body: FlashCardResponse = FlashCardResponse {
         msg: "success".to_string(),
         content: FlashCardContent::from(flash_card),
@@ -114,11 +219,29 @@ pub async fn list_flashcard_handler(
     Ok(Response::ok().set_typed_body(json))
 }
 
-// handler which retrieves a list of flash card tags from the database
-pub async fn list_flashcard_tags_handler(db: &DatabaseConfig) -> Result<Response, ApiError> {
-    let pool = db.get_pool().await;
+// handler which grades an answer typed by a learner against the answer of a flash card
+pub async fn check_answer_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<AnswerAttempt>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    validate_answer_attempt(&body.0)?;
+    let flash_card = repo.get_flashcard(id).await?;
+    let check = flash_card
+        .check_answer(&body.0)
+        .ok_or_else(cloze_number_error)?;
+    let response_body = AnswerCheckResponse {
+        msg: "success".to_string(),
+        content: check,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
 
-    let tags: Vec<String> = list_tags(pool).await?;
+// handler which retrieves a list of flash card tags from the database
+pub async fn list_flashcard_tags_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let tags: Vec<Tag> = repo.list_tags().await?;
 
     let response_body: TagsResponse = TagsResponse {
         msg: "success".to_string(),
@@ -131,10 +254,8 @@ pub async fn list_flashcard_tags_handler(db: &DatabaseConfig) -> Result<Response
 }
 
 // handler which retrieves a list of flash card topics from the database
-pub async fn list_flashcard_topics_handler(db: &DatabaseConfig) -> Result<Response, ApiError> {
-    let pool = db.get_pool().await;
-
-    let topics: Vec<String> = list_topics(pool).await?;
+pub async fn list_flashcard_topics_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let topics: Vec<String> = repo.list_topics().await?;
 
     let response_body: TopicsResponse = TopicsResponse {
         msg: "success".to_string(),
@@ -146,27 +267,27 @@ pub async fn list_flashcard_topics_handler(db: &DatabaseConfig) -> Result<Respon
     Ok(Response::ok().set_typed_body(json))
 }
 
-// handler which creates a new flash card in the database
+// handler which retrieves aggregate statistics over the flash cards, to spot coverage gaps
+pub async fn flashcard_stats_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let stats = repo.flashcard_stats().await?;
+
+    let response_body: StatsResponse = StatsResponse {
+        msg: "success".to_string(),
+        content: stats,
+    };
+
+    let json = Json::new(response_body)?;
+
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which creates a new flash card in the database, as a draft
 pub async fn create_flashcard_handler(
-    db: &DatabaseConfig,
+    repo: &FlashcardStore,
     body: &JsonBody<NewFlashCard>,
 ) -> Result<Response, ApiError> {
-    let pool = db.get_pool().await;
     let new_flash_card = FlashCard::try_from(body.0.clone())?;
-    let created_flash_card = match create_flashcard(pool, &new_flash_card).await {
-        Ok(card) => card,
-        Err(e) => {
-            if let sqlx::Error::Database(db_err) = &e {
-                if let Some(pg_err) = db_err.try_downcast_ref::<PgDatabaseError>() {
-                    if pg_err.constraint() == Some("flashcards_question_key") {
-                        return Err(ApiError::DuplicateQuestion(new_flash_card.question));
-                    }
-                }
-            }
-
-            return Err(ApiError::from(e)); // use the original error
-        }
-    };
+    let created_flash_card = repo.create_flashcard(&new_flash_card).await?;
     let response_body: FlashCardResponse = FlashCardResponse {
         msg: "success".to_string(),
         content: FlashCardContent::from(created_flash_card),
@@ -177,13 +298,13 @@ pub async fn create_flashcard_handler(
 
 // handler which updates a flash card in the database, given and id
 pub async fn update_flashcard_handler(
-    db: &DatabaseConfig,
+    repo: &FlashcardStore,
     body: &JsonBody<UpdatedFlashCard>,
     params: &PathParams<FlashCardParams>,
 ) -> Result<Response, ApiError> {
     let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
-    let pool = db.get_pool().await;
-    let updated_flash_card = update_flashcard(pool, id, &body.0).await?;
+    validate_update(repo, id, &body.0).await?;
+    let updated_flash_card = repo.update_flashcard(id, &body.0).await?;
     let response_body: FlashCardResponse = FlashCardResponse {
         msg: "success".to_string(),
         content: FlashCardContent::from(updated_flash_card),
@@ -192,14 +313,32 @@ pub async fn update_flashcard_handler(
     Ok(Response::ok().set_typed_body(json))
 }
 
+// validate changes to a flash card; shared by the moderation of suggestions
+pub(crate) async fn validate_update(
+    repo: &FlashcardStore,
+    id: Uuid,
+    updated: &UpdatedFlashCard,
+) -> Result<(), ApiError> {
+    validate_updated_flashcard(updated)?;
+    if updated.kind.is_some() || updated.question.is_some() {
+        // the kind of a card and its question are checked together, e.g. a cloze question
+        // must keep at least one deletion
+        let current = repo.get_flashcard_any_status(id).await?;
+        let kind = updated.kind.as_ref().unwrap_or(&current.kind);
+        let question = updated.question.as_deref().unwrap_or(&current.question);
+        validate_card_kind(kind, question.trim())?;
+    }
+
+    Ok(())
+}
+
 // handler which deletes a flash card from the database, given an id
 pub async fn delete_flashcard_handler(
-    db: &DatabaseConfig,
+    repo: &FlashcardStore,
     params: &PathParams<FlashCardParams>,
 ) -> Result<Response, ApiError> {
     let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
-    let pool = db.get_pool().await;
-    let deleted = delete_flashcard(pool, id).await?;
+    let deleted = repo.delete_flashcard(id).await?;
     if deleted == 0 {
         return Err(ApiError::NotFound(format!(
             "Flashcard with id {} not found",
@@ -210,13 +349,50 @@ pub async fn delete_flashcard_handler(
     Ok(Response::no_content())
 }
 
-// handler which retrieves a random flash card from the database
-pub async fn random_flashcard_handler(db: &DatabaseConfig) -> Result<Response, ApiError> {
-    let pool = db.get_pool().await;
+// handler which retrieves random flash cards from the database; without `count` a single card
+// is returned, otherwise a list of up to `count` distinct cards
+pub async fn random_flashcard_handler(
+    repo: &FlashcardStore,
+    params: &QueryParams<RandomParams>,
+) -> Result<Response, ApiError> {
+    let selection = RandomSelection::try_from(&params.0)?;
+    let random_cards = repo.random_flashcards(&selection).await?;
+    if random_cards.is_empty() {
+        return Err(ApiError::NotFound("No flashcards available".into()));
+    }
 
-    let random_card = random_flashcard(pool).await?;
+    let mut responses: Vec<FlashCardResponse> = random_cards
+        .into_iter()
+        .map(|card| FlashCardResponse {
+            msg: "success".to_string(),
+            content: FlashCardContent::from(card),
+        })
+        .collect();
+    let json = match params.0.count {
+        Some(_) => Json::new(responses)?,
+        None => Json::new(responses.swap_remove(0))?,
+    };
+    Ok(Response::ok().set_typed_body(json))
+}
 
-    match random_card {
+// handler which retrieves the card of the day, the same for everyone on a given calendar day,
+// optionally among the cards of a topic
+pub async fn daily_flashcard_handler(
+    repo: &FlashcardStore,
+    config: &DailyConfig,
+    params: &QueryParams<DailyParams>,
+) -> Result<Response, ApiError> {
+    let time_zone = match &params.0.tz {
+        Some(tz) => TimeZone::get(tz).map_err(|_| time_zone_error())?,
+        None => TimeZone::UTC,
+    };
+    let selection = DailySelection {
+        day: Timestamp::now().to_zoned(time_zone).date(),
+        topic: params.0.topic.clone(),
+        repeat_window: config.repeat_window,
+    };
+
+    match repo.daily_flashcard(&selection).await? {
         Some(card) => {
             let response = FlashCardResponse {
                 msg: "success".to_string(),
@@ -228,3 +404,113 @@ pub async fn random_flashcard_handler(db: &DatabaseConfig) -> Result<Response, A
         None => Err(ApiError::NotFound("No flashcards available".into())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::InMemoryFlashcardRepository;
+    use pavex::http::StatusCode;
+
+    fn new_card(question: &str) -> NewFlashCard {
+        NewFlashCard {
+            question: question.to_string(),
+            answer: "an answer".to_string(),
+            topic: "intro".to_string(),
+            tags: vec!["basics".to_string()],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        }
+    }
+
+    fn in_memory_store(questions: &[&str]) -> FlashcardStore {
+        let cards = questions.iter().map(|question| FlashCard {
+            status: CardStatus::Published,
+            ..FlashCard::try_from(new_card(question)).unwrap()
+        });
+        FlashcardStore::InMemory(InMemoryFlashcardRepository::with_cards(cards))
+    }
+
+    #[tokio::test]
+    async fn create_flashcard_handler_stores_the_card() {
+        let repo = in_memory_store(&[]);
+
+        let response = create_flashcard_handler(&repo, &JsonBody(new_card("What is Rust?")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(repo.list_flashcards().await.unwrap().is_empty());
+        let cards = repo.list_all_flashcards(None).await.unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].status, CardStatus::Draft);
+    }
+
+    #[tokio::test]
+    async fn create_flashcard_handler_rejects_duplicate_questions() {
+        let repo = in_memory_store(&["What is Rust?"]);
+
+        let result = create_flashcard_handler(&repo, &JsonBody(new_card("What is Rust?"))).await;
+
+        assert!(matches!(result, Err(ApiError::DuplicateQuestion)));
+    }
+
+    #[tokio::test]
+    async fn list_flashcard_handler_returns_not_found_for_a_missing_card() {
+        let repo = in_memory_store(&["What is Rust?"]);
+        let params = PathParams(FlashCardParams {
+            id: Uuid::new_v4().to_string(),
+        });
+
+        let result = list_flashcard_handler(&repo, &params).await;
+
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn delete_flashcard_handler_removes_the_card() {
+        let repo = in_memory_store(&["What is Rust?"]);
+        let id = repo.list_flashcards().await.unwrap()[0].id;
+        let params = PathParams(FlashCardParams { id: id.to_string() });
+
+        let response = delete_flashcard_handler(&repo, &params).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(repo.list_flashcards().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn random_flashcard_handler_returns_not_found_without_cards() {
+        let repo = in_memory_store(&[]);
+
+        let result = random_flashcard_handler(&repo, &QueryParams(RandomParams::default())).await;
+
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn random_flashcard_handler_rejects_an_invalid_excluded_id() {
+        let repo = in_memory_store(&["What is Rust?"]);
+        let params = RandomParams {
+            exclude: Some("not-a-uuid".to_string()),
+            ..Default::default()
+        };
+
+        let result = random_flashcard_handler(&repo, &QueryParams(params)).await;
+
+        assert!(matches!(result, Err(ApiError::UuidError(_))));
+    }
+
+    #[tokio::test]
+    async fn daily_flashcard_handler_rejects_an_unknown_time_zone() {
+        let repo = in_memory_store(&["What is Rust?"]);
+        let config = DailyConfig { repeat_window: 30 };
+        let params = DailyParams {
+            tz: Some("Mars/Olympus_Mons".to_string()),
+            topic: None,
+        };
+
+        let result = daily_flashcard_handler(&repo, &config, &QueryParams(params)).await;
+
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+    }
+}
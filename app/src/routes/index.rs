@@ -1,6 +1,7 @@
 // app/src/routes/index.rs
 
 // dependencies
+use crate::configuration::RoutingConfig;
 use pavex::{
     cookie::{ResponseCookie, ResponseCookies},
     http::StatusCode,
@@ -16,14 +17,15 @@ pub fn template_error2response(e: &TemplateError) -> StatusCode {
     }
 }
 
-// handler which returns the index page template
-pub fn get(response_cookies: &mut ResponseCookies, template: &TemplateEngine) -> Result<Response, TemplateError> {
+// handler which returns the index page template, which calls the public API where it is served
+pub fn get(response_cookies: &mut ResponseCookies, template: &TemplateEngine, routing: &RoutingConfig) -> Result<Response, TemplateError> {
     let now = Zoned::now().to_string();
     let cookie = ResponseCookie::new("last_visited", now)
         .set_path("/");
 
     response_cookies.insert(cookie);
-    let context = Context::new();
+    let mut context = Context::new();
+    context.insert("api_base", &routing.public_api_base());
 
     let body: Html = template.render("index.html", &context)?.into();
 
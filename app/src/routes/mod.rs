@@ -1,22 +1,37 @@
 // app/src/routes/mod.rs
 
 // modules into scope
+pub mod code_checks;
+pub mod drafts;
+pub mod feedback;
 pub mod flashcards;
 pub mod health;
 pub mod index;
+pub mod organizations;
+pub mod pages;
 pub mod preflight;
+pub mod progress;
+pub mod quizzes;
+pub mod reviews;
 pub mod static_server;
+pub mod suggestions;
+pub mod tags;
+pub mod topics;
+pub mod users;
 
 // dependencies
 use pavex::blueprint::{
     Blueprint,
-    router::{DELETE, GET, OPTIONS, POST, PUT},
+    router::{DELETE, GET, POST, PUT},
 };
 use pavex::f;
 
 // protected routes, require an API key to access
 fn admin_api_bp() -> Blueprint {
     let mut bp = Blueprint::new();
+    bp.pre_process(f!(crate::middleware::route_admin_api));
+    bp.pre_process(f!(crate::middleware::rate_limit_admin_api))
+        .error_handler(f!(crate::errors::api_error2response));
     bp.pre_process(f!(crate::middleware::validate_api_key))
         .error_handler(f!(crate::errors::api_error2response));
     bp.route(
@@ -37,13 +52,159 @@ fn admin_api_bp() -> Blueprint {
         f!(self::flashcards::delete_flashcard_handler),
     )
     .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/verify-code",
+        f!(self::code_checks::verify_all_code_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/verify-code",
+        f!(self::code_checks::verify_code_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/flashcards/{id}/verify-code",
+        f!(self::code_checks::get_code_verification_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/submit",
+        f!(self::reviews::submit_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/approve",
+        f!(self::reviews::approve_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/reject",
+        f!(self::reviews::reject_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/archive",
+        f!(self::reviews::archive_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/flashcards/{id}/reviews",
+        f!(self::reviews::list_reviews_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/review/flashcards",
+        f!(self::reviews::review_queue_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/drafts/import",
+        f!(self::drafts::import_rustdoc_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(GET, "/drafts", f!(self::drafts::list_drafts_handler))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/drafts/{id}/accept",
+        f!(self::drafts::accept_draft_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        DELETE,
+        "/drafts/{id}",
+        f!(self::drafts::delete_draft_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(PUT, "/tags/{slug}", f!(self::tags::update_tag_handler))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/tags/{slug}/merge",
+        f!(self::tags::merge_tags_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/feedback/report",
+        f!(self::feedback::feedback_report_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/flashcards/{id}/feedback",
+        f!(self::feedback::list_flags_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/feedback/resolve",
+        f!(self::feedback::resolve_flags_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/moderation/queue",
+        f!(self::suggestions::moderation_queue_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/moderation/suggestions/{id}/approve",
+        f!(self::suggestions::approve_suggestion_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/moderation/suggestions/{id}/reject",
+        f!(self::suggestions::reject_suggestion_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(POST, "/topics", f!(self::topics::create_topic_handler))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        PUT,
+        "/topics/{slug}",
+        f!(self::topics::update_topic_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/organizations",
+        f!(self::organizations::create_organization_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/organizations",
+        f!(self::organizations::list_organizations_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/organizations/{slug}/api-keys",
+        f!(self::organizations::create_api_key_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
     bp
 }
 
 // public routes, no API key required
 fn public_api_bp() -> Blueprint {
     let mut bp = Blueprint::new();
-    bp.post_process(f!(crate::middleware::add_cors_headers));
+    bp.pre_process(f!(crate::middleware::route_public_api));
+    bp.pre_process(f!(crate::middleware::rate_limit_public_api))
+        .error_handler(f!(crate::errors::api_error2response));
     bp.route(GET, "/flashcards/health", f!(self::health::check_health));
     bp.route(
         GET,
@@ -51,6 +212,26 @@ fn public_api_bp() -> Blueprint {
         f!(self::flashcards::random_flashcard_handler),
     )
     .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        GET,
+        "/flashcards/daily",
+        f!(self::flashcards::daily_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/check",
+        f!(self::flashcards::check_answer_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(GET, "/quizzes", f!(self::quizzes::create_quiz_handler))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/quizzes/{id}/submit",
+        f!(self::quizzes::submit_quiz_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
     bp.route(
         GET,
         "/flashcards/tags",
@@ -63,6 +244,14 @@ fn public_api_bp() -> Blueprint {
         f!(self::flashcards::list_flashcard_topics_handler),
     )
     .error_handler(f!(crate::api_error2response));
+    bp.route(
+        GET,
+        "/flashcards/stats",
+        f!(self::flashcards::flashcard_stats_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(GET, "/topics/tree", f!(self::topics::topic_tree_handler))
+        .error_handler(f!(crate::errors::api_error2response));
     bp.route(
         GET,
         "/flashcards",
@@ -75,41 +264,69 @@ fn public_api_bp() -> Blueprint {
         f!(self::flashcards::list_flashcard_handler),
     )
     .error_handler(f!(crate::errors::api_error2response));
+    bp.route(POST, "/users", f!(self::users::register_user_handler))
+        .error_handler(f!(crate::errors::api_error2response));
+    bp.route(GET, "/me", f!(self::users::current_user_handler))
+        .error_handler(f!(crate::errors::api_error2response));
     bp.route(
-        OPTIONS,
-        "/flashcards",
-        f!(self::preflight::preflight_handler),
-    );
+        POST,
+        "/me/reviews",
+        f!(self::progress::record_study_review_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(GET, "/me/progress", f!(self::progress::progress_handler))
+        .error_handler(f!(crate::errors::api_error2response));
     bp.route(
-        OPTIONS,
-        "/flashcards/{id}",
-        f!(self::preflight::preflight_handler),
-    );
+        POST,
+        "/suggestions",
+        f!(self::suggestions::suggest_flashcard_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
     bp.route(
-        OPTIONS,
-        "/flashcards/random",
-        f!(self::preflight::preflight_handler),
-    );
+        GET,
+        "/suggestions",
+        f!(self::suggestions::list_user_suggestions_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/suggestions",
+        f!(self::suggestions::suggest_edit_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
+    bp.route(
+        POST,
+        "/flashcards/{id}/feedback",
+        f!(self::feedback::leave_feedback_handler),
+    )
+    .error_handler(f!(crate::errors::api_error2response));
     bp
 }
 
 // web asset routes
 fn web_bp() -> Blueprint {
     let mut bp = Blueprint::new();
+    bp.pre_process(f!(crate::middleware::route_web));
     bp.route(GET, "/", f!(self::index::get))
         .error_handler(f!(crate::routes::index::template_error2response));
+    bp.route(GET, "/flashcards/{id}", f!(self::pages::flashcard_page))
+        .error_handler(f!(crate::routes::pages::page_error2response));
     bp.route(GET, "/static/{filename}", f!(self::static_server::get))
         .error_handler(f!(crate::routes::static_server::static_error2response));
     bp
 }
 
-// combine the public and private routes and register them
+// combine the public and private routes and register them; the hosts and path prefixes they are
+// served at come from the `routing` configuration, which the routing middlewares of each
+// blueprint check, so both APIs sit under the same `{prefix}` path parameter. Both follow the
+// `cors` configuration, and the fallback answers the preflight requests of all their routes
 pub fn register(bp: &mut Blueprint) {
-    bp.domain("rusty-flash-knowledge.net")
-        .prefix("/v1")
-        .nest(public_api_bp());
-    bp.domain("api.rusty-flash-knowledge.net")
-        .prefix("/v1")
-        .nest(admin_api_bp());
-    bp.domain("app.rusty-flash-knowledge.net").nest(web_bp());
+    let mut api = Blueprint::new();
+    api.post_process(f!(crate::middleware::add_cors_headers));
+    api.post_process(f!(crate::middleware::add_rate_limit_headers));
+    api.nest(public_api_bp());
+    api.nest(admin_api_bp());
+    bp.prefix("/{prefix}").nest(api);
+    bp.nest(web_bp());
+    bp.fallback(f!(self::preflight::preflight_fallback));
 }
@@ -0,0 +1,141 @@
+// app/src/routes/organizations.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::identity::{hash_token, new_token};
+use crate::models::{NewOrganization, Organization};
+use crate::repository::{OrganizationRepository, SharedStore};
+use crate::tenancy::Tenant;
+use crate::validation::validate_new_organization;
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent the path parameters of an incoming organization request
+#[PathParams]
+pub struct OrganizationParams {
+    pub slug: String,
+}
+
+// struct type to represent the data for an organization
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct OrganizationContent {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub hosts: Vec<String>,
+    pub created_at: String,
+}
+
+// implement the From trait to convert the Organization type into an OrganizationContent type
+impl From<Organization> for OrganizationContent {
+    fn from(organization: Organization) -> Self {
+        Self {
+            id: organization.id,
+            slug: organization.slug,
+            name: organization.name,
+            hosts: organization.hosts,
+            created_at: organization.created_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a list of organizations response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct OrganizationsResponse {
+    pub msg: String,
+    pub content: Vec<OrganizationContent>,
+}
+
+// struct type to represent an organization along with a new API key of it; the key is only ever
+// shown here
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct OrganizationKey {
+    pub organization: OrganizationContent,
+    pub api_key: String,
+}
+
+// struct type to represent a response wrapping an organization and a new API key of it
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct OrganizationKeyResponse {
+    pub msg: String,
+    pub content: OrganizationKey,
+}
+
+// organizations are managed by the operators of the instance, who hold a key of the default one
+fn require_default_organization(tenant: &Tenant) -> Result<(), ApiError> {
+    if !tenant.is_default() {
+        return Err(ApiError::DefaultOrganizationKeyRequired);
+    }
+
+    Ok(())
+}
+
+// handler which creates an organization, and hands out its first API key
+pub async fn create_organization_handler(
+    tenant: &Tenant,
+    store: &SharedStore,
+    body: &JsonBody<NewOrganization>,
+) -> Result<Response, ApiError> {
+    require_default_organization(tenant)?;
+    validate_new_organization(&body.0)?;
+    let api_key = new_token();
+    let organization = store
+        .create_organization(&Organization::new(&body.0), &hash_token(&api_key))
+        .await?;
+
+    let response_body = OrganizationKeyResponse {
+        msg: "success".to_string(),
+        content: OrganizationKey {
+            organization: OrganizationContent::from(organization),
+            api_key,
+        },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::created().set_typed_body(json))
+}
+
+// handler which retrieves every organization, sorted by slug
+pub async fn list_organizations_handler(
+    tenant: &Tenant,
+    store: &SharedStore,
+) -> Result<Response, ApiError> {
+    require_default_organization(tenant)?;
+    let organizations = store.list_organizations().await?;
+
+    let response_body = OrganizationsResponse {
+        msg: "success".to_string(),
+        content: organizations
+            .into_iter()
+            .map(OrganizationContent::from)
+            .collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which hands out another API key of an organization, e.g. to rotate the previous one
+pub async fn create_api_key_handler(
+    tenant: &Tenant,
+    store: &SharedStore,
+    params: &PathParams<OrganizationParams>,
+) -> Result<Response, ApiError> {
+    require_default_organization(tenant)?;
+    let organization = store.get_organization(&params.0.slug).await?;
+    let api_key = new_token();
+    store
+        .add_api_key(organization.id, &hash_token(&api_key))
+        .await?;
+
+    let response_body = OrganizationKeyResponse {
+        msg: "success".to_string(),
+        content: OrganizationKey {
+            organization: OrganizationContent::from(organization),
+            api_key,
+        },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::created().set_typed_body(json))
+}
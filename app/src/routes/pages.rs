@@ -0,0 +1,47 @@
+// app/src/routes/pages.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::{FlashCardContent, FlashCardParams};
+use pavex::http::StatusCode;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Html};
+use pavex_tera_template::{Context, TemplateEngine, TemplateError};
+use thiserror::Error;
+use uuid::Uuid;
+
+// enum type to represent the ways rendering a page can fail
+#[derive(Debug, Error)]
+pub enum PageError {
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+}
+
+// error handler for the page endpoints
+pub fn page_error2response(e: &PageError) -> StatusCode {
+    match e {
+        PageError::Api(e) => e.status_code(),
+        PageError::Template(TemplateError::Tera(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// handler which returns the page of a single flash card, its question and answer rendered from
+// Markdown on the server
+pub async fn flashcard_page(
+    repo: &FlashcardStore,
+    template: &TemplateEngine,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, PageError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let flash_card = repo.get_flashcard(id).await?;
+
+    let mut context = Context::new();
+    context.insert("card", &FlashCardContent::from(flash_card));
+    let body: Html = template.render("flashcard.html", &context)?.into();
+
+    Ok(Response::ok().set_typed_body(body))
+}
@@ -1,23 +1,32 @@
 // app/src/routes/preflight.rs
 
 // dependencies
-use pavex::http::HeaderValue;
+use crate::configuration::CorsConfig;
+use crate::cors;
+use pavex::request::RequestHead;
 use pavex::response::Response;
+use pavex::router::{AllowedMethods, default_fallback};
 
-// handler function to add CORS related headers for public routes
-pub fn preflight_handler() -> Response {
-    let mut response = Response::no_content();
+// fallback handler answering the preflight requests of every route, none of which registers an
+// OPTIONS route of its own; any other request matching no route is answered as usual, with a
+// 404 or, when the path matches but the method doesn't, a 405
+pub async fn preflight_fallback(
+    request: &RequestHead,
+    allowed_methods: &AllowedMethods,
+    config: &CorsConfig,
+) -> Response {
+    let route_methods = allowed_methods.allow_header_value();
+    let Some(route_methods) = route_methods
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .filter(|_| cors::is_preflight(request))
+    else {
+        return default_fallback(allowed_methods).await;
+    };
+    let route_methods: Vec<&str> = route_methods.split(',').map(str::trim).collect();
 
-    let headers = response.headers_mut();
-    headers.insert("Access-Control-Allow-Origin", HeaderValue::from_static("*"));
-    headers.insert(
-        "Access-Control-Allow-Methods",
-        HeaderValue::from_static("GET, OPTIONS"),
-    );
-    headers.insert(
-        "Access-Control-Allow-Headers",
-        HeaderValue::from_static("Content-Type, Authorization"),
-    );
+    let mut response = Response::no_content();
+    cors::apply_preflight(config, request, &route_methods, response.headers_mut());
 
     response
 }
@@ -0,0 +1,100 @@
+// app/src/routes/progress.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::models::{CardStatus, NewStudyReview, RecallGrade, StudyReview, User};
+use crate::progress::{Progress, progress};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::time_zone_error;
+use pavex::request::body::JsonBody;
+use pavex::request::query::QueryParams;
+use pavex::response::{Response, body::Json};
+use pavex::time::Timestamp;
+use pavex::time::tz::TimeZone;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent the query parameters of the progress of a learner; days follow UTC
+// unless `tz` names a time zone
+#[derive(Debug, Default, Deserialize)]
+pub struct ProgressParams {
+    pub tz: Option<String>,
+}
+
+// struct type to represent a card studied by a learner
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct StudyReviewContent {
+    pub id: Uuid,
+    pub flashcard_id: Uuid,
+    pub grade: RecallGrade,
+    pub reviewed_at: String,
+}
+
+// implement the From trait to convert the StudyReview type into a StudyReviewContent type
+impl From<StudyReview> for StudyReviewContent {
+    fn from(review: StudyReview) -> Self {
+        Self {
+            id: review.id,
+            flashcard_id: review.flashcard_id,
+            grade: review.grade,
+            reviewed_at: review.reviewed_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a study review response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct StudyReviewResponse {
+    pub msg: String,
+    pub content: StudyReviewContent,
+}
+
+// struct type to represent a response wrapping the progress of a learner
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ProgressResponse {
+    pub msg: String,
+    pub content: Progress,
+}
+
+// handler which records how well the user making the request recalled a published card
+pub async fn record_study_review_handler(
+    repo: &FlashcardStore,
+    user: &User,
+    body: &JsonBody<NewStudyReview>,
+) -> Result<Response, ApiError> {
+    // a missing card is reported, rather than a review of nothing
+    repo.get_flashcard(body.0.flashcard_id).await?;
+    let review = repo
+        .create_study_review(&StudyReview::new(user, &body.0))
+        .await?;
+
+    let response_body = StudyReviewResponse {
+        msg: "success".to_string(),
+        content: StudyReviewContent::from(review),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the progress of the user making the request over the published cards
+pub async fn progress_handler(
+    repo: &FlashcardStore,
+    user: &User,
+    params: &QueryParams<ProgressParams>,
+) -> Result<Response, ApiError> {
+    let time_zone = match &params.0.tz {
+        Some(tz) => TimeZone::get(tz).map_err(|_| time_zone_error())?,
+        None => TimeZone::UTC,
+    };
+    let cards = repo
+        .list_all_flashcards(Some(CardStatus::Published))
+        .await?;
+    let reviews = repo.list_study_reviews(user.id).await?;
+
+    let response_body = ProgressResponse {
+        msg: "success".to_string(),
+        content: progress(&cards, &reviews, Timestamp::now(), &time_zone),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
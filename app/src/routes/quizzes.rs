@@ -0,0 +1,265 @@
+// app/src/routes/quizzes.rs
+
+// dependencies
+use crate::configuration::QuizConfig;
+use crate::errors::ApiError;
+use crate::models::{Quiz, QuizResult, QuizSubmission, RandomSelection};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::{validate_quiz_size, validate_quiz_submission};
+use jiff_sqlx::ToSqlx;
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::request::query::QueryParams;
+use pavex::response::{Response, body::Json};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// number of questions of a quiz when no size is requested
+const DEFAULT_QUIZ_SIZE: usize = 10;
+
+// number of cards sampled for their answers to be offered as distractors, both among the cards
+// of the quiz topic or tag and among every card
+const DISTRACTOR_SAMPLE_SIZE: usize = 100;
+
+// struct type to represent the path parameters of an incoming quiz request
+#[PathParams]
+pub struct QuizIdParams {
+    pub id: String,
+}
+
+// struct type to represent the query parameters used to build a quiz
+#[derive(Debug, Default, Deserialize)]
+pub struct QuizParams {
+    pub topic: Option<String>,
+    pub tag: Option<String>,
+    pub size: Option<usize>,
+}
+
+// struct type to represent a quiz question as handed out, without its answer
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuizQuestionContent {
+    pub index: usize,
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+// struct type to represent the data for a quiz
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuizContent {
+    pub id: Uuid,
+    pub topic: Option<String>,
+    pub tag: Option<String>,
+    pub questions: Vec<QuizQuestionContent>,
+}
+
+// implement the From trait to convert the Quiz type into a QuizContent type, leaving out the
+// answer key and the flash cards the questions come from
+impl From<Quiz> for QuizContent {
+    fn from(quiz: Quiz) -> Self {
+        Self {
+            id: quiz.id,
+            topic: quiz.topic,
+            tag: quiz.tag,
+            questions: quiz
+                .questions
+                .into_iter()
+                .enumerate()
+                .map(|(index, question)| QuizQuestionContent {
+                    index,
+                    question: question.question,
+                    options: question.options,
+                })
+                .collect(),
+        }
+    }
+}
+
+// struct type to represent a quiz response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuizResponse {
+    pub msg: String,
+    pub content: QuizContent,
+}
+
+// struct type to represent a graded quiz response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuizResultResponse {
+    pub msg: String,
+    pub content: QuizResult,
+}
+
+// handler which builds a multiple-choice quiz out of random flash cards, optionally of a topic
+// or carrying a tag, and stores its answer key for grading; the quizzes past their retention are
+// deleted on the way
+pub async fn create_quiz_handler(
+    repo: &FlashcardStore,
+    config: &QuizConfig,
+    params: &QueryParams<QuizParams>,
+) -> Result<Response, ApiError> {
+    let params = &params.0;
+    let size = params.size.unwrap_or(DEFAULT_QUIZ_SIZE);
+    validate_quiz_size(size)?;
+    let selection = RandomSelection {
+        topic: params.topic.clone(),
+        tags: params.tag.iter().cloned().collect(),
+        count: size,
+        ..Default::default()
+    };
+    let cards = repo.random_flashcards(&selection).await?;
+    // the distractors come from a sample of the cards rather than the whole corpus
+    let mut corpus = repo
+        .random_flashcards(&RandomSelection {
+            count: DISTRACTOR_SAMPLE_SIZE,
+            ..selection.clone()
+        })
+        .await?;
+    if params.topic.is_some() || params.tag.is_some() {
+        corpus.extend(
+            repo.random_flashcards(&RandomSelection {
+                count: DISTRACTOR_SAMPLE_SIZE,
+                ..Default::default()
+            })
+            .await?,
+        );
+    }
+    let quiz = Quiz::build(
+        params.topic.clone(),
+        params.tag.clone(),
+        &cards,
+        &corpus,
+        &mut rand::rng(),
+    );
+    if quiz.questions.is_empty() {
+        return Err(ApiError::NotFound(
+            "Not enough flashcards to build a quiz".into(),
+        ));
+    }
+
+    repo.delete_quizzes_before((Timestamp::now() - config.retention).to_sqlx())
+        .await?;
+    let quiz = repo.create_quiz(&quiz).await?;
+    let response_body = QuizResponse {
+        msg: "success".to_string(),
+        content: QuizContent::from(quiz),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which grades the answers submitted for a quiz, given its id
+pub async fn submit_quiz_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<QuizSubmission>,
+    params: &PathParams<QuizIdParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let quiz = repo.get_quiz(id).await?;
+    validate_quiz_submission(&quiz, &body.0)?;
+
+    let response_body = QuizResultResponse {
+        msg: "success".to_string(),
+        content: quiz.grade(&body.0),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CardKind, CardStatus, FlashCard, NewFlashCard, QuizAnswer};
+    use crate::repository::InMemoryFlashcardRepository;
+
+    fn in_memory_store() -> FlashcardStore {
+        let cards = [
+            ("What is ownership?", "Single owner"),
+            ("What is borrowing?", "A reference"),
+            ("What is a lifetime?", "A scope"),
+        ]
+        .map(|(question, answer)| FlashCard {
+            status: CardStatus::Published,
+            ..FlashCard::try_from(NewFlashCard {
+                question: question.to_string(),
+                answer: answer.to_string(),
+                topic: "memory".to_string(),
+                tags: vec!["ownership".to_string()],
+                difficulty: 1,
+                kind: CardKind::FreeText,
+            })
+            .unwrap()
+        });
+        FlashcardStore::InMemory(InMemoryFlashcardRepository::with_cards(cards))
+    }
+
+    #[tokio::test]
+    async fn submitted_answers_are_graded_against_the_stored_quiz() {
+        let repo = in_memory_store();
+        let cards = repo.list_flashcards().await.unwrap();
+        let quiz = Quiz::build(None, None, &cards, &cards, &mut rand::rng());
+        repo.create_quiz(&quiz).await.unwrap();
+        let answers = quiz
+            .questions
+            .iter()
+            .enumerate()
+            .map(|(index, question)| QuizAnswer {
+                question: index,
+                option: question.correct_option,
+            })
+            .collect();
+        let params = PathParams(QuizIdParams {
+            id: quiz.id.to_string(),
+        });
+
+        let response = submit_quiz_handler(&repo, &JsonBody(QuizSubmission { answers }), &params)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), pavex::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn building_a_quiz_deletes_the_expired_ones() {
+        let repo = in_memory_store();
+        let cards = repo.list_flashcards().await.unwrap();
+        let expired = Quiz {
+            created_at: (Timestamp::now() - std::time::Duration::from_secs(2 * 60 * 60)).to_sqlx(),
+            ..Quiz::build(None, None, &cards, &cards, &mut rand::rng())
+        };
+        repo.create_quiz(&expired).await.unwrap();
+        let config = QuizConfig {
+            retention: std::time::Duration::from_secs(60 * 60),
+        };
+
+        let response = create_quiz_handler(&repo, &config, &QueryParams(QuizParams::default()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), pavex::http::StatusCode::OK);
+        assert!(matches!(
+            repo.get_quiz(expired.id).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn answers_to_unknown_questions_are_rejected() {
+        let repo = in_memory_store();
+        let cards = repo.list_flashcards().await.unwrap();
+        let quiz = Quiz::build(None, None, &cards, &cards, &mut rand::rng());
+        repo.create_quiz(&quiz).await.unwrap();
+        let submission = QuizSubmission {
+            answers: vec![QuizAnswer {
+                question: 99,
+                option: 0,
+            }],
+        };
+        let params = PathParams(QuizIdParams {
+            id: quiz.id.to_string(),
+        });
+
+        let result = submit_quiz_handler(&repo, &JsonBody(submission), &params).await;
+
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,152 @@
+// app/src/routes/reviews.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::models::{CardReview, CardStatus, ReviewDecision};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::{FlashCardContent, FlashCardParams, FlashCardResponse};
+use crate::validation::validate_review_decision;
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::request::query::QueryParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent the query parameters of the review queue, the cards waiting for a
+// review by default
+#[derive(Debug, Default, Deserialize)]
+pub struct StatusParams {
+    pub status: Option<CardStatus>,
+}
+
+// struct type to represent a change of status of a flash card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CardReviewContent {
+    pub id: Uuid,
+    pub flashcard_id: Uuid,
+    pub from_status: CardStatus,
+    pub to_status: CardStatus,
+    pub note: Option<String>,
+    pub reviewed_at: String,
+}
+
+// implement the From trait to convert the CardReview type into a CardReviewContent type
+impl From<CardReview> for CardReviewContent {
+    fn from(review: CardReview) -> Self {
+        Self {
+            id: review.id,
+            flashcard_id: review.flashcard_id,
+            from_status: review.from_status,
+            to_status: review.to_status,
+            note: review.note,
+            reviewed_at: review.reviewed_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a response wrapping the review history of a flash card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CardReviewsResponse {
+    pub msg: String,
+    pub content: Vec<CardReviewContent>,
+}
+
+// move a flash card to the given status, if the workflow allows it, and record the review
+async fn review(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+    to_status: CardStatus,
+    note: Option<&str>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let flash_card = repo.get_flashcard_any_status(id).await?;
+    let review =
+        CardReview::of(&flash_card, to_status, note).ok_or(ApiError::InvalidStatusTransition {
+            from: flash_card.status,
+            to: to_status,
+        })?;
+    let reviewed_flash_card = repo.review_flashcard(&review).await?;
+
+    let response_body = FlashCardResponse {
+        msg: "success".to_string(),
+        content: FlashCardContent::from(reviewed_flash_card),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which submits a draft flash card for review
+pub async fn submit_flashcard_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    review(repo, params, CardStatus::InReview, None).await
+}
+
+// handler which approves a flash card under review, publishing it; the note is optional
+pub async fn approve_flashcard_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<ReviewDecision>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    validate_review_decision(&body.0, false)?;
+    review(repo, params, CardStatus::Published, body.0.note.as_deref()).await
+}
+
+// handler which rejects a flash card under review, sending it back to draft; the note telling
+// the author what to change is required
+pub async fn reject_flashcard_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<ReviewDecision>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    validate_review_decision(&body.0, true)?;
+    review(repo, params, CardStatus::Draft, body.0.note.as_deref()).await
+}
+
+// handler which archives a published flash card, withdrawing it from the public API
+pub async fn archive_flashcard_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    review(repo, params, CardStatus::Archived, None).await
+}
+
+// handler which retrieves the review history of a flash card, oldest first
+pub async fn list_reviews_handler(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    // a missing card is reported, rather than an empty history
+    repo.get_flashcard_any_status(id).await?;
+    let reviews = repo.list_reviews(id).await?;
+
+    let response_body = CardReviewsResponse {
+        msg: "success".to_string(),
+        content: reviews.into_iter().map(CardReviewContent::from).collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the flash cards in a given status, whatever the public API shows;
+// the cards waiting for a review by default
+pub async fn review_queue_handler(
+    repo: &FlashcardStore,
+    params: &QueryParams<StatusParams>,
+) -> Result<Response, ApiError> {
+    let status = params.0.status.unwrap_or(CardStatus::InReview);
+    let flash_cards = repo.list_all_flashcards(Some(status)).await?;
+
+    let response_body: Vec<FlashCardResponse> = flash_cards
+        .into_iter()
+        .map(|card| FlashCardResponse {
+            msg: "success".to_string(),
+            content: FlashCardContent::from(card),
+        })
+        .collect();
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
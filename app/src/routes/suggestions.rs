@@ -0,0 +1,219 @@
+// app/src/routes/suggestions.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::models::{
+    FieldChange, FlashCard, NewFlashCard, ReviewDecision, Suggestion, SuggestionStatus,
+    UpdatedFlashCard, User,
+};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::routes::flashcards::{FlashCardContent, FlashCardParams, validate_update};
+use crate::validation::{empty_suggestion_error, validate_review_decision};
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent a suggestion; `flashcard_id` is `None` for a new card
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SuggestionContent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub flashcard_id: Option<Uuid>,
+    pub changes: UpdatedFlashCard,
+    pub diff: Vec<FieldChange>,
+    pub status: SuggestionStatus,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub reviewed_at: Option<String>,
+}
+
+// implement the From trait to convert the Suggestion type into a SuggestionContent type
+impl From<Suggestion> for SuggestionContent {
+    fn from(suggestion: Suggestion) -> Self {
+        Self {
+            id: suggestion.id,
+            user_id: suggestion.user_id,
+            flashcard_id: suggestion.flashcard_id,
+            changes: suggestion.changes,
+            diff: suggestion.diff,
+            status: suggestion.status,
+            note: suggestion.note,
+            created_at: suggestion.created_at.to_jiff().to_string(),
+            reviewed_at: suggestion
+                .reviewed_at
+                .map(|reviewed_at| reviewed_at.to_jiff().to_string()),
+        }
+    }
+}
+
+// struct type to represent a suggestion response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SuggestionResponse {
+    pub msg: String,
+    pub content: SuggestionContent,
+}
+
+// struct type to represent a response wrapping a list of suggestions
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SuggestionsResponse {
+    pub msg: String,
+    pub content: Vec<SuggestionContent>,
+}
+
+// struct type to represent an approved suggestion, along with the card it was applied to
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ApprovedSuggestion {
+    pub suggestion: SuggestionContent,
+    pub flashcard: FlashCardContent,
+}
+
+// struct type to represent a response wrapping an approved suggestion
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ApprovedSuggestionResponse {
+    pub msg: String,
+    pub content: ApprovedSuggestion,
+}
+
+// store a suggestion and respond with it
+async fn store(repo: &FlashcardStore, suggestion: &Suggestion) -> Result<Response, ApiError> {
+    let created = repo.create_suggestion(suggestion).await?;
+
+    let response_body = SuggestionResponse {
+        msg: "success".to_string(),
+        content: SuggestionContent::from(created),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which records a new card suggested by a learner
+pub async fn suggest_flashcard_handler(
+    repo: &FlashcardStore,
+    user: &User,
+    body: &JsonBody<NewFlashCard>,
+) -> Result<Response, ApiError> {
+    let suggestion = Suggestion::new_card(user, &body.0)?;
+    store(repo, &suggestion).await
+}
+
+// handler which records an edit to a published card suggested by a learner, with its diff
+// against the card
+pub async fn suggest_edit_handler(
+    repo: &FlashcardStore,
+    user: &User,
+    body: &JsonBody<UpdatedFlashCard>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let flash_card = repo.get_flashcard(id).await?;
+    let suggestion = Suggestion::edit(user, &flash_card, &body.0)?;
+    store(repo, &suggestion).await
+}
+
+// handler which retrieves the suggestions made by the user making the request, oldest first
+pub async fn list_user_suggestions_handler(
+    repo: &FlashcardStore,
+    user: &User,
+) -> Result<Response, ApiError> {
+    let suggestions = repo.list_suggestions(None, Some(user.id)).await?;
+
+    let response_body = SuggestionsResponse {
+        msg: "success".to_string(),
+        content: suggestions
+            .into_iter()
+            .map(SuggestionContent::from)
+            .collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the suggestions waiting for a moderator, oldest first
+pub async fn moderation_queue_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let suggestions = repo
+        .list_suggestions(Some(SuggestionStatus::Pending), None)
+        .await?;
+
+    let response_body = SuggestionsResponse {
+        msg: "success".to_string(),
+        content: suggestions
+            .into_iter()
+            .map(SuggestionContent::from)
+            .collect(),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// the pending suggestion with the id in the path
+async fn pending_suggestion(
+    repo: &FlashcardStore,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Suggestion, ApiError> {
+    let id = Uuid::parse_str(&params.0.id).map_err(ApiError::UuidError)?;
+    let suggestion = repo.get_suggestion(id).await?;
+    if suggestion.status != SuggestionStatus::Pending {
+        return Err(ApiError::SuggestionClosed);
+    }
+
+    Ok(suggestion)
+}
+
+// handler which approves a suggestion: an edit is validated like an update of the card, a new
+// card is created as a draft to go through the review workflow. The suggestion is closed and its
+// change applied at once, so that concurrent approvals apply it only once
+pub async fn approve_suggestion_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<ReviewDecision>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    validate_review_decision(&body.0, false)?;
+    let suggestion = pending_suggestion(repo, params).await?;
+    let new_card = match suggestion.flashcard_id {
+        Some(id) => {
+            validate_update(repo, id, &suggestion.changes).await?;
+            None
+        }
+        None => {
+            let new_flash_card = suggestion
+                .to_new_flashcard()
+                .ok_or_else(empty_suggestion_error)?;
+            Some(FlashCard::try_from(new_flash_card)?)
+        }
+    };
+    let approved = suggestion.closed(SuggestionStatus::Approved, body.0.note.as_deref());
+    let (closed, flash_card) = repo
+        .approve_suggestion(&approved, new_card.as_ref())
+        .await?;
+
+    let response_body = ApprovedSuggestionResponse {
+        msg: "success".to_string(),
+        content: ApprovedSuggestion {
+            suggestion: SuggestionContent::from(closed),
+            flashcard: FlashCardContent::from(flash_card),
+        },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which rejects a suggestion; the note telling the learner why is required
+pub async fn reject_suggestion_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<ReviewDecision>,
+    params: &PathParams<FlashCardParams>,
+) -> Result<Response, ApiError> {
+    validate_review_decision(&body.0, true)?;
+    let suggestion = pending_suggestion(repo, params).await?;
+    let rejected = suggestion.closed(SuggestionStatus::Rejected, body.0.note.as_deref());
+    let closed = repo.close_suggestion(&rejected).await?;
+
+    let response_body = SuggestionResponse {
+        msg: "success".to_string(),
+        content: SuggestionContent::from(closed),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
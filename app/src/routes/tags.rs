@@ -0,0 +1,58 @@
+// app/src/routes/tags.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::models::{Tag, TagMerge, UpdatedTag, slugify};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::{validate_tag_merge, validate_updated_tag};
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+
+// struct type to represent the path parameters of an incoming tag request
+#[PathParams]
+pub struct TagParams {
+    pub slug: String,
+}
+
+// struct type to represent a tag response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TagResponse {
+    pub msg: String,
+    pub content: Tag,
+}
+
+// handler which renames and/or describes a tag, across every flash card carrying it
+pub async fn update_tag_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<UpdatedTag>,
+    params: &PathParams<TagParams>,
+) -> Result<Response, ApiError> {
+    validate_updated_tag(&body.0)?;
+    let tag = repo.update_tag(&params.0.slug, &body.0).await?;
+    let response_body = TagResponse {
+        msg: "success".to_string(),
+        content: tag,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which merges a tag into another one; the tag in the path is the one going away
+pub async fn merge_tags_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<TagMerge>,
+    params: &PathParams<TagParams>,
+) -> Result<Response, ApiError> {
+    validate_tag_merge(&body.0)?;
+    let tag = repo
+        .merge_tags(&params.0.slug, &slugify(&body.0.into))
+        .await?;
+    let response_body = TagResponse {
+        msg: "success".to_string(),
+        content: tag,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
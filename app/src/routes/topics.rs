@@ -0,0 +1,76 @@
+// app/src/routes/topics.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::models::{NewTopic, Topic, TopicNode, UpdatedTopic, build_topic_tree};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::{validate_new_topic, validate_updated_topic};
+use pavex::request::body::JsonBody;
+use pavex::request::path::PathParams;
+use pavex::response::{Response, body::Json};
+use serde::{Deserialize, Serialize};
+
+// struct type to represent the path parameters of an incoming topic request
+#[PathParams]
+pub struct TopicParams {
+    pub slug: String,
+}
+
+// struct type to represent a topic response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TopicResponse {
+    pub msg: String,
+    pub content: Topic,
+}
+
+// struct type to represent a response wrapping the topic tree
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TopicTreeResponse {
+    pub msg: String,
+    pub content: Vec<TopicNode>,
+}
+
+// handler which retrieves every topic from the database, arranged as a tree
+pub async fn topic_tree_handler(repo: &FlashcardStore) -> Result<Response, ApiError> {
+    let topics = repo.list_topic_details().await?;
+
+    let response_body = TopicTreeResponse {
+        msg: "success".to_string(),
+        content: build_topic_tree(topics),
+    };
+
+    let json = Json::new(response_body)?;
+
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which creates a new topic, nested under its parent if it has one
+pub async fn create_topic_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<NewTopic>,
+) -> Result<Response, ApiError> {
+    validate_new_topic(&body.0)?;
+    let topic = repo.create_topic(&body.0).await?;
+    let response_body = TopicResponse {
+        msg: "success".to_string(),
+        content: topic,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which renames, describes and/or moves a topic within the tree
+pub async fn update_topic_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<UpdatedTopic>,
+    params: &PathParams<TopicParams>,
+) -> Result<Response, ApiError> {
+    validate_updated_topic(&body.0)?;
+    let topic = repo.update_topic(&params.0.slug, &body.0).await?;
+    let response_body = TopicResponse {
+        msg: "success".to_string(),
+        content: topic,
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
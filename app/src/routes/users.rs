@@ -0,0 +1,90 @@
+// app/src/routes/users.rs
+
+// dependencies
+use crate::errors::ApiError;
+use crate::identity::{hash_token, new_token};
+use crate::models::{NewUser, User};
+use crate::repository::{FlashcardRepository, FlashcardStore};
+use crate::validation::validate_new_user;
+use jiff_sqlx::ToSqlx;
+use pavex::request::body::JsonBody;
+use pavex::response::{Response, body::Json};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// struct type to represent the data for a user
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct UserContent {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: String,
+}
+
+// implement the From trait to convert the User type into a UserContent type
+impl From<User> for UserContent {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name,
+            created_at: user.created_at.to_jiff().to_string(),
+        }
+    }
+}
+
+// struct type to represent a user response
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct UserResponse {
+    pub msg: String,
+    pub content: UserContent,
+}
+
+// struct type to represent a newly registered user, along with their token; the token is only
+// ever shown here
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct RegisteredUser {
+    pub user: UserContent,
+    pub token: String,
+}
+
+// struct type to represent a response wrapping a newly registered user
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct RegisteredUserResponse {
+    pub msg: String,
+    pub content: RegisteredUser,
+}
+
+// handler which registers a learner, and hands out the token authenticating them
+pub async fn register_user_handler(
+    repo: &FlashcardStore,
+    body: &JsonBody<NewUser>,
+) -> Result<Response, ApiError> {
+    validate_new_user(&body.0)?;
+    let user = User {
+        id: Uuid::new_v4(),
+        name: body.0.name.trim().to_string(),
+        created_at: Timestamp::now().to_sqlx(),
+    };
+    let token = new_token();
+    let created_user = repo.create_user(&user, &hash_token(&token)).await?;
+
+    let response_body = RegisteredUserResponse {
+        msg: "success".to_string(),
+        content: RegisteredUser {
+            user: UserContent::from(created_user),
+            token,
+        },
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
+
+// handler which retrieves the user making the request
+pub async fn current_user_handler(user: &User) -> Result<Response, ApiError> {
+    let response_body = UserResponse {
+        msg: "success".to_string(),
+        content: UserContent::from(user.clone()),
+    };
+    let json = Json::new(response_body)?;
+    Ok(Response::ok().set_typed_body(json))
+}
@@ -0,0 +1,291 @@
+// app/src/rustdoc.rs
+
+// generation of draft flash cards from the JSON output of rustdoc
+// (`cargo +nightly rustdoc -- -Z unstable-options --output-format json`): every documented
+// public item of the crate makes a card asking what it does, answered by its doc summary
+
+// dependencies
+use crate::models::FlashcardDraft;
+use crate::validation::{MAX_ANSWER_LENGTH, MAX_QUESTION_LENGTH, MAX_TAG_LENGTH};
+use jiff_sqlx::ToSqlx;
+use pavex::time::Timestamp;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+// the id of the documented crate in the `crate_id` fields; the other ids are dependencies
+const LOCAL_CRATE: u64 = 0;
+
+// struct type to represent a public item worth a card: its path, e.g. `vec::Vec::retain`
+// within the crate, its kind and its documentation
+struct DocumentedItem<'a> {
+    module: Vec<&'a str>,
+    name: String,
+    kind: &'a str,
+    docs: &'a str,
+}
+
+/// Build a draft card for every documented public item of a rustdoc JSON document: functions,
+/// types, traits, constants and macros, as well as the inherent methods of types and the
+/// methods of traits. The cards are filed under `topic`, the crate name by default, and tagged
+/// with the module they come from. `None` if the document isn't rustdoc JSON output.
+pub fn draft_cards(
+    document: &Value,
+    topic: Option<&str>,
+    difficulty: i32,
+) -> Option<Vec<FlashcardDraft>> {
+    let index = document.get("index")?.as_object()?;
+    let paths = document.get("paths")?.as_object()?;
+    let root = index.get(&id_key(document.get("root")?)?)?;
+    let crate_name = root.get("name")?.as_str()?;
+    let topic = topic.unwrap_or(crate_name);
+
+    let mut items = Vec::new();
+    for (id, summary) in paths {
+        let Some(item) = index.get(id).filter(|item| is_local(item)) else {
+            continue;
+        };
+        let Some(path) = summary.get("path").and_then(Value::as_array) else {
+            continue;
+        };
+        let path: Vec<&str> = path.iter().filter_map(Value::as_str).collect();
+        let Some((name, module)) = path.split_last() else {
+            continue;
+        };
+        if let Some(documented) = documented(item, module.to_vec(), name.to_string()) {
+            items.push(documented);
+        }
+        items.extend(associated_items(index, item, module, name));
+    }
+
+    // the paths come in no particular order
+    items.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+    let created_at = Timestamp::now().to_sqlx();
+    let drafts = items
+        .into_iter()
+        .filter_map(|item| {
+            let question = question(item.kind, &item.name)?;
+            let source = format!("{}::{}", item.module.join("::"), item.name);
+            Some(FlashcardDraft {
+                id: Uuid::new_v4(),
+                question: truncate(question, MAX_QUESTION_LENGTH),
+                answer: truncate(summary(item.docs), MAX_ANSWER_LENGTH),
+                topic: topic.to_string(),
+                tags: vec![module_tag(&item.module)],
+                difficulty,
+                source,
+                created_at,
+            })
+        })
+        .collect();
+
+    Some(drafts)
+}
+
+// the key of an item in the index; ids are numbers in recent format versions, strings before
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(id) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+// whether an item belongs to the documented crate
+fn is_local(item: &Value) -> bool {
+    item.get("crate_id").and_then(Value::as_u64) == Some(LOCAL_CRATE)
+}
+
+// the kind of an item, the single key of its `inner` object, e.g. `function`
+fn kind(item: &Value) -> Option<&str> {
+    item.get("inner")?
+        .as_object()?
+        .keys()
+        .next()
+        .map(String::as_str)
+}
+
+// the documented public item, if it is one
+fn documented<'a>(
+    item: &'a Value,
+    module: Vec<&'a str>,
+    name: String,
+) -> Option<DocumentedItem<'a>> {
+    let is_public = item.get("visibility").and_then(Value::as_str) == Some("public");
+    let docs = item.get("docs")?.as_str()?;
+    if !is_public || docs.trim().is_empty() {
+        return None;
+    }
+
+    Some(DocumentedItem {
+        module,
+        name,
+        kind: kind(item)?,
+        docs,
+    })
+}
+
+// the documented methods of a type's inherent impls, or of a trait, named `Type::method`
+fn associated_items<'a>(
+    index: &'a Map<String, Value>,
+    item: &'a Value,
+    module: &[&'a str],
+    name: &str,
+) -> Vec<DocumentedItem<'a>> {
+    let Some((kind, inner)) = item
+        .get("inner")
+        .and_then(Value::as_object)
+        .and_then(|inner| inner.iter().next())
+    else {
+        return Vec::new();
+    };
+    let ids: Vec<&Value> = match kind.as_str() {
+        "struct" | "enum" | "union" => inner
+            .get("impls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| index.get(&id_key(id)?))
+            .filter_map(|block| block.get("inner")?.get("impl"))
+            // trait impls are documented by their trait
+            .filter(|block| block.get("trait").is_none_or(Value::is_null))
+            .filter_map(|block| block.get("items")?.as_array())
+            .flatten()
+            .collect(),
+        "trait" => inner
+            .get("items")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let is_trait = kind == "trait";
+    ids.into_iter()
+        .filter_map(|id| index.get(&id_key(id)?))
+        .filter(|method| kind_is(method, "function"))
+        .filter_map(|method| {
+            let method_name = method.get("name")?.as_str()?;
+            // trait items have no visibility of their own
+            let documented = if is_trait {
+                let docs = method.get("docs")?.as_str()?;
+                (!docs.trim().is_empty()).then_some(DocumentedItem {
+                    module: module.to_vec(),
+                    name: String::new(),
+                    kind: "function",
+                    docs,
+                })?
+            } else {
+                documented(method, module.to_vec(), String::new())?
+            };
+            Some(DocumentedItem {
+                name: format!("{}::{}", name, method_name),
+                ..documented
+            })
+        })
+        .collect()
+}
+
+// whether an item is of the given kind
+fn kind_is(item: &Value, expected: &str) -> bool {
+    kind(item) == Some(expected)
+}
+
+// the question asked about an item of the given kind; `None` for kinds which don't make cards
+fn question(kind: &str, name: &str) -> Option<String> {
+    let question = match kind {
+        "function" => format!("What does `{}` do?", name),
+        "struct" | "enum" | "union" | "type_alias" => format!("What is the `{}` type for?", name),
+        "trait" => format!("What is the `{}` trait for?", name),
+        "constant" | "static" => format!("What is `{}`?", name),
+        "macro" | "proc_macro" => format!("What does the `{}!` macro do?", name),
+        _ => return None,
+    };
+    Some(question)
+}
+
+// the summary of some documentation: its first paragraph, on a single line
+fn summary(docs: &str) -> String {
+    docs.trim()
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// the tag of the cards coming from a module, e.g. `my_crate-collections`; a tag must end with
+// a letter or digit
+fn module_tag(module: &[&str]) -> String {
+    let tag = truncate(module.join("-"), MAX_TAG_LENGTH);
+    tag.trim_end_matches(['-', '_']).to_string()
+}
+
+// keep at most `max` characters of a text
+fn truncate(text: String, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => text[..index].to_string(),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "root": 0,
+            "format_version": 39,
+            "index": {
+                "0": {"id": 0, "crate_id": 0, "name": "deck", "visibility": "public",
+                      "docs": "A deck of cards.", "inner": {"module": {"items": [1, 2]}}},
+                "1": {"id": 1, "crate_id": 0, "name": "Pile", "visibility": "public",
+                      "docs": "A pile of cards.\n\nMore details.",
+                      "inner": {"struct": {"impls": [3, 5]}}},
+                "2": {"id": 2, "crate_id": 0, "name": "helper", "visibility": "crate",
+                      "docs": "Not public.", "inner": {"function": {}}},
+                "3": {"id": 3, "crate_id": 0, "name": null, "visibility": "default",
+                      "docs": null, "inner": {"impl": {"trait": null, "items": [4]}}},
+                "4": {"id": 4, "crate_id": 0, "name": "shuffle", "visibility": "public",
+                      "docs": "Shuffle the pile\nin place.", "inner": {"function": {}}},
+                "5": {"id": 5, "crate_id": 0, "name": null, "visibility": "default",
+                      "docs": null, "inner": {"impl": {"trait": {"name": "Clone"}, "items": [6]}}},
+                "6": {"id": 6, "crate_id": 0, "name": "clone", "visibility": "default",
+                      "docs": "Clone it.", "inner": {"function": {}}}
+            },
+            "paths": {
+                "0": {"crate_id": 0, "path": ["deck"], "kind": "module"},
+                "1": {"crate_id": 0, "path": ["deck", "piles", "Pile"], "kind": "struct"},
+                "2": {"crate_id": 0, "path": ["deck", "helper"], "kind": "function"}
+            }
+        })
+    }
+
+    #[test]
+    fn documented_public_items_make_drafts() {
+        let drafts = draft_cards(&document(), None, 2).unwrap();
+
+        let questions: Vec<&str> = drafts.iter().map(|d| d.question.as_str()).collect();
+        assert_eq!(
+            questions,
+            vec![
+                "What is the `Pile` type for?",
+                "What does `Pile::shuffle` do?"
+            ]
+        );
+        assert_eq!(drafts[0].answer, "A pile of cards.");
+        assert_eq!(drafts[1].answer, "Shuffle the pile in place.");
+        assert_eq!(drafts[1].topic, "deck");
+        assert_eq!(drafts[1].tags, vec!["deck-piles"]);
+        assert_eq!(drafts[1].source, "deck::piles::Pile::shuffle");
+        assert_eq!(drafts[1].difficulty, 2);
+    }
+
+    #[test]
+    fn other_documents_are_rejected() {
+        assert!(draft_cards(&json!({"name": "not rustdoc"}), None, 1).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+// app/src/tenancy.rs
+
+// resolution of the organization a request is made for: an API key of an organization selects
+// it, and so does the host the request was sent to, as found in Host or, in the requests of a
+// trusted reverse proxy, in X-Forwarded-Host. Requests matching neither are served the content
+// of the default organization, which the API key of the configuration also belongs to
+
+// dependencies
+use crate::configuration::{AuthConfig, ProxyConfig};
+use crate::errors::ApiError;
+use crate::identity::{bearer_token, hash_token};
+use crate::models::DEFAULT_ORGANIZATION_ID;
+use crate::repository::{OrganizationRepository, SharedStore};
+use pavex::connection::ConnectionInfo;
+use pavex::http::HeaderMap;
+use pavex::request::RequestHead;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// The organization a request is made for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tenant {
+    pub organization_id: Uuid,
+    /// Whether the request carries an API key of the organization, as required by the admin API.
+    pub authenticated: bool,
+}
+
+// methods for the Tenant type
+impl Tenant {
+    /// Whether the request is made for the default organization, the only one allowed to
+    /// manage the others.
+    pub fn is_default(&self) -> bool {
+        self.organization_id == DEFAULT_ORGANIZATION_ID
+    }
+}
+
+/// Request-scoped constructor for the organization the request is made for, selected by its API
+/// key first, by its host otherwise.
+pub async fn resolve_tenant(
+    request: &RequestHead,
+    connection_info: &ConnectionInfo,
+    auth_config: &AuthConfig,
+    proxy_config: &ProxyConfig,
+    store: &SharedStore,
+) -> Result<Tenant, ApiError> {
+    if let Some(token) = bearer_token(request) {
+        if token == auth_config.api_key {
+            return Ok(Tenant {
+                organization_id: DEFAULT_ORGANIZATION_ID,
+                authenticated: true,
+            });
+        }
+        // the bearer token may as well be the token of a learner, which no organization owns
+        if let Some(organization) = store
+            .find_organization_by_api_key(&hash_token(token))
+            .await?
+        {
+            return Ok(Tenant {
+                organization_id: organization.id,
+                authenticated: true,
+            });
+        }
+    }
+
+    let peer = connection_info.peer_addr().ip();
+    let organization = match request_host(&request.headers, peer, proxy_config) {
+        Some(host) => store.find_organization_by_host(&host).await?,
+        None => None,
+    };
+
+    Ok(Tenant {
+        organization_id: organization
+            .map_or(DEFAULT_ORGANIZATION_ID, |organization| organization.id),
+        authenticated: false,
+    })
+}
+
+// the host the request was sent to, lowercased and without its port; X-Forwarded-Host is only
+// read from the trusted proxies, since any other peer could forge it
+fn request_host(headers: &HeaderMap, peer: IpAddr, proxy_config: &ProxyConfig) -> Option<String> {
+    let forwarded = headers
+        .get("x-forwarded-host")
+        .filter(|_| proxy_config.trusts(peer));
+    let header = forwarded.or_else(|| headers.get("host"))?;
+
+    normalize_host(header.to_str().ok()?)
+}
+
+// the first host of a Host or X-Forwarded-Host value, lowercased and without its port; a chain
+// of proxies lists the hosts, the first one being the client's
+pub(crate) fn normalize_host(value: &str) -> Option<String> {
+    let host = value.split(',').next()?.trim();
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+
+    Some(host.to_ascii_lowercase()).filter(|host| !host.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts_are_normalized() {
+        assert_eq!(
+            normalize_host("Team.Example.com:443, proxy.example.com").as_deref(),
+            Some("team.example.com")
+        );
+        assert_eq!(
+            normalize_host("cards.example.com").as_deref(),
+            Some("cards.example.com")
+        );
+        assert_eq!(normalize_host(" "), None);
+    }
+
+    #[test]
+    fn only_trusted_proxies_forward_the_host() {
+        let proxy_config = ProxyConfig {
+            trusted_addresses: vec!["10.0.0.2".parse().unwrap()],
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "cards.example.com".parse().unwrap());
+        headers.insert("x-forwarded-host", "team.example.com".parse().unwrap());
+
+        assert_eq!(
+            request_host(&headers, "10.0.0.2".parse().unwrap(), &proxy_config).as_deref(),
+            Some("team.example.com")
+        );
+        assert_eq!(
+            request_host(&headers, "::ffff:10.0.0.2".parse().unwrap(), &proxy_config).as_deref(),
+            Some("team.example.com")
+        );
+        assert_eq!(
+            request_host(&headers, "203.0.113.7".parse().unwrap(), &proxy_config).as_deref(),
+            Some("cards.example.com")
+        );
+    }
+}
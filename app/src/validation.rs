@@ -0,0 +1,795 @@
+// app/src/validation.rs
+
+// validation rules for incoming flash card data; every rule is checked so that all
+// the problems with a payload can be reported back to the client at once
+
+// dependencies
+use crate::errors::{FlashcardValidationError, ValidationErrors};
+use crate::models::{
+    AnswerAttempt, CardKind, NewFeedback, NewFlashCard, NewOrganization, NewTopic, NewUser, Quiz,
+    QuizSubmission, RandomSelection, ReviewDecision, RustdocImport, TagMerge, UpdatedFlashCard,
+    UpdatedTag, UpdatedTopic, parse_cloze, slugify,
+};
+use std::collections::HashSet;
+use std::path::{Component, Path};
+
+/// Maximum number of characters allowed in a question.
+pub const MAX_QUESTION_LENGTH: usize = 1_000;
+
+/// Maximum number of characters allowed in an answer.
+pub const MAX_ANSWER_LENGTH: usize = 5_000;
+
+/// Maximum number of characters allowed in a topic.
+pub const MAX_TOPIC_LENGTH: usize = 100;
+
+/// Maximum number of characters allowed in a single tag.
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Maximum number of tags a flash card can carry.
+pub const MAX_TAGS: usize = 10;
+
+/// Maximum number of characters allowed in a tag or topic description.
+pub const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+/// Maximum number of options of a multiple choice card.
+pub const MAX_OPTIONS: usize = 10;
+
+/// Maximum number of characters allowed in a multiple choice option.
+pub const MAX_OPTION_LENGTH: usize = 500;
+
+/// Maximum number of characters allowed in a reviewer note.
+pub const MAX_REVIEW_NOTE_LENGTH: usize = 1_000;
+
+/// Maximum number of characters allowed in the name of a user.
+pub const MAX_USER_NAME_LENGTH: usize = 50;
+
+/// Maximum number of characters allowed in a feedback comment.
+pub const MAX_FEEDBACK_COMMENT_LENGTH: usize = 500;
+
+/// Maximum number of characters allowed in the name of an organization.
+pub const MAX_ORGANIZATION_NAME_LENGTH: usize = 100;
+
+/// Maximum number of characters allowed in a host name.
+pub const MAX_HOST_LENGTH: usize = 253;
+
+/// Lowest allowed difficulty level.
+pub const MIN_DIFFICULTY: i32 = 1;
+
+/// Highest allowed difficulty level.
+pub const MAX_DIFFICULTY: i32 = 5;
+
+/// Maximum number of flash cards returned by a single random selection.
+pub const MAX_RANDOM_COUNT: usize = 50;
+
+/// Maximum number of questions in a quiz.
+pub const MAX_QUIZ_SIZE: usize = 20;
+
+/// Validate a new flash card, collecting every field error.
+pub fn validate_new_flashcard(new: &NewFlashCard) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    validate_question(&mut errors, &new.question);
+    validate_answer(&mut errors, &new.answer);
+    validate_topic(&mut errors, "topic", &new.topic);
+    validate_tags(&mut errors, &new.tags);
+    validate_difficulty(&mut errors, new.difficulty);
+    validate_kind(&mut errors, &new.kind, &new.question);
+
+    errors.into_result()
+}
+
+/// Validate an updated flash card, collecting every field error.
+///
+/// Only the fields present in the update are checked.
+pub fn validate_updated_flashcard(updated: &UpdatedFlashCard) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    if let Some(question) = &updated.question {
+        validate_question(&mut errors, question);
+    }
+    if let Some(answer) = &updated.answer {
+        validate_answer(&mut errors, answer);
+    }
+    if let Some(topic) = &updated.topic {
+        validate_topic(&mut errors, "topic", topic);
+    }
+    if let Some(tags) = &updated.tags {
+        validate_tags(&mut errors, tags);
+    }
+    if let Some(difficulty) = updated.difficulty {
+        validate_difficulty(&mut errors, difficulty);
+    }
+
+    errors.into_result()
+}
+
+/// Validate the kind of a flash card along with its question, which holds the deletions of a
+/// cloze card; used on updates, once the changes are merged with the current card.
+pub fn validate_card_kind(kind: &CardKind, question: &str) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    validate_kind(&mut errors, kind, question);
+
+    errors.into_result()
+}
+
+/// Validate changes to a tag, collecting every field error.
+pub fn validate_updated_tag(updated: &UpdatedTag) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    if let Some(name) = &updated.name {
+        validate_tag(&mut errors, "name", name);
+    }
+    validate_description(&mut errors, updated.description.as_deref());
+
+    errors.into_result()
+}
+
+/// Validate a request to merge a tag into another one.
+pub fn validate_tag_merge(merge: &TagMerge) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    if merge.into.trim().is_empty() {
+        errors.add("into", FlashcardValidationError::EmptyTag);
+    }
+
+    errors.into_result()
+}
+
+/// Validate a new topic, collecting every field error.
+pub fn validate_new_topic(new: &NewTopic) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    validate_topic(&mut errors, "name", &new.name);
+    validate_description(&mut errors, new.description.as_deref());
+    if new
+        .parent
+        .as_ref()
+        .is_some_and(|parent| parent.trim().is_empty())
+    {
+        errors.add("parent", FlashcardValidationError::EmptyTopic);
+    }
+
+    errors.into_result()
+}
+
+/// Validate changes to a topic, collecting every field error.
+pub fn validate_updated_topic(updated: &UpdatedTopic) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    if let Some(name) = &updated.name {
+        validate_topic(&mut errors, "name", name);
+    }
+    validate_description(&mut errors, updated.description.as_deref());
+    let empty_parent = matches!(&updated.parent, Some(Some(parent)) if parent.trim().is_empty());
+    if empty_parent {
+        errors.add("parent", FlashcardValidationError::EmptyTopic);
+    }
+
+    errors.into_result()
+}
+
+/// Validate the criteria of a random selection, collecting every field error.
+pub fn validate_random_selection(selection: &RandomSelection) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    if !(1..=MAX_RANDOM_COUNT).contains(&selection.count) {
+        errors.add("count", FlashcardValidationError::InvalidCount);
+    }
+    let min_valid = is_valid_difficulty(selection.min_difficulty);
+    let max_valid = is_valid_difficulty(selection.max_difficulty);
+    if !min_valid {
+        errors.add(
+            "min_difficulty",
+            FlashcardValidationError::InvalidDifficulty,
+        );
+    }
+    if !max_valid {
+        errors.add(
+            "max_difficulty",
+            FlashcardValidationError::InvalidDifficulty,
+        );
+    }
+    if min_valid && max_valid && selection.min_difficulty > selection.max_difficulty {
+        errors.add(
+            "min_difficulty",
+            FlashcardValidationError::InvalidDifficultyRange,
+        );
+    }
+
+    errors.into_result()
+}
+
+/// Validate the number of questions requested for a quiz.
+pub fn validate_quiz_size(size: usize) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    if !(1..=MAX_QUIZ_SIZE).contains(&size) {
+        errors.add("size", FlashcardValidationError::InvalidQuizSize);
+    }
+
+    errors.into_result()
+}
+
+/// Validate the answers submitted for a quiz: every answer must refer to one of its questions,
+/// and one of the options of that question, at most once per question.
+pub fn validate_quiz_submission(
+    quiz: &Quiz,
+    submission: &QuizSubmission,
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let mut answered = HashSet::new();
+    for (index, answer) in submission.answers.iter().enumerate() {
+        match quiz.questions.get(answer.question) {
+            None => errors.add(
+                format!("answers[{}].question", index),
+                FlashcardValidationError::InvalidQuizAnswer,
+            ),
+            Some(question) if answer.option >= question.options.len() => errors.add(
+                format!("answers[{}].option", index),
+                FlashcardValidationError::InvalidQuizAnswer,
+            ),
+            Some(_) if !answered.insert(answer.question) => errors.add(
+                format!("answers[{}].question", index),
+                FlashcardValidationError::DuplicateQuizAnswer,
+            ),
+            Some(_) => {}
+        }
+    }
+
+    errors.into_result()
+}
+
+/// Validate an answer typed by a learner.
+pub fn validate_answer_attempt(attempt: &AnswerAttempt) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    validate_answer(&mut errors, &attempt.answer);
+
+    errors.into_result()
+}
+
+/// Validate the decision of a reviewer; a rejection must say what is wrong with the card.
+pub fn validate_review_decision(
+    decision: &ReviewDecision,
+    note_required: bool,
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let note = decision.note.as_deref().map(str::trim).unwrap_or_default();
+    if note.is_empty() && note_required {
+        errors.add("note", FlashcardValidationError::MissingReviewNote);
+    } else if note.chars().count() > MAX_REVIEW_NOTE_LENGTH {
+        errors.add("note", FlashcardValidationError::ReviewNoteTooLong);
+    }
+
+    errors.into_result()
+}
+
+/// Validate a new user, collecting every field error.
+pub fn validate_new_user(new: &NewUser) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let name = new.name.trim();
+    if name.is_empty() {
+        errors.add("name", FlashcardValidationError::EmptyUserName);
+    } else if name.chars().count() > MAX_USER_NAME_LENGTH {
+        errors.add("name", FlashcardValidationError::UserNameTooLong);
+    }
+
+    errors.into_result()
+}
+
+/// Validate feedback on a card, collecting every field error.
+pub fn validate_new_feedback(new: &NewFeedback) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let comment = new.comment.as_deref().map(str::trim).unwrap_or_default();
+    if comment.chars().count() > MAX_FEEDBACK_COMMENT_LENGTH {
+        errors.add("comment", FlashcardValidationError::FeedbackCommentTooLong);
+    }
+
+    errors.into_result()
+}
+
+/// Validate a new organization, collecting every field error.
+pub fn validate_new_organization(new: &NewOrganization) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let name = new.name.trim();
+    if name.is_empty() {
+        errors.add("name", FlashcardValidationError::EmptyOrganizationName);
+    } else if name.chars().count() > MAX_ORGANIZATION_NAME_LENGTH {
+        errors.add("name", FlashcardValidationError::OrganizationNameTooLong);
+    } else if slugify(name).is_empty() {
+        errors.add("name", FlashcardValidationError::InvalidOrganizationName);
+    }
+
+    for (index, host) in new.hosts.iter().enumerate() {
+        if !is_valid_host(host.trim()) {
+            errors.add(
+                format!("hosts[{}]", index),
+                FlashcardValidationError::InvalidHost,
+            );
+        }
+    }
+
+    errors.into_result()
+}
+
+/// Validate a request to import rustdoc JSON output: the file must be a `.json` file within
+/// the configured rustdoc directory.
+pub fn validate_rustdoc_import(import: &RustdocImport) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+
+    let path = Path::new(&import.path);
+    let stays_inside = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension == "json");
+    if !stays_inside || !is_json {
+        errors.add("path", FlashcardValidationError::InvalidImportPath);
+    }
+    if let Some(topic) = &import.topic {
+        validate_topic(&mut errors, "topic", topic);
+    }
+    if let Some(difficulty) = import.difficulty {
+        validate_difficulty(&mut errors, difficulty);
+    }
+
+    errors.into_result()
+}
+
+/// The error reported when an imported file can't be read, or isn't rustdoc JSON output.
+pub fn rustdoc_error() -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add("path", FlashcardValidationError::InvalidRustdoc);
+    errors
+}
+
+/// The error reported when a suggested edit leaves the card as it is.
+pub fn empty_suggestion_error() -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add("changes", FlashcardValidationError::EmptySuggestion);
+    errors
+}
+
+/// The error reported when an answer is checked against a cloze sub-card which doesn't exist.
+pub fn cloze_number_error() -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add("cloze", FlashcardValidationError::InvalidClozeNumber);
+    errors
+}
+
+/// The error reported when the `tz` query parameter isn't a known time zone.
+pub fn time_zone_error() -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add("tz", FlashcardValidationError::InvalidTimeZone);
+    errors
+}
+
+/// The error reported when a topic would end up nested under itself.
+pub fn topic_cycle_error() -> ValidationErrors {
+    let mut errors = ValidationErrors::default();
+    errors.add("parent", FlashcardValidationError::TopicCycle);
+    errors
+}
+
+// check the question field
+fn validate_question(errors: &mut ValidationErrors, question: &str) {
+    let question = question.trim();
+    if question.is_empty() {
+        errors.add("question", FlashcardValidationError::EmptyQuestion);
+    } else if question.chars().count() > MAX_QUESTION_LENGTH {
+        errors.add("question", FlashcardValidationError::QuestionTooLong);
+    }
+}
+
+// check the answer field
+fn validate_answer(errors: &mut ValidationErrors, answer: &str) {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        errors.add("answer", FlashcardValidationError::EmptyAnswer);
+    } else if answer.chars().count() > MAX_ANSWER_LENGTH {
+        errors.add("answer", FlashcardValidationError::AnswerTooLong);
+    }
+}
+
+// check a topic name, which must have a slug
+fn validate_topic(errors: &mut ValidationErrors, field: &str, topic: &str) {
+    let topic = topic.trim();
+    if topic.is_empty() {
+        errors.add(field, FlashcardValidationError::EmptyTopic);
+    } else if topic.chars().count() > MAX_TOPIC_LENGTH {
+        errors.add(field, FlashcardValidationError::TopicTooLong);
+    } else if slugify(topic).is_empty() {
+        errors.add(field, FlashcardValidationError::InvalidTopicFormat);
+    }
+}
+
+// check the payload of the card kind
+fn validate_kind(errors: &mut ValidationErrors, kind: &CardKind, question: &str) {
+    match kind {
+        CardKind::FreeText | CardKind::TrueFalse { .. } => {}
+        CardKind::MultipleChoice { options, correct } => {
+            validate_options(errors, options);
+            validate_correct_options(errors, correct, options.len());
+        }
+        CardKind::Cloze => match parse_cloze(question) {
+            Ok(deletions) if deletions.is_empty() => {
+                errors.add("question", FlashcardValidationError::MissingClozeDeletion);
+            }
+            Ok(_) => {}
+            Err(_) => errors.add("question", FlashcardValidationError::InvalidClozeFormat),
+        },
+    }
+}
+
+// check the options of a multiple choice card, as well as every individual option
+fn validate_options(errors: &mut ValidationErrors, options: &[String]) {
+    if !(2..=MAX_OPTIONS).contains(&options.len()) {
+        errors.add("kind.options", FlashcardValidationError::InvalidOptionCount);
+    }
+
+    let mut seen = HashSet::new();
+    for (index, option) in options.iter().enumerate() {
+        let field = format!("kind.options[{}]", index);
+        let option = option.trim();
+        if option.is_empty() {
+            errors.add(field, FlashcardValidationError::EmptyOption);
+        } else if option.chars().count() > MAX_OPTION_LENGTH {
+            errors.add(field, FlashcardValidationError::OptionTooLong);
+        } else if !seen.insert(option.to_lowercase()) {
+            errors.add(field, FlashcardValidationError::DuplicateOption);
+        }
+    }
+}
+
+// check the correct options of a multiple choice card, which must be distinct option indexes
+fn validate_correct_options(errors: &mut ValidationErrors, correct: &[usize], option_count: usize) {
+    if correct.is_empty() {
+        errors.add("kind.correct", FlashcardValidationError::NoCorrectOption);
+        return;
+    }
+
+    let mut seen = HashSet::new();
+    for (index, option) in correct.iter().enumerate() {
+        if *option >= option_count || !seen.insert(option) {
+            errors.add(
+                format!("kind.correct[{}]", index),
+                FlashcardValidationError::InvalidCorrectOption,
+            );
+        }
+    }
+}
+
+// check an optional description
+fn validate_description(errors: &mut ValidationErrors, description: Option<&str>) {
+    if description.is_some_and(|description| description.chars().count() > MAX_DESCRIPTION_LENGTH) {
+        errors.add("description", FlashcardValidationError::DescriptionTooLong);
+    }
+}
+
+// check the tags field, as well as every individual tag
+fn validate_tags(errors: &mut ValidationErrors, tags: &[String]) {
+    if tags.is_empty() {
+        errors.add("tags", FlashcardValidationError::EmptyTags);
+        return;
+    }
+
+    if tags.len() > MAX_TAGS {
+        errors.add("tags", FlashcardValidationError::TooManyTags);
+    }
+
+    let mut seen = HashSet::new();
+    for (index, tag) in tags.iter().enumerate() {
+        let field = format!("tags[{}]", index);
+        if validate_tag(errors, &field, tag) && !seen.insert(slugify(tag)) {
+            errors.add(field, FlashcardValidationError::DuplicateTag);
+        }
+    }
+}
+
+// check a single tag, returning whether it is valid
+fn validate_tag(errors: &mut ValidationErrors, field: &str, tag: &str) -> bool {
+    let tag = tag.trim();
+    let error = if tag.is_empty() {
+        FlashcardValidationError::EmptyTag
+    } else if tag.chars().count() > MAX_TAG_LENGTH {
+        FlashcardValidationError::TagTooLong
+    } else if !is_valid_tag(tag) {
+        FlashcardValidationError::InvalidTagFormat
+    } else {
+        return true;
+    };
+
+    errors.add(field, error);
+    false
+}
+
+// check the difficulty field
+fn validate_difficulty(errors: &mut ValidationErrors, difficulty: i32) {
+    if !is_valid_difficulty(difficulty) {
+        errors.add("difficulty", FlashcardValidationError::InvalidDifficulty);
+    }
+}
+
+// a difficulty level lies within the allowed range
+fn is_valid_difficulty(difficulty: i32) -> bool {
+    (MIN_DIFFICULTY..=MAX_DIFFICULTY).contains(&difficulty)
+}
+
+// a tag is made of letters, digits, spaces, hyphens and underscores, and must start
+// and end with a letter or digit
+fn is_valid_tag(tag: &str) -> bool {
+    let starts_and_ends_alphanumeric = tag.chars().next().is_some_and(char::is_alphanumeric)
+        && tag.chars().last().is_some_and(char::is_alphanumeric);
+
+    starts_and_ends_alphanumeric
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+// a host name is made of dot-separated labels of ASCII letters, digits and hyphens, which
+// don't start or end with a hyphen; no port
+fn is_valid_host(host: &str) -> bool {
+    host.len() <= MAX_HOST_LENGTH
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(errors: &ValidationErrors) -> Vec<(&str, &str)> {
+        errors
+            .errors()
+            .iter()
+            .map(|e| (e.field.as_str(), e.rule.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn every_field_error_is_reported() {
+        let new = NewFlashCard {
+            question: "Valid question".to_string(),
+            answer: "".to_string(),
+            topic: "valid topic".to_string(),
+            tags: vec!["valid tag".to_string()],
+            difficulty: 0,
+            kind: CardKind::FreeText,
+        };
+
+        let errors = validate_new_flashcard(&new).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("answer", "empty_answer"),
+                ("difficulty", "invalid_difficulty")
+            ]
+        );
+    }
+
+    #[test]
+    fn overly_long_fields_are_invalid() {
+        let new = NewFlashCard {
+            question: "q".repeat(MAX_QUESTION_LENGTH + 1),
+            answer: "a".repeat(MAX_ANSWER_LENGTH + 1),
+            topic: "t".repeat(MAX_TOPIC_LENGTH + 1),
+            tags: vec!["x".repeat(MAX_TAG_LENGTH + 1)],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+
+        let errors = validate_new_flashcard(&new).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("question", "question_too_long"),
+                ("answer", "answer_too_long"),
+                ("topic", "topic_too_long"),
+                ("tags[0]", "tag_too_long")
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_rules_are_checked_per_tag() {
+        let new = NewFlashCard {
+            question: "Valid question".to_string(),
+            answer: "Valid answer".to_string(),
+            topic: "valid topic".to_string(),
+            tags: vec![
+                "ownership".to_string(),
+                "Ownership".to_string(),
+                "no#hashes".to_string(),
+                " ".to_string(),
+            ],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+
+        let errors = validate_new_flashcard(&new).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("tags[1]", "duplicate_tag"),
+                ("tags[2]", "invalid_tag_format"),
+                ("tags[3]", "empty_tag")
+            ]
+        );
+    }
+
+    #[test]
+    fn too_many_tags_are_invalid() {
+        let new = NewFlashCard {
+            question: "Valid question".to_string(),
+            answer: "Valid answer".to_string(),
+            topic: "valid topic".to_string(),
+            tags: (0..=MAX_TAGS).map(|i| format!("tag{}", i)).collect(),
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+
+        let errors = validate_new_flashcard(&new).unwrap_err();
+
+        assert_eq!(rules(&errors), vec![("tags", "too_many_tags")]);
+    }
+
+    #[test]
+    fn only_present_fields_of_an_update_are_validated() {
+        let updated = UpdatedFlashCard {
+            answer: Some("Valid answer".to_string()),
+            difficulty: Some(6),
+            ..Default::default()
+        };
+
+        let errors = validate_updated_flashcard(&updated).unwrap_err();
+
+        assert_eq!(rules(&errors), vec![("difficulty", "invalid_difficulty")]);
+    }
+
+    #[test]
+    fn tag_names_are_validated_like_card_tags() {
+        let updated = UpdatedTag {
+            name: Some("no#hashes".to_string()),
+            description: Some("d".repeat(MAX_DESCRIPTION_LENGTH + 1)),
+        };
+
+        let errors = validate_updated_tag(&updated).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("name", "invalid_tag_format"),
+                ("description", "description_too_long")
+            ]
+        );
+    }
+
+    #[test]
+    fn topic_names_need_a_letter_or_digit() {
+        let new = NewTopic {
+            name: "???".to_string(),
+            description: None,
+            parent: Some(" ".to_string()),
+        };
+
+        let errors = validate_new_topic(&new).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![("name", "invalid_topic_format"), ("parent", "empty_topic")]
+        );
+    }
+
+    #[test]
+    fn random_selection_bounds_are_checked() {
+        let selection = RandomSelection {
+            min_difficulty: 4,
+            max_difficulty: 2,
+            count: MAX_RANDOM_COUNT + 1,
+            ..Default::default()
+        };
+
+        let errors = validate_random_selection(&selection).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("count", "invalid_count"),
+                ("min_difficulty", "invalid_difficulty_range")
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_choice_payloads_are_checked() {
+        let new = NewFlashCard {
+            question: "Which types are Copy?".to_string(),
+            answer: "Integers and shared references.".to_string(),
+            topic: "traits".to_string(),
+            tags: vec!["copy".to_string()],
+            difficulty: 2,
+            kind: CardKind::MultipleChoice {
+                options: vec!["i32".to_string(), "I32 ".to_string(), "".to_string()],
+                correct: vec![0, 3],
+            },
+        };
+
+        let errors = validate_new_flashcard(&new).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("kind.options[1]", "duplicate_option"),
+                ("kind.options[2]", "empty_option"),
+                ("kind.correct[1]", "invalid_correct_option")
+            ]
+        );
+    }
+
+    #[test]
+    fn cloze_cards_need_well_formed_deletions() {
+        let missing = validate_card_kind(&CardKind::Cloze, "What checks borrows?").unwrap_err();
+        let malformed =
+            validate_card_kind(&CardKind::Cloze, "The {{c1::borrow checker").unwrap_err();
+
+        assert_eq!(
+            rules(&missing),
+            vec![("question", "missing_cloze_deletion")]
+        );
+        assert_eq!(
+            rules(&malformed),
+            vec![("question", "invalid_cloze_format")]
+        );
+        assert!(validate_card_kind(&CardKind::Cloze, "The {{c1::borrow checker}}").is_ok());
+    }
+
+    #[test]
+    fn organizations_need_a_name_and_valid_hosts() {
+        let organization = NewOrganization {
+            name: "  ".to_string(),
+            hosts: vec![
+                "flash.example.com".to_string(),
+                "flash.example.com:8000".to_string(),
+                "-flash.example.com".to_string(),
+            ],
+        };
+
+        let errors = validate_new_organization(&organization).unwrap_err();
+
+        assert_eq!(
+            rules(&errors),
+            vec![
+                ("name", "empty_organization_name"),
+                ("hosts[1]", "invalid_host"),
+                ("hosts[2]", "invalid_host"),
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_stay_within_the_rustdoc_directory() {
+        let import = |path: &str| RustdocImport {
+            path: path.to_string(),
+            ..Default::default()
+        };
+
+        assert!(validate_rustdoc_import(&import("my_crate.json")).is_ok());
+        assert!(validate_rustdoc_import(&import("./nested/my_crate.json")).is_ok());
+        for path in ["", "../secrets.json", "/etc/my_crate.json", "my_crate.html"] {
+            let errors = validate_rustdoc_import(&import(path)).unwrap_err();
+            assert_eq!(rules(&errors), vec![("path", "invalid_import_path")]);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+-- Add down migration script here
+ALTER TABLE flashcards ADD COLUMN tags TEXT[];
+
+UPDATE flashcards f
+SET tags = ARRAY(
+    SELECT t.name
+    FROM flashcard_tags ft
+    JOIN tags t ON t.id = ft.tag_id
+    WHERE ft.flashcard_id = f.id
+    ORDER BY ft.position
+);
+
+CREATE INDEX idx_flashcards_tags ON flashcards USING GIN (tags);
+
+DROP TABLE flashcard_tags;
+DROP TABLE tags;
@@ -0,0 +1,44 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS tags (
+    id UUID PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS flashcard_tags (
+    flashcard_id UUID NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    PRIMARY KEY (flashcard_id, tag_id)
+);
+
+CREATE INDEX idx_flashcard_tags_tag_id ON flashcard_tags(tag_id);
+
+-- move the existing tags over; the slug is the lowercased tag, with every run of
+-- non-alphanumeric characters replaced by a single hyphen
+CREATE TEMPORARY TABLE existing_tags AS
+SELECT f.id AS flashcard_id,
+       trim(t.name) AS name,
+       trim(BOTH '-' FROM regexp_replace(lower(trim(t.name)), '[^[:alnum:]]+', '-', 'g')) AS slug,
+       t.position
+FROM flashcards f, UNNEST(f.tags) WITH ORDINALITY AS t(name, position)
+WHERE trim(t.name) <> '';
+
+INSERT INTO tags (id, slug, name)
+SELECT gen_random_uuid(), slug, MIN(name)
+FROM existing_tags
+WHERE slug <> ''
+GROUP BY slug;
+
+INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+SELECT e.flashcard_id, t.id, MIN(e.position)
+FROM existing_tags e
+JOIN tags t ON t.slug = e.slug
+GROUP BY e.flashcard_id, t.id;
+
+DROP TABLE existing_tags;
+
+DROP INDEX IF EXISTS idx_flashcards_tags;
+ALTER TABLE flashcards DROP COLUMN tags;
@@ -0,0 +1,14 @@
+-- Add down migration script here
+ALTER TABLE flashcards ADD COLUMN topic TEXT;
+
+UPDATE flashcards f
+SET topic = t.name
+FROM topics t
+WHERE t.id = f.topic_id;
+
+DROP INDEX IF EXISTS idx_flashcards_topic_id;
+ALTER TABLE flashcards DROP COLUMN topic_id;
+
+CREATE INDEX idx_flashcards_topic ON flashcards(topic);
+
+DROP TABLE topics;
@@ -0,0 +1,36 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS topics (
+    id UUID PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    parent_id UUID REFERENCES topics(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT topics_parent_check CHECK (parent_id <> id)
+);
+
+CREATE INDEX idx_topics_parent_id ON topics(parent_id);
+
+-- every existing topic becomes a root topic; the slug is built the same way as for tags
+INSERT INTO topics (id, slug, name)
+SELECT gen_random_uuid(), slug, MIN(name)
+FROM (
+    SELECT trim(topic) AS name,
+           trim(BOTH '-' FROM regexp_replace(lower(trim(topic)), '[^[:alnum:]]+', '-', 'g')) AS slug
+    FROM flashcards
+    WHERE topic IS NOT NULL
+) existing_topics
+WHERE slug <> ''
+GROUP BY slug;
+
+ALTER TABLE flashcards ADD COLUMN topic_id UUID REFERENCES topics(id);
+
+UPDATE flashcards f
+SET topic_id = t.id
+FROM topics t
+WHERE t.slug = trim(BOTH '-' FROM regexp_replace(lower(trim(f.topic)), '[^[:alnum:]]+', '-', 'g'));
+
+DROP INDEX IF EXISTS idx_flashcards_topic;
+ALTER TABLE flashcards DROP COLUMN topic;
+
+CREATE INDEX idx_flashcards_topic_id ON flashcards(topic_id);
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS quizzes (
+    id UUID PRIMARY KEY,
+    topic TEXT,
+    tag TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- the questions are a snapshot of the flash cards they were built from, so that editing or
+-- deleting a card doesn't change the answer key of the quizzes already handed out
+CREATE TABLE IF NOT EXISTS quiz_questions (
+    quiz_id UUID NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    flashcard_id UUID NOT NULL,
+    question TEXT NOT NULL,
+    options TEXT[] NOT NULL,
+    correct_option INTEGER NOT NULL,
+    PRIMARY KEY (quiz_id, position),
+    CONSTRAINT quiz_questions_correct_option_check
+        CHECK (correct_option >= 0 AND correct_option < cardinality(options))
+);
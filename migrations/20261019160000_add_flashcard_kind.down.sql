@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE flashcards DROP CONSTRAINT flashcards_kind_type_check;
+ALTER TABLE flashcards DROP COLUMN kind;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- the kind of a flash card and its payload, e.g. {"type": "multiple_choice", "options": [...],
+-- "correct": [0]}; existing cards are free text cards
+ALTER TABLE flashcards ADD COLUMN kind JSONB NOT NULL DEFAULT '{"type": "free_text"}';
+
+ALTER TABLE flashcards ADD CONSTRAINT flashcards_kind_type_check
+    CHECK (kind->>'type' IN ('free_text', 'multiple_choice', 'true_false', 'cloze'));
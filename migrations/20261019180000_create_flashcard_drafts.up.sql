@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- draft flash cards generated from documentation, kept out of the deck until an editor accepts
+-- them; source is the path of the documented item
+CREATE TABLE IF NOT EXISTS flashcard_drafts (
+    id UUID PRIMARY KEY,
+    question TEXT NOT NULL UNIQUE,
+    answer TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    difficulty INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
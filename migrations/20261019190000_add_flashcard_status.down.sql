@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE flashcard_reviews;
+DROP INDEX flashcards_status_idx;
+ALTER TABLE flashcards DROP CONSTRAINT flashcards_status_check;
+ALTER TABLE flashcards DROP COLUMN status;
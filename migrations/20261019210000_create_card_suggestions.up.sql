@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- new cards and edits proposed by the learners, waiting for a moderator; flashcard_id is null
+-- for a new card, changes holds the proposed fields and diff what they change on the card
+CREATE TABLE IF NOT EXISTS card_suggestions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    flashcard_id UUID REFERENCES flashcards(id) ON DELETE CASCADE,
+    changes JSONB NOT NULL,
+    diff JSONB NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'pending',
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reviewed_at TIMESTAMPTZ,
+    CONSTRAINT card_suggestions_status_check CHECK (status IN ('pending', 'approved', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS card_suggestions_status_idx ON card_suggestions (status, created_at);
@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- flags and upvotes left on the cards by the learners; client is a hash identifying who left
+-- them, so that they can be rate-limited, and a flag stays open until resolved_at is set
+CREATE TABLE IF NOT EXISTS card_feedback (
+    id UUID PRIMARY KEY,
+    flashcard_id UUID NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    comment TEXT,
+    client TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    resolved_at TIMESTAMPTZ,
+    CONSTRAINT card_feedback_kind_check
+        CHECK (kind IN ('wrong_answer', 'unclear', 'outdated', 'duplicate', 'upvote'))
+);
+
+CREATE INDEX IF NOT EXISTS card_feedback_flashcard_idx ON card_feedback (flashcard_id);
+CREATE INDEX IF NOT EXISTS card_feedback_client_idx ON card_feedback (client, created_at);
@@ -0,0 +1,29 @@
+-- Add down migration script here
+-- only the content of the default organization is kept
+DELETE FROM organizations WHERE id <> '00000000-0000-0000-0000-000000000000';
+
+ALTER TABLE daily_cards DROP CONSTRAINT daily_cards_pkey;
+ALTER TABLE daily_cards ADD PRIMARY KEY (scope, day);
+ALTER TABLE flashcard_drafts DROP CONSTRAINT flashcard_drafts_question_key;
+ALTER TABLE flashcard_drafts ADD CONSTRAINT flashcard_drafts_question_key UNIQUE (question);
+ALTER TABLE topics DROP CONSTRAINT topics_slug_key;
+ALTER TABLE topics ADD CONSTRAINT topics_slug_key UNIQUE (slug);
+ALTER TABLE tags DROP CONSTRAINT tags_slug_key;
+ALTER TABLE tags ADD CONSTRAINT tags_slug_key UNIQUE (slug);
+ALTER TABLE flashcards DROP CONSTRAINT flashcards_question_key;
+ALTER TABLE flashcards ADD CONSTRAINT flashcards_question_key UNIQUE (question);
+
+ALTER TABLE study_reviews DROP COLUMN organization_id;
+ALTER TABLE card_feedback DROP COLUMN organization_id;
+ALTER TABLE card_suggestions DROP COLUMN organization_id;
+ALTER TABLE users DROP COLUMN organization_id;
+ALTER TABLE flashcard_drafts DROP COLUMN organization_id;
+ALTER TABLE quizzes DROP COLUMN organization_id;
+ALTER TABLE daily_cards DROP COLUMN organization_id;
+ALTER TABLE topics DROP COLUMN organization_id;
+ALTER TABLE tags DROP COLUMN organization_id;
+ALTER TABLE flashcards DROP COLUMN organization_id;
+
+DROP TABLE organization_api_keys;
+DROP TABLE organization_hosts;
+DROP TABLE organizations;
@@ -0,0 +1,83 @@
+-- Add up migration script here
+-- the teams sharing the instance, each with its own cards, tags, topics, learners and API keys;
+-- the content created before organizations existed belongs to the default organization, whose
+-- API key is the one of the configuration
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO organizations (id, slug, name)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'Default');
+
+-- the host names an organization is served at, as forwarded by the reverse proxy
+CREATE TABLE IF NOT EXISTS organization_hosts (
+    host TEXT PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS organization_hosts_organization_idx ON organization_hosts (organization_id);
+
+-- the API keys of the organizations, of which only the SHA-256 hash is kept
+CREATE TABLE IF NOT EXISTS organization_api_keys (
+    key_hash TEXT PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS organization_api_keys_organization_idx ON organization_api_keys (organization_id);
+
+-- every existing row belongs to the default organization; new rows are always inserted with
+-- their organization. Rows attached to a card, a quiz or a user are reached through it
+ALTER TABLE flashcards ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE tags ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE topics ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE daily_cards ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE quizzes ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE flashcard_drafts ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE users ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE card_suggestions ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE card_feedback ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE study_reviews ADD COLUMN organization_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES organizations(id) ON DELETE CASCADE;
+
+ALTER TABLE flashcards ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE tags ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE topics ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE daily_cards ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE quizzes ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE flashcard_drafts ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE users ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE card_suggestions ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE card_feedback ALTER COLUMN organization_id DROP DEFAULT;
+ALTER TABLE study_reviews ALTER COLUMN organization_id DROP DEFAULT;
+
+-- questions, tag slugs, topic slugs and cards of the day are now unique within an organization;
+-- the constraints keep their names, which the API reports on conflicts
+ALTER TABLE flashcards DROP CONSTRAINT flashcards_question_key;
+ALTER TABLE flashcards ADD CONSTRAINT flashcards_question_key UNIQUE (organization_id, question);
+ALTER TABLE tags DROP CONSTRAINT tags_slug_key;
+ALTER TABLE tags ADD CONSTRAINT tags_slug_key UNIQUE (organization_id, slug);
+ALTER TABLE topics DROP CONSTRAINT topics_slug_key;
+ALTER TABLE topics ADD CONSTRAINT topics_slug_key UNIQUE (organization_id, slug);
+ALTER TABLE flashcard_drafts DROP CONSTRAINT flashcard_drafts_question_key;
+ALTER TABLE flashcard_drafts ADD CONSTRAINT flashcard_drafts_question_key UNIQUE (organization_id, question);
+ALTER TABLE daily_cards DROP CONSTRAINT daily_cards_pkey;
+ALTER TABLE daily_cards ADD PRIMARY KEY (organization_id, scope, day);
+
+CREATE INDEX IF NOT EXISTS quizzes_organization_idx ON quizzes (organization_id);
+CREATE INDEX IF NOT EXISTS users_organization_idx ON users (organization_id);
+CREATE INDEX IF NOT EXISTS card_suggestions_organization_idx ON card_suggestions (organization_id);
+CREATE INDEX IF NOT EXISTS card_feedback_organization_idx ON card_feedback (organization_id);
+CREATE INDEX IF NOT EXISTS study_reviews_organization_idx ON study_reviews (organization_id);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS quizzes_created_at_idx;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS flashcards (
+    id BLOB PRIMARY KEY,
+    question TEXT NOT NULL UNIQUE,
+    answer TEXT NOT NULL,
+    topic TEXT,
+    difficulty INTEGER,
+    created_at TEXT NOT NULL,
+    updated_at TEXT
+);
+
+-- tags are stored in a join table, SQLite has no array type
+CREATE TABLE IF NOT EXISTS flashcard_tags (
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (flashcard_id, tag)
+);
+
+CREATE INDEX idx_flashcards_topic ON flashcards(topic);
+CREATE INDEX idx_flashcard_tags_tag ON flashcard_tags(tag);
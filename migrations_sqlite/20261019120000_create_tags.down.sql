@@ -0,0 +1,22 @@
+-- Add down migration script here
+CREATE TEMPORARY TABLE existing_tags AS
+SELECT ft.flashcard_id, t.name AS tag, ft.position
+FROM flashcard_tags ft
+JOIN tags t ON t.id = ft.tag_id;
+
+DROP TABLE flashcard_tags;
+DROP TABLE tags;
+
+CREATE TABLE flashcard_tags (
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (flashcard_id, tag)
+);
+
+INSERT INTO flashcard_tags (flashcard_id, tag, position)
+SELECT flashcard_id, tag, position FROM existing_tags;
+
+DROP TABLE existing_tags;
+
+CREATE INDEX idx_flashcard_tags_tag ON flashcard_tags(tag);
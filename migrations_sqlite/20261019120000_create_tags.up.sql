@@ -0,0 +1,42 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS tags (
+    id BLOB PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- the existing join table stores tag names; it is replaced by one referencing the tags table.
+-- SQLite has no regular expressions, the slug of existing tags only replaces the characters
+-- allowed in tags besides letters and digits, which is what the application does too
+CREATE TEMPORARY TABLE existing_tags AS
+SELECT flashcard_id,
+       trim(tag) AS name,
+       lower(replace(replace(trim(tag), ' ', '-'), '_', '-')) AS slug,
+       position
+FROM flashcard_tags;
+
+INSERT INTO tags (id, slug, name)
+SELECT randomblob(16), slug, MIN(name)
+FROM existing_tags
+GROUP BY slug;
+
+DROP TABLE flashcard_tags;
+
+CREATE TABLE flashcard_tags (
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    tag_id BLOB NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (flashcard_id, tag_id)
+);
+
+INSERT INTO flashcard_tags (flashcard_id, tag_id, position)
+SELECT e.flashcard_id, t.id, MIN(e.position)
+FROM existing_tags e
+JOIN tags t ON t.slug = e.slug
+GROUP BY e.flashcard_id, t.id;
+
+DROP TABLE existing_tags;
+
+CREATE INDEX idx_flashcard_tags_tag_id ON flashcard_tags(tag_id);
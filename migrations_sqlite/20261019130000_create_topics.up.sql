@@ -0,0 +1,41 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS topics (
+    id BLOB PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    parent_id BLOB REFERENCES topics(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT topics_parent_check CHECK (parent_id <> id)
+);
+
+CREATE INDEX idx_topics_parent_id ON topics(parent_id);
+
+-- every existing topic becomes a root topic; as for tags, the slug only replaces spaces
+-- and underscores, SQLite has no regular expressions
+INSERT INTO topics (id, slug, name)
+SELECT randomblob(16), slug, MIN(name)
+FROM (
+    SELECT trim(topic) AS name,
+           lower(replace(replace(trim(topic), ' ', '-'), '_', '-')) AS slug
+    FROM flashcards
+    WHERE topic IS NOT NULL
+)
+WHERE slug <> ''
+GROUP BY slug;
+
+-- no REFERENCES clause: SQLite cannot drop a column used in a foreign key, which the down
+-- migration needs to do; the application always resolves the topic before linking it
+ALTER TABLE flashcards ADD COLUMN topic_id BLOB;
+
+UPDATE flashcards
+SET topic_id = (
+    SELECT t.id
+    FROM topics t
+    WHERE t.slug = lower(replace(replace(trim(flashcards.topic), ' ', '-'), '_', '-'))
+);
+
+DROP INDEX IF EXISTS idx_flashcards_topic;
+ALTER TABLE flashcards DROP COLUMN topic;
+
+CREATE INDEX idx_flashcards_topic_id ON flashcards(topic_id);
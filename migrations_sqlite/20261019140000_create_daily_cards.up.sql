@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- the card of the day picked for each calendar day, either among every card (empty scope)
+-- or among the cards of a topic (the topic slug); kept so that recent picks aren't repeated
+CREATE TABLE IF NOT EXISTS daily_cards (
+    day TEXT NOT NULL,
+    scope TEXT NOT NULL DEFAULT '',
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    PRIMARY KEY (scope, day)
+);
+
+CREATE INDEX idx_daily_cards_flashcard_id ON daily_cards(flashcard_id);
@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS quizzes (
+    id BLOB PRIMARY KEY,
+    topic TEXT,
+    tag TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- the questions are a snapshot of the flash cards they were built from, so that editing or
+-- deleting a card doesn't change the answer key of the quizzes already handed out; the options
+-- are stored as a JSON array
+CREATE TABLE IF NOT EXISTS quiz_questions (
+    quiz_id BLOB NOT NULL REFERENCES quizzes(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    flashcard_id BLOB NOT NULL,
+    question TEXT NOT NULL,
+    options TEXT NOT NULL,
+    correct_option INTEGER NOT NULL,
+    PRIMARY KEY (quiz_id, position),
+    CONSTRAINT quiz_questions_correct_option_check
+        CHECK (correct_option >= 0 AND correct_option < json_array_length(options))
+);
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- the kind of a flash card and its payload as JSON, e.g. {"type": "multiple_choice",
+-- "options": [...], "correct": [0]}; existing cards are free text cards
+ALTER TABLE flashcards ADD COLUMN kind TEXT NOT NULL DEFAULT '{"type": "free_text"}'
+    CHECK (json_extract(kind, '$.type') IN ('free_text', 'multiple_choice', 'true_false', 'cloze'));
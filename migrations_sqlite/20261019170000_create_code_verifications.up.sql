@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- the last compile-check of the Rust snippets of each flash card; the output holds the compiler
+-- messages of the snippets which failed
+CREATE TABLE IF NOT EXISTS code_verifications (
+    flashcard_id BLOB PRIMARY KEY REFERENCES flashcards(id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    snippet_count INTEGER NOT NULL,
+    output TEXT NOT NULL DEFAULT '',
+    checked_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT code_verifications_status_check CHECK (status IN ('passed', 'failed', 'no_code'))
+);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- draft flash cards generated from documentation, kept out of the deck until an editor accepts
+-- them; source is the path of the documented item, tags a JSON array
+CREATE TABLE IF NOT EXISTS flashcard_drafts (
+    id BLOB PRIMARY KEY,
+    question TEXT NOT NULL UNIQUE,
+    answer TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    difficulty INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
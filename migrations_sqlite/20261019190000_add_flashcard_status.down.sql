@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE flashcard_reviews;
+DROP INDEX flashcards_status_idx;
+ALTER TABLE flashcards DROP COLUMN status;
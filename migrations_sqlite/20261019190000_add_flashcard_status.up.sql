@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- the lifecycle of a flash card: draft, in_review, published or archived; only published cards
+-- are shown on the public API. Existing cards are already live, so they are published; new cards
+-- are always inserted with their status
+ALTER TABLE flashcards ADD COLUMN status TEXT NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'in_review', 'published', 'archived'));
+
+CREATE INDEX IF NOT EXISTS flashcards_status_idx ON flashcards (status);
+
+-- every change of status of a flash card, with the note left by the reviewer
+CREATE TABLE IF NOT EXISTS flashcard_reviews (
+    id BLOB PRIMARY KEY,
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    note TEXT,
+    reviewed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS flashcard_reviews_flashcard_id_idx ON flashcard_reviews (flashcard_id);
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- the learners, authenticated by a bearer token of which only the SHA-256 hash is kept
+CREATE TABLE IF NOT EXISTS users (
+    id BLOB PRIMARY KEY,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,18 @@
+-- Add up migration script here
+-- new cards and edits proposed by the learners, waiting for a moderator; flashcard_id is null
+-- for a new card, changes holds the proposed fields and diff what they change on the card, both
+-- as JSON
+CREATE TABLE IF NOT EXISTS card_suggestions (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    flashcard_id BLOB REFERENCES flashcards(id) ON DELETE CASCADE,
+    changes TEXT NOT NULL,
+    diff TEXT NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'pending',
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reviewed_at TEXT,
+    CONSTRAINT card_suggestions_status_check CHECK (status IN ('pending', 'approved', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS card_suggestions_status_idx ON card_suggestions (status, created_at);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- the cards studied by the learners, with how well they recalled them; their progress is
+-- derived from these records
+CREATE TABLE IF NOT EXISTS study_reviews (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    grade TEXT NOT NULL,
+    reviewed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT study_reviews_grade_check CHECK (grade IN ('again', 'hard', 'good', 'easy'))
+);
+
+CREATE INDEX IF NOT EXISTS study_reviews_user_idx ON study_reviews (user_id, reviewed_at);
@@ -0,0 +1,128 @@
+-- no-transaction
+-- Add down migration script here
+-- only the content of the default organization is kept; the tables are rebuilt with their
+-- former UNIQUE constraints, with foreign keys turned off as in the up migration
+PRAGMA foreign_keys = ON;
+
+DELETE FROM organizations WHERE id <> X'00000000000000000000000000000000';
+DELETE FROM quizzes WHERE organization_id <> X'00000000000000000000000000000000';
+DELETE FROM users WHERE organization_id <> X'00000000000000000000000000000000';
+DELETE FROM card_suggestions WHERE organization_id <> X'00000000000000000000000000000000';
+DELETE FROM card_feedback WHERE organization_id <> X'00000000000000000000000000000000';
+DELETE FROM study_reviews WHERE organization_id <> X'00000000000000000000000000000000';
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+DROP INDEX IF EXISTS study_reviews_organization_idx;
+DROP INDEX IF EXISTS card_feedback_organization_idx;
+DROP INDEX IF EXISTS card_suggestions_organization_idx;
+DROP INDEX IF EXISTS users_organization_idx;
+DROP INDEX IF EXISTS quizzes_organization_idx;
+
+ALTER TABLE study_reviews DROP COLUMN organization_id;
+ALTER TABLE card_feedback DROP COLUMN organization_id;
+ALTER TABLE card_suggestions DROP COLUMN organization_id;
+ALTER TABLE users DROP COLUMN organization_id;
+ALTER TABLE quizzes DROP COLUMN organization_id;
+
+CREATE TABLE new_flashcard_drafts (
+    id BLOB PRIMARY KEY,
+    question TEXT NOT NULL UNIQUE,
+    answer TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    difficulty INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO new_flashcard_drafts (id, question, answer, topic, tags, difficulty, source, created_at)
+SELECT id, question, answer, topic, tags, difficulty, source, created_at
+FROM flashcard_drafts;
+
+DROP TABLE flashcard_drafts;
+ALTER TABLE new_flashcard_drafts RENAME TO flashcard_drafts;
+
+CREATE TABLE new_daily_cards (
+    day TEXT NOT NULL,
+    scope TEXT NOT NULL DEFAULT '',
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    PRIMARY KEY (scope, day)
+);
+
+INSERT INTO new_daily_cards (day, scope, flashcard_id)
+SELECT day, scope, flashcard_id
+FROM daily_cards;
+
+DROP TABLE daily_cards;
+ALTER TABLE new_daily_cards RENAME TO daily_cards;
+
+CREATE INDEX idx_daily_cards_flashcard_id ON daily_cards(flashcard_id);
+
+CREATE TABLE new_topics (
+    id BLOB PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    parent_id BLOB REFERENCES topics(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT topics_parent_check CHECK (parent_id <> id)
+);
+
+INSERT INTO new_topics (id, slug, name, description, parent_id, created_at)
+SELECT id, slug, name, description, parent_id, created_at
+FROM topics;
+
+DROP TABLE topics;
+ALTER TABLE new_topics RENAME TO topics;
+
+CREATE INDEX idx_topics_parent_id ON topics(parent_id);
+
+CREATE TABLE new_tags (
+    id BLOB PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO new_tags (id, slug, name, description, created_at)
+SELECT id, slug, name, description, created_at
+FROM tags;
+
+DROP TABLE tags;
+ALTER TABLE new_tags RENAME TO tags;
+
+CREATE TABLE new_flashcards (
+    id BLOB PRIMARY KEY,
+    question TEXT NOT NULL UNIQUE,
+    answer TEXT NOT NULL,
+    difficulty INTEGER,
+    created_at TEXT NOT NULL,
+    updated_at TEXT,
+    topic_id BLOB,
+    kind TEXT NOT NULL DEFAULT '{"type": "free_text"}'
+        CHECK (json_extract(kind, '$.type') IN ('free_text', 'multiple_choice', 'true_false', 'cloze')),
+    status TEXT NOT NULL DEFAULT 'published'
+        CHECK (status IN ('draft', 'in_review', 'published', 'archived'))
+);
+
+INSERT INTO new_flashcards (id, question, answer, difficulty, created_at, updated_at, topic_id, kind, status)
+SELECT id, question, answer, difficulty, created_at, updated_at, topic_id, kind, status
+FROM flashcards;
+
+DROP TABLE flashcards;
+ALTER TABLE new_flashcards RENAME TO flashcards;
+
+CREATE INDEX idx_flashcards_topic_id ON flashcards(topic_id);
+CREATE INDEX flashcards_status_idx ON flashcards (status);
+
+DROP TABLE organization_api_keys;
+DROP TABLE organization_hosts;
+DROP TABLE organizations;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
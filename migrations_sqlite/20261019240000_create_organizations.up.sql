@@ -0,0 +1,158 @@
+-- no-transaction
+-- Add up migration script here
+-- the teams sharing the instance, each with its own cards, tags, topics, learners and API keys;
+-- the content created before organizations existed belongs to the default organization, whose
+-- API key is the one of the configuration. The tables whose uniqueness now holds within an
+-- organization are rebuilt, SQLite cannot drop a UNIQUE constraint; foreign keys are turned off
+-- meanwhile, which has to happen outside of a transaction, so that dropping the old tables
+-- doesn't cascade
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id BLOB PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO organizations (id, slug, name, created_at)
+VALUES (X'00000000000000000000000000000000', 'default', 'Default', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+
+-- the host names an organization is served at, as forwarded by the reverse proxy
+CREATE TABLE IF NOT EXISTS organization_hosts (
+    host TEXT PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE
+);
+
+-- the API keys of the organizations, of which only the SHA-256 hash is kept
+CREATE TABLE IF NOT EXISTS organization_api_keys (
+    key_hash TEXT PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE new_flashcards (
+    id BLOB PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    question TEXT NOT NULL,
+    answer TEXT NOT NULL,
+    difficulty INTEGER,
+    created_at TEXT NOT NULL,
+    updated_at TEXT,
+    topic_id BLOB,
+    kind TEXT NOT NULL DEFAULT '{"type": "free_text"}'
+        CHECK (json_extract(kind, '$.type') IN ('free_text', 'multiple_choice', 'true_false', 'cloze')),
+    status TEXT NOT NULL DEFAULT 'published'
+        CHECK (status IN ('draft', 'in_review', 'published', 'archived')),
+    UNIQUE (organization_id, question)
+);
+
+INSERT INTO new_flashcards (id, organization_id, question, answer, difficulty, created_at, updated_at, topic_id, kind, status)
+SELECT id, X'00000000000000000000000000000000', question, answer, difficulty, created_at, updated_at, topic_id, kind, status
+FROM flashcards;
+
+DROP TABLE flashcards;
+ALTER TABLE new_flashcards RENAME TO flashcards;
+
+CREATE INDEX idx_flashcards_topic_id ON flashcards(topic_id);
+CREATE INDEX flashcards_status_idx ON flashcards (status);
+
+CREATE TABLE new_tags (
+    id BLOB PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    slug TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (organization_id, slug)
+);
+
+INSERT INTO new_tags (id, organization_id, slug, name, description, created_at)
+SELECT id, X'00000000000000000000000000000000', slug, name, description, created_at
+FROM tags;
+
+DROP TABLE tags;
+ALTER TABLE new_tags RENAME TO tags;
+
+CREATE TABLE new_topics (
+    id BLOB PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    slug TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    parent_id BLOB REFERENCES topics(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT topics_parent_check CHECK (parent_id <> id),
+    UNIQUE (organization_id, slug)
+);
+
+INSERT INTO new_topics (id, organization_id, slug, name, description, parent_id, created_at)
+SELECT id, X'00000000000000000000000000000000', slug, name, description, parent_id, created_at
+FROM topics;
+
+DROP TABLE topics;
+ALTER TABLE new_topics RENAME TO topics;
+
+CREATE INDEX idx_topics_parent_id ON topics(parent_id);
+
+CREATE TABLE new_daily_cards (
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    day TEXT NOT NULL,
+    scope TEXT NOT NULL DEFAULT '',
+    flashcard_id BLOB NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    PRIMARY KEY (organization_id, scope, day)
+);
+
+INSERT INTO new_daily_cards (organization_id, day, scope, flashcard_id)
+SELECT X'00000000000000000000000000000000', day, scope, flashcard_id
+FROM daily_cards;
+
+DROP TABLE daily_cards;
+ALTER TABLE new_daily_cards RENAME TO daily_cards;
+
+CREATE INDEX idx_daily_cards_flashcard_id ON daily_cards(flashcard_id);
+
+CREATE TABLE new_flashcard_drafts (
+    id BLOB PRIMARY KEY,
+    organization_id BLOB NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    question TEXT NOT NULL,
+    answer TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    difficulty INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (organization_id, question)
+);
+
+INSERT INTO new_flashcard_drafts (id, organization_id, question, answer, topic, tags, difficulty, source, created_at)
+SELECT id, X'00000000000000000000000000000000', question, answer, topic, tags, difficulty, source, created_at
+FROM flashcard_drafts;
+
+DROP TABLE flashcard_drafts;
+ALTER TABLE new_flashcard_drafts RENAME TO flashcard_drafts;
+
+-- the other tables only gain a column; no REFERENCES clause, so that the down migration can
+-- drop it. New rows are always inserted with their organization
+ALTER TABLE quizzes ADD COLUMN organization_id BLOB NOT NULL
+    DEFAULT X'00000000000000000000000000000000';
+ALTER TABLE users ADD COLUMN organization_id BLOB NOT NULL
+    DEFAULT X'00000000000000000000000000000000';
+ALTER TABLE card_suggestions ADD COLUMN organization_id BLOB NOT NULL
+    DEFAULT X'00000000000000000000000000000000';
+ALTER TABLE card_feedback ADD COLUMN organization_id BLOB NOT NULL
+    DEFAULT X'00000000000000000000000000000000';
+ALTER TABLE study_reviews ADD COLUMN organization_id BLOB NOT NULL
+    DEFAULT X'00000000000000000000000000000000';
+
+CREATE INDEX IF NOT EXISTS quizzes_organization_idx ON quizzes (organization_id);
+CREATE INDEX IF NOT EXISTS users_organization_idx ON users (organization_id);
+CREATE INDEX IF NOT EXISTS card_suggestions_organization_idx ON card_suggestions (organization_id);
+CREATE INDEX IF NOT EXISTS card_feedback_organization_idx ON card_feedback (organization_id);
+CREATE INDEX IF NOT EXISTS study_reviews_organization_idx ON study_reviews (organization_id);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
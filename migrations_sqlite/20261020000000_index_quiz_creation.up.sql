@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- expired quizzes are deleted by their creation time
+CREATE INDEX IF NOT EXISTS quizzes_created_at_idx ON quizzes (organization_id, created_at);
@@ -0,0 +1,159 @@
+// server/tests/integration/check_answer.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::{AnswerAttempt, AnswerVerdict, CardKind, NewFlashCard};
+use app::routes::flashcards::{AnswerCheckResponse, FlashCardResponse};
+use pavex::http::StatusCode;
+use uuid::Uuid;
+
+// create a flash card of the given kind and return its id
+async fn create_card(api: &TestApi, question: &str, answer: &str, kind: CardKind) -> String {
+    let card = NewFlashCard {
+        question: question.to_string(),
+        answer: answer.to_string(),
+        topic: "ownership".to_string(),
+        tags: vec!["borrowing".to_string()],
+        difficulty: 1,
+        kind,
+    };
+    let created: FlashCardResponse = api
+        .create_published_flashcard(&card)
+        .await
+        .json()
+        .await
+        .unwrap();
+    created.content.id.to_string()
+}
+
+// submit a typed answer and return the verdict
+async fn verdict(api: &TestApi, id: &str, answer: &str) -> AnswerVerdict {
+    let attempt = AnswerAttempt {
+        answer: answer.to_string(),
+        cloze: None,
+    };
+    let response = api.check_answer(id.to_string(), &attempt).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: AnswerCheckResponse = response.json().await.unwrap();
+    body.content.verdict
+}
+
+#[tokio::test]
+async fn check_answer_grades_exact_close_and_wrong_answers() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "Which part of the compiler enforces the borrowing rules?",
+        "The borrow checker.",
+        CardKind::FreeText,
+    )
+    .await;
+
+    // Act & Assert
+    assert_eq!(
+        verdict(&api, &id, "the Borrow Checker").await,
+        AnswerVerdict::Exact
+    );
+    assert_eq!(
+        verdict(&api, &id, "the borow checker").await,
+        AnswerVerdict::Close
+    );
+    assert_eq!(
+        verdict(&api, &id, "the type system").await,
+        AnswerVerdict::Wrong
+    );
+}
+
+#[tokio::test]
+async fn check_answer_returns_the_canonical_answer() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "Which part of the compiler enforces the borrowing rules?",
+        "The borrow checker.",
+        CardKind::FreeText,
+    )
+    .await;
+    let attempt = AnswerAttempt {
+        answer: "the lexer".to_string(),
+        cloze: None,
+    };
+
+    // Act
+    let response = api.check_answer(id.clone(), &attempt).await;
+
+    // Assert
+    let body: AnswerCheckResponse = response.json().await.unwrap();
+    assert_eq!(body.content.flashcard_id.to_string(), id);
+    assert_eq!(body.content.canonical_answer, "The borrow checker.");
+}
+
+#[tokio::test]
+async fn check_answer_grades_the_requested_cloze_deletion() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "{{c1::Ownership}} is checked by the {{c2::borrow checker}}.",
+        "Ownership is checked by the borrow checker.",
+        CardKind::Cloze,
+    )
+    .await;
+    let attempt = AnswerAttempt {
+        answer: "borrow checker".to_string(),
+        cloze: Some(2),
+    };
+
+    // Act
+    let response = api.check_answer(id.clone(), &attempt).await;
+
+    // Assert
+    let body: AnswerCheckResponse = response.json().await.unwrap();
+    assert_eq!(body.content.verdict, AnswerVerdict::Exact);
+    assert_eq!(body.content.canonical_answer, "borrow checker");
+
+    let unknown = AnswerAttempt {
+        cloze: Some(3),
+        ..attempt
+    };
+    let response = api.check_answer(id, &unknown).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = response.json().await.unwrap();
+    assert_eq!(problem.errors[0].rule, "invalid_cloze_number");
+}
+
+#[tokio::test]
+async fn check_answer_returns_422_for_an_empty_answer() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(&api, "What is Rust?", "A language.", CardKind::FreeText).await;
+    let attempt = AnswerAttempt {
+        answer: "  ".to_string(),
+        cloze: None,
+    };
+
+    // Act
+    let response = api.check_answer(id, &attempt).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn check_answer_returns_404_for_an_unknown_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let attempt = AnswerAttempt {
+        answer: "A language.".to_string(),
+        cloze: None,
+    };
+
+    // Act
+    let response = api.check_answer(Uuid::new_v4().to_string(), &attempt).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
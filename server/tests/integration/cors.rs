@@ -0,0 +1,195 @@
+use crate::helpers::TestApi;
+use pavex::http::{Method, StatusCode};
+use reqwest::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD, HOST,
+    ORIGIN, VARY,
+};
+use uuid::Uuid;
+
+async fn get_flashcards_from(api: &TestApi, origin: &str) -> reqwest::Response {
+    api.api_client
+        .get(format!("{}/v1/flashcards", api.api_address))
+        .header(HOST, "rusty-flash-knowledge.net")
+        .header(ORIGIN, origin)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn preflight(api: &TestApi, path: &str, origin: &str, method: &str) -> reqwest::Response {
+    api.api_client
+        .request(Method::OPTIONS, format!("{}{}", api.api_address, path))
+        .header(HOST, "api.rusty-flash-knowledge.net")
+        .header(ORIGIN, origin)
+        .header(ACCESS_CONTROL_REQUEST_METHOD, method)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+fn header<'a>(
+    response: &'a reqwest::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .map(|value| value.to_str().unwrap())
+}
+
+async fn spawn_with_allow_list() -> TestApi {
+    TestApi::spawn_with_config(|config| {
+        config.cors.allowed_origins = vec![
+            "https://cards.example.com".to_string(),
+            "https://*.partner.example.org".to_string(),
+        ];
+        config.cors.allowed_methods = vec!["GET".to_string(), "POST".to_string()];
+        config.cors.exposed_headers = vec!["X-Request-Id".to_string()];
+        config.cors.allow_credentials = true;
+    })
+    .await
+}
+
+#[tokio::test]
+async fn any_origin_is_allowed_by_default() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = get_flashcards_from(&api, "https://elsewhere.example.net").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header(&response, ACCESS_CONTROL_ALLOW_ORIGIN), Some("*"));
+    assert_eq!(header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS), None);
+    assert_eq!(header(&response, VARY), None);
+}
+
+#[tokio::test]
+async fn any_origin_is_not_allowed_with_credentials() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.cors.allowed_origins =
+            vec!["*".to_string(), "https://cards.example.com".to_string()];
+        config.cors.allow_credentials = true;
+    })
+    .await;
+
+    // Act
+    let elsewhere = get_flashcards_from(&api, "https://elsewhere.example.net").await;
+    let listed = get_flashcards_from(&api, "https://cards.example.com").await;
+    let preflight = preflight(&api, "/v1/drafts", "https://elsewhere.example.net", "GET").await;
+
+    // Assert
+    assert_eq!(elsewhere.status(), StatusCode::OK);
+    assert_eq!(header(&elsewhere, ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    assert_eq!(header(&elsewhere, ACCESS_CONTROL_ALLOW_CREDENTIALS), None);
+    assert_eq!(header(&elsewhere, VARY), Some("Origin"));
+    assert_eq!(
+        header(&listed, ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some("https://cards.example.com")
+    );
+    assert_eq!(header(&preflight, ACCESS_CONTROL_ALLOW_ORIGIN), None);
+}
+
+#[tokio::test]
+async fn preflight_requests_are_answered_for_every_route() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = Uuid::new_v4();
+
+    // Act
+    let admin = preflight(
+        &api,
+        &format!("/v1/flashcards/{id}/verify-code"),
+        "https://elsewhere.example.net",
+        "POST",
+    )
+    .await;
+    let unknown = preflight(&api, "/v1/unknown", "https://elsewhere.example.net", "GET").await;
+
+    // Assert
+    assert_eq!(admin.status(), StatusCode::NO_CONTENT);
+    assert_eq!(header(&admin, ACCESS_CONTROL_ALLOW_ORIGIN), Some("*"));
+    let methods = header(&admin, ACCESS_CONTROL_ALLOW_METHODS).unwrap();
+    assert!(methods.contains("GET") && methods.contains("POST"));
+    assert_eq!(header(&admin, ACCESS_CONTROL_MAX_AGE), Some("3600"));
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn allowed_origins_are_echoed_with_credentials() {
+    // Arrange
+    let api = spawn_with_allow_list().await;
+
+    // Act
+    let listed = get_flashcards_from(&api, "https://cards.example.com").await;
+    let subdomain = get_flashcards_from(&api, "https://eu.partner.example.org").await;
+
+    // Assert
+    for (response, origin) in [
+        (&listed, "https://cards.example.com"),
+        (&subdomain, "https://eu.partner.example.org"),
+    ] {
+        assert_eq!(header(response, ACCESS_CONTROL_ALLOW_ORIGIN), Some(origin));
+        assert_eq!(
+            header(response, ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(
+            header(response, ACCESS_CONTROL_EXPOSE_HEADERS),
+            Some("X-Request-Id")
+        );
+        assert_eq!(header(response, VARY), Some("Origin"));
+    }
+}
+
+#[tokio::test]
+async fn other_origins_get_no_cors_headers_but_still_vary() {
+    // Arrange
+    let api = spawn_with_allow_list().await;
+
+    // Act
+    let response = get_flashcards_from(&api, "https://partner.example.org").await;
+    let preflight = preflight(&api, "/v1/flashcards", "https://evil.example.net", "POST").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header(&response, ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    assert_eq!(header(&response, VARY), Some("Origin"));
+    assert_eq!(header(&preflight, ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    assert_eq!(header(&preflight, VARY), Some("Origin"));
+}
+
+#[tokio::test]
+async fn preflight_only_allows_the_configured_methods() {
+    // Arrange
+    let api = spawn_with_allow_list().await;
+    let id = Uuid::new_v4();
+
+    // Act
+    let allowed = preflight(
+        &api,
+        &format!("/v1/flashcards/{id}"),
+        "https://cards.example.com",
+        "GET",
+    )
+    .await;
+    let refused = preflight(
+        &api,
+        &format!("/v1/flashcards/{id}"),
+        "https://cards.example.com",
+        "DELETE",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(
+        header(&allowed, ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some("https://cards.example.com")
+    );
+    assert_eq!(header(&allowed, ACCESS_CONTROL_ALLOW_METHODS), Some("GET"));
+    assert_eq!(refused.status(), StatusCode::NO_CONTENT);
+    assert_eq!(header(&refused, ACCESS_CONTROL_ALLOW_ORIGIN), None);
+}
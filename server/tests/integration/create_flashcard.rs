@@ -2,9 +2,11 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::NewFlashCard;
+use app::errors::ProblemDetails;
+use app::models::{CardKind, NewFlashCard};
 use app::routes::flashcards::FlashCardResponse;
 use pavex::http::StatusCode;
+use reqwest::header::CONTENT_TYPE;
 
 #[tokio::test]
 async fn create_flashcard_returns_200_for_valid_data() {
@@ -16,6 +18,7 @@ async fn create_flashcard_returns_200_for_valid_data() {
         topic: "new test topic".to_string(),
         tags: vec!["newtag1".to_string(), "newtag2".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
 
     // Act
@@ -35,7 +38,7 @@ async fn create_flashcard_returns_200_for_valid_data() {
 }
 
 #[tokio::test]
-async fn create_flashcard_returns_400_for_invalid_data() {
+async fn create_flashcard_returns_422_and_every_field_error_for_invalid_data() {
     // Arrange
     let api = TestApi::spawn().await;
     let new_flash_card = NewFlashCard {
@@ -44,13 +47,22 @@ async fn create_flashcard_returns_400_for_invalid_data() {
         topic: "".to_string(),
         tags: vec![],
         difficulty: 99,
+        kind: CardKind::FreeText,
     };
 
     // Act
     let response = api.create_flashcard(&new_flash_card).await;
 
     // Assert
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let problem = response.json::<ProblemDetails>().await.unwrap();
+    let fields: Vec<&str> = problem.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(problem.code, "validation_failed");
+    assert_eq!(
+        fields,
+        vec!["question", "answer", "topic", "tags", "difficulty"]
+    );
 }
 
 #[tokio::test]
@@ -63,6 +75,7 @@ async fn create_returns_500_if_there_is_a_fatal_database_error() {
         topic: "new test topic".to_string(),
         tags: vec!["newtag1".to_string(), "newtag2".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
 
     // Sabotage the database
@@ -87,6 +100,7 @@ async fn create_flashcard_returns_409_for_duplicate_question() {
         topic: "intro".to_string(),
         tags: vec!["basics".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
 
     // First insertion should succeed
@@ -97,3 +111,172 @@ async fn create_flashcard_returns_409_for_duplicate_question() {
     let second = api.create_flashcard(&flashcard).await;
     assert_eq!(second.status(), StatusCode::CONFLICT);
 }
+
+#[tokio::test]
+async fn create_flashcard_errors_are_problem_details_documents() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let flashcard = NewFlashCard {
+        question: "What is Rust?".to_string(),
+        answer: "A system programming language.".to_string(),
+        topic: "intro".to_string(),
+        tags: vec!["basics".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+    api.create_flashcard(&flashcard).await;
+
+    // Act
+    let response = api.create_flashcard(&flashcard).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        response.headers().get(CONTENT_TYPE).unwrap(),
+        "application/problem+json"
+    );
+
+    let problem = response.json::<ProblemDetails>().await.unwrap();
+    assert_eq!(problem.status, 409);
+    assert_eq!(problem.code, "duplicate_question");
+    assert_eq!(problem.instance, "/v1/flashcards");
+    assert!(!problem.request_id.is_empty());
+}
+
+#[tokio::test]
+async fn create_returns_sanitized_details_for_a_fatal_database_error() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let new_flash_card = NewFlashCard {
+        question: "new test question".to_string(),
+        answer: "new test answer".to_string(),
+        topic: "new test topic".to_string(),
+        tags: vec!["newtag1".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+
+    // Sabotage the database
+    sqlx::query("ALTER TABLE flashcards DROP COLUMN question;")
+        .execute(&api.api_db_pool)
+        .await
+        .unwrap();
+
+    // Act
+    let response = api.create_flashcard(&new_flash_card).await;
+
+    // Assert
+    let problem = response.json::<ProblemDetails>().await.unwrap();
+    assert_eq!(problem.code, "database_error");
+    assert!(!problem.detail.contains("question"));
+}
+
+#[tokio::test]
+async fn create_flashcard_accepts_a_multiple_choice_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let new_flash_card = NewFlashCard {
+        question: "Which of these types are Copy?".to_string(),
+        answer: "Scalars are Copy, heap owning types are not.".to_string(),
+        topic: "ownership".to_string(),
+        tags: vec!["traits".to_string()],
+        difficulty: 2,
+        kind: CardKind::MultipleChoice {
+            options: vec![
+                " u32 ".to_string(),
+                "String".to_string(),
+                "bool".to_string(),
+            ],
+            correct: vec![0, 2],
+        },
+    };
+
+    // Act
+    let response = api.create_flashcard(&new_flash_card).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.json::<FlashCardResponse>().await.unwrap();
+    assert_eq!(
+        body.content.kind,
+        CardKind::MultipleChoice {
+            options: vec!["u32".to_string(), "String".to_string(), "bool".to_string()],
+            correct: vec![0, 2],
+        }
+    );
+}
+
+#[tokio::test]
+async fn create_flashcard_returns_the_sub_cards_of_a_cloze_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let new_flash_card = NewFlashCard {
+        question: "{{c1::Ownership}} is checked by the {{c2::borrow checker}}.".to_string(),
+        answer: "Ownership is checked by the borrow checker.".to_string(),
+        topic: "ownership".to_string(),
+        tags: vec!["borrowing".to_string()],
+        difficulty: 2,
+        kind: CardKind::Cloze,
+    };
+
+    // Act
+    let response = api.create_flashcard(&new_flash_card).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.json::<FlashCardResponse>().await.unwrap();
+    assert_eq!(body.content.kind, CardKind::Cloze);
+    assert_eq!(body.content.cloze_cards.len(), 2);
+    assert_eq!(
+        body.content.cloze_cards[1].prompt,
+        "Ownership is checked by the [...]."
+    );
+    assert_eq!(body.content.cloze_cards[1].answer, "borrow checker");
+}
+
+#[tokio::test]
+async fn create_flashcard_returns_422_for_an_invalid_card_kind() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let cases = [
+        (
+            CardKind::MultipleChoice {
+                options: vec!["Yes".to_string(), "Yes".to_string()],
+                correct: vec![0],
+            },
+            "duplicate_option",
+        ),
+        (
+            CardKind::MultipleChoice {
+                options: vec!["Yes".to_string(), "No".to_string()],
+                correct: vec![2],
+            },
+            "invalid_correct_option",
+        ),
+        (CardKind::Cloze, "missing_cloze_deletion"),
+    ];
+
+    for (kind, code) in cases {
+        let new_flash_card = NewFlashCard {
+            question: "Is Rust memory safe?".to_string(),
+            answer: "Yes, without a garbage collector.".to_string(),
+            topic: "intro".to_string(),
+            tags: vec!["basics".to_string()],
+            difficulty: 1,
+            kind,
+        };
+
+        // Act
+        let response = api.create_flashcard(&new_flash_card).await;
+
+        // Assert
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let problem = response.json::<ProblemDetails>().await.unwrap();
+        assert!(
+            problem.errors.iter().any(|error| error.rule == code),
+            "expected a {code} error"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// server/tests/integration/daily_flashcard.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard};
+use app::routes::flashcards::FlashCardResponse;
+use pavex::http::StatusCode;
+
+// seed a few flash cards, over two topics
+async fn seed_flashcards(api: &TestApi) {
+    for (question, topic) in [
+        ("What is ownership?", "memory"),
+        ("What is borrowing?", "memory"),
+        ("What is a trait?", "traits"),
+        ("What is a generic?", "traits"),
+    ] {
+        let card = NewFlashCard {
+            question: question.to_string(),
+            answer: "An answer.".to_string(),
+            topic: topic.to_string(),
+            tags: vec!["rust".to_string()],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+        api.create_published_flashcard(&card).await;
+    }
+}
+
+#[tokio::test]
+async fn get_daily_flashcard_returns_the_same_card_all_day() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+
+    // Act
+    let first: FlashCardResponse = api
+        .get_daily_flashcard("tz=Europe/Paris")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let second: FlashCardResponse = api
+        .get_daily_flashcard("tz=Europe/Paris")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn get_daily_flashcard_picks_within_the_topic() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+
+    // Act
+    let response = api.get_daily_flashcard("topic=traits").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let card: FlashCardResponse = response.json().await.unwrap();
+    assert_eq!(card.content.topic, "traits");
+}
+
+#[tokio::test]
+async fn get_daily_flashcard_returns_404_when_no_flashcards_exist() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_daily_flashcard("").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_daily_flashcard_returns_422_for_an_unknown_time_zone() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+
+    // Act
+    let response = api.get_daily_flashcard("tz=Mars/Olympus_Mons").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -2,7 +2,7 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::FlashCard;
+use app::models::{CardKind, CardStatus, FlashCard};
 use jiff_sqlx::ToSqlx;
 use pavex::http::StatusCode;
 use pavex::time::Timestamp as PavexTimestamp;
@@ -19,22 +19,13 @@ async fn delete_flashcard_returns_204_when_successful() {
         topic: "test topic".to_string(),
         tags: vec!["tag1".to_string(), "tag2".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
         created_at: PavexTimestamp::now().to_sqlx(),
         updated_at: None,
     };
 
-    let id: Uuid = sqlx::query_scalar("INSERT INTO flashcards (id, question, answer, topic, tags, difficulty, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id;")
-        .bind(flash_card.id)
-        .bind(flash_card.question)
-        .bind(flash_card.answer)
-        .bind(flash_card.topic)
-        .bind(flash_card.tags)
-        .bind(flash_card.difficulty)
-        .bind(flash_card.created_at)
-        .bind(flash_card.updated_at)
-        .fetch_one(&api.api_db_pool)
-        .await
-        .unwrap();
+    let id = api.insert_flashcard(&flash_card).await;
 
     // Act
     let response = api.delete_flashcard(id.to_string()).await;
@@ -76,3 +67,42 @@ async fn delete_flashcard_returns_404_for_non_existent_id() {
     // Assert
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn delete_flashcard_returns_409_when_still_referenced() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let flash_card = FlashCard {
+        id: Uuid::new_v4(),
+        question: "test question".to_string(),
+        answer: "test answer".to_string(),
+        topic: "test topic".to_string(),
+        tags: vec!["tag1".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
+        created_at: PavexTimestamp::now().to_sqlx(),
+        updated_at: None,
+    };
+
+    api.insert_flashcard(&flash_card).await;
+
+    // Reference the flash card from another table
+    sqlx::query(
+        "CREATE TABLE card_references (flashcard_id UUID NOT NULL REFERENCES flashcards(id));",
+    )
+    .execute(&api.api_db_pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO card_references (flashcard_id) VALUES ($1);")
+        .bind(flash_card.id)
+        .execute(&api.api_db_pool)
+        .await
+        .unwrap();
+
+    // Act
+    let response = api.delete_flashcard(flash_card.id.to_string()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
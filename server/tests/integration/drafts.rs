@@ -0,0 +1,177 @@
+// server/tests/integration/drafts.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::{CardStatus, ReviewDecision, RustdocImport};
+use app::routes::drafts::{DraftImportResponse, DraftsResponse};
+use app::routes::flashcards::FlashCardResponse;
+use pavex::http::StatusCode;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// a trimmed down rustdoc JSON document: a documented struct with a documented method, a trait
+// impl and a private function, neither of which make cards
+const RUSTDOC_JSON: &str = r#"{
+    "root": 0,
+    "format_version": 39,
+    "index": {
+        "0": {"id": 0, "crate_id": 0, "name": "deck", "visibility": "public", "docs": "Decks.",
+              "inner": {"module": {"items": [1, 2]}}},
+        "1": {"id": 1, "crate_id": 0, "name": "Pile", "visibility": "public",
+              "docs": "A pile of cards.\n\nDetails.", "inner": {"struct": {"impls": [3, 5]}}},
+        "2": {"id": 2, "crate_id": 0, "name": "helper", "visibility": "crate", "docs": "Hidden.",
+              "inner": {"function": {}}},
+        "3": {"id": 3, "crate_id": 0, "name": null, "visibility": "default", "docs": null,
+              "inner": {"impl": {"trait": null, "items": [4]}}},
+        "4": {"id": 4, "crate_id": 0, "name": "shuffle", "visibility": "public",
+              "docs": "Shuffle the pile in place.", "inner": {"function": {}}},
+        "5": {"id": 5, "crate_id": 0, "name": null, "visibility": "default", "docs": null,
+              "inner": {"impl": {"trait": {"name": "Clone"}, "items": [6]}}},
+        "6": {"id": 6, "crate_id": 0, "name": "clone", "visibility": "default", "docs": "Clone.",
+              "inner": {"function": {}}}
+    },
+    "paths": {
+        "0": {"crate_id": 0, "path": ["deck"], "kind": "module"},
+        "1": {"crate_id": 0, "path": ["deck", "piles", "Pile"], "kind": "struct"},
+        "2": {"crate_id": 0, "path": ["deck", "helper"], "kind": "function"}
+    }
+}"#;
+
+// spawn the API with a fresh rustdoc directory holding `deck.json`
+async fn spawn_with_rustdoc() -> TestApi {
+    let dir: PathBuf = std::env::temp_dir().join(format!("rustdoc-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("deck.json"), RUSTDOC_JSON).unwrap();
+    std::fs::write(dir.join("notes.json"), r#"{"name": "not rustdoc"}"#).unwrap();
+
+    TestApi::spawn_with_config(|config| config.import.rustdoc_dir = dir).await
+}
+
+fn import(path: &str) -> RustdocImport {
+    RustdocImport {
+        path: path.to_string(),
+        topic: None,
+        difficulty: Some(2),
+    }
+}
+
+#[tokio::test]
+async fn import_rustdoc_creates_drafts_for_documented_public_items() {
+    // Arrange
+    let api = spawn_with_rustdoc().await;
+
+    // Act
+    let response = api.import_rustdoc(&import("deck.json")).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: DraftImportResponse = response.json().await.unwrap();
+    assert_eq!(body.content.documented, 2);
+    assert_eq!(body.content.created, 2);
+    let questions: Vec<&str> = body
+        .content
+        .drafts
+        .iter()
+        .map(|draft| draft.question.as_str())
+        .collect();
+    assert_eq!(
+        questions,
+        vec![
+            "What is the `Pile` type for?",
+            "What does `Pile::shuffle` do?"
+        ]
+    );
+    let shuffle = &body.content.drafts[1];
+    assert_eq!(shuffle.answer, "Shuffle the pile in place.");
+    assert_eq!(shuffle.topic, "deck");
+    assert_eq!(shuffle.tags, vec!["deck-piles".to_string()]);
+    assert_eq!(shuffle.source, "deck::piles::Pile::shuffle");
+}
+
+#[tokio::test]
+async fn drafts_stay_out_of_the_deck_until_accepted() {
+    // Arrange
+    let api = spawn_with_rustdoc().await;
+    api.import_rustdoc(&import("deck.json")).await;
+    let drafts: DraftsResponse = api.list_drafts().await.json().await.unwrap();
+    assert_eq!(drafts.content.len(), 2);
+    let deck: Vec<FlashCardResponse> = api.get_flashcards().await.json().await.unwrap();
+    assert!(deck.is_empty());
+
+    // Act
+    let response = api.accept_draft(drafts.content[1].id.to_string()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let accepted: FlashCardResponse = response.json().await.unwrap();
+    assert_eq!(accepted.content.question, "What does `Pile::shuffle` do?");
+    assert_eq!(accepted.content.difficulty, 2);
+    assert_eq!(accepted.content.status, CardStatus::Draft);
+
+    // an accepted draft goes through the review workflow like any new card
+    let deck: Vec<FlashCardResponse> = api.get_flashcards().await.json().await.unwrap();
+    assert!(deck.is_empty());
+    let id = accepted.content.id.to_string();
+    api.submit_flashcard(id.clone()).await;
+    api.approve_flashcard(id, &ReviewDecision::default()).await;
+    let deck: Vec<FlashCardResponse> = api.get_flashcards().await.json().await.unwrap();
+    assert_eq!(deck.len(), 1);
+    let remaining: DraftsResponse = api.list_drafts().await.json().await.unwrap();
+    assert_eq!(remaining.content.len(), 1);
+}
+
+#[tokio::test]
+async fn import_rustdoc_skips_questions_already_asked() {
+    // Arrange
+    let api = spawn_with_rustdoc().await;
+    api.import_rustdoc(&import("deck.json")).await;
+
+    // Act
+    let response = api.import_rustdoc(&import("deck.json")).await;
+
+    // Assert
+    let body: DraftImportResponse = response.json().await.unwrap();
+    assert_eq!(body.content.documented, 2);
+    assert_eq!(body.content.created, 0);
+}
+
+#[tokio::test]
+async fn delete_draft_discards_a_draft() {
+    // Arrange
+    let api = spawn_with_rustdoc().await;
+    api.import_rustdoc(&import("deck.json")).await;
+    let drafts: DraftsResponse = api.list_drafts().await.json().await.unwrap();
+    let id = drafts.content[0].id.to_string();
+
+    // Act
+    let response = api.delete_draft(id.clone()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        api.delete_draft(id.clone()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(api.accept_draft(id).await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn import_rustdoc_returns_422_for_invalid_files() {
+    // Arrange
+    let api = spawn_with_rustdoc().await;
+
+    for (path, rule) in [
+        ("../deck.json", "invalid_import_path"),
+        ("missing.json", "invalid_rustdoc"),
+        ("notes.json", "invalid_rustdoc"),
+    ] {
+        // Act
+        let response = api.import_rustdoc(&import(path)).await;
+
+        // Assert
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let problem: ProblemDetails = response.json().await.unwrap();
+        assert_eq!(problem.errors[0].rule, rule);
+    }
+}
@@ -0,0 +1,205 @@
+// server/tests/integration/feedback.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::{CardKind, FeedbackKind, NewFeedback, NewFlashCard};
+use app::routes::feedback::{
+    FeedbackListResponse, FeedbackReportResponse, FeedbackResponse, ResolvedFlagsResponse,
+};
+use app::routes::flashcards::FlashCardResponse;
+use pavex::http::StatusCode;
+
+fn new_card(question: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: "memory".to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+fn feedback(kind: FeedbackKind, comment: Option<&str>) -> NewFeedback {
+    NewFeedback {
+        kind,
+        comment: comment.map(str::to_string),
+    }
+}
+
+// publish a card, returning its id
+async fn publish(api: &TestApi, question: &str) -> String {
+    let created: FlashCardResponse = api
+        .create_published_flashcard(&new_card(question))
+        .await
+        .json()
+        .await
+        .unwrap();
+    created.content.id.to_string()
+}
+
+#[tokio::test]
+async fn flags_show_up_in_the_report_until_resolved() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let flagged_once = publish(&api, "What is a trait?").await;
+    let flagged_twice = publish(&api, "What is ownership?").await;
+
+    // Act
+    let response = api
+        .leave_feedback(
+            flagged_twice.clone(),
+            &feedback(FeedbackKind::WrongAnswer, Some("  Values can be shared.  ")),
+        )
+        .await;
+    api.leave_feedback(
+        flagged_twice.clone(),
+        &feedback(FeedbackKind::Unclear, None),
+    )
+    .await;
+    api.leave_feedback(flagged_twice.clone(), &feedback(FeedbackKind::Upvote, None))
+        .await;
+    api.leave_feedback(
+        flagged_once.clone(),
+        &feedback(FeedbackKind::Outdated, None),
+    )
+    .await;
+    let report: FeedbackReportResponse = api.get_feedback_report().await.json().await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let created: FeedbackResponse = response.json().await.unwrap();
+    assert_eq!(created.content.kind, FeedbackKind::WrongAnswer);
+    assert_eq!(
+        created.content.comment.as_deref(),
+        Some("Values can be shared.")
+    );
+
+    let flagged: Vec<(String, i64, i64)> = report
+        .content
+        .iter()
+        .map(|card| (card.flashcard_id.to_string(), card.open_flags, card.upvotes))
+        .collect();
+    assert_eq!(
+        flagged,
+        vec![(flagged_twice.clone(), 2, 1), (flagged_once.clone(), 1, 0)]
+    );
+
+    let flags: FeedbackListResponse = api
+        .list_flags(flagged_twice.clone())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(flags.content.len(), 2);
+    let resolved: ResolvedFlagsResponse =
+        api.resolve_flags(flagged_twice).await.json().await.unwrap();
+    assert_eq!(resolved.content.resolved, 2);
+    let report: FeedbackReportResponse = api.get_feedback_report().await.json().await.unwrap();
+    assert_eq!(report.content.len(), 1);
+    assert_eq!(report.content[0].flashcard_id.to_string(), flagged_once);
+}
+
+#[tokio::test]
+async fn feedback_is_only_accepted_on_published_cards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let draft: FlashCardResponse = api
+        .create_flashcard(&new_card("What is borrowing?"))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Act
+    let response = api
+        .leave_feedback(
+            draft.content.id.to_string(),
+            &feedback(FeedbackKind::Unclear, None),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn long_comments_are_rejected() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = publish(&api, "What is ownership?").await;
+    let comment = "a".repeat(501);
+
+    // Act
+    let response = api
+        .leave_feedback(id, &feedback(FeedbackKind::Unclear, Some(&comment)))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = response.json().await.unwrap();
+    assert_eq!(problem.errors[0].rule, "feedback_comment_too_long");
+}
+
+#[tokio::test]
+async fn clients_sending_too_much_feedback_are_limited() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| config.feedback.limit = 2).await;
+    let id = publish(&api, "What is ownership?").await;
+
+    // Act
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        let response = api
+            .leave_feedback(id.clone(), &feedback(FeedbackKind::Upvote, None))
+            .await;
+        statuses.push(response.status());
+    }
+    let limited = api
+        .leave_feedback(id, &feedback(FeedbackKind::Unclear, None))
+        .await;
+
+    // Assert
+    assert_eq!(
+        statuses,
+        vec![
+            StatusCode::OK,
+            StatusCode::OK,
+            StatusCode::TOO_MANY_REQUESTS
+        ]
+    );
+    let problem: ProblemDetails = limited.json().await.unwrap();
+    assert_eq!(problem.code, "feedback_limit_reached");
+}
+
+#[tokio::test]
+async fn concurrent_feedback_does_not_exceed_the_limit() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| config.feedback.limit = 2).await;
+    let id = publish(&api, "What is ownership?").await;
+    let flag = feedback(FeedbackKind::Unclear, None);
+
+    // Act
+    let responses = tokio::join!(
+        api.leave_feedback(id.clone(), &flag),
+        api.leave_feedback(id.clone(), &flag),
+        api.leave_feedback(id.clone(), &flag),
+        api.leave_feedback(id.clone(), &flag),
+    );
+
+    // Assert
+    let statuses = [
+        responses.0.status(),
+        responses.1.status(),
+        responses.2.status(),
+        responses.3.status(),
+    ];
+    let accepted = statuses
+        .iter()
+        .filter(|status| **status == StatusCode::OK)
+        .count();
+    assert_eq!(accepted, 2);
+    let report: FeedbackReportResponse = api.get_feedback_report().await.json().await.unwrap();
+    assert_eq!(report.content[0].open_flags, 2);
+}
@@ -0,0 +1,87 @@
+// server/tests/integration/flashcard_stats.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard};
+use app::routes::flashcards::StatsResponse;
+use pavex::http::StatusCode;
+
+fn new_card(question: &str, topic: &str, tags: &[&str], difficulty: i32) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "an answer".to_string(),
+        topic: topic.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        difficulty,
+        kind: CardKind::FreeText,
+    }
+}
+
+#[tokio::test]
+async fn flashcard_stats_returns_200_and_grouped_counts() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", "memory", &["ownership"], 1))
+        .await;
+    api.create_published_flashcard(&new_card(
+        "What is a lifetime?",
+        "memory",
+        &["lifetimes", "ownership"],
+        4,
+    ))
+    .await;
+    api.create_published_flashcard(&new_card("What is async?", "async", &["futures"], 3))
+        .await;
+
+    // Act
+    let response = api.get_flashcard_stats().await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let StatsResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.total, 3);
+
+    let topics: Vec<(&str, i64)> = content
+        .topics
+        .iter()
+        .map(|topic| (topic.slug.as_str(), topic.card_count))
+        .collect();
+    assert_eq!(topics, vec![("memory", 2), ("async", 1)]);
+    assert_eq!(content.topics[0].average_difficulty, Some(2.5));
+
+    let tags: Vec<(&str, i64)> = content
+        .tags
+        .iter()
+        .map(|tag| (tag.slug.as_str(), tag.card_count))
+        .collect();
+    assert_eq!(
+        tags,
+        vec![("ownership", 2), ("futures", 1), ("lifetimes", 1)]
+    );
+
+    let difficulties: Vec<(i32, i64)> = content
+        .difficulties
+        .iter()
+        .map(|d| (d.difficulty, d.card_count))
+        .collect();
+    assert_eq!(difficulties, vec![(1, 1), (3, 1), (4, 1)]);
+
+    assert_eq!(content.weeks.len(), 1);
+    assert_eq!(content.weeks[0].card_count, 3);
+}
+
+#[tokio::test]
+async fn flashcard_stats_are_empty_without_cards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_flashcard_stats().await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let StatsResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.total, 0);
+    assert!(content.topics.is_empty());
+    assert!(content.weeks.is_empty());
+}
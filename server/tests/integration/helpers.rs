@@ -1,7 +1,12 @@
 // server/tests/api/helpers.rs
 
 // dependencies
-use app::{UpdatedFlashCard, models::NewFlashCard};
+use app::UpdatedFlashCard;
+use app::models::{
+    AnswerAttempt, DEFAULT_ORGANIZATION_ID, FlashCard, NewFeedback, NewFlashCard, NewOrganization,
+    NewStudyReview, NewTopic, NewUser, QuizSubmission, ReviewDecision, RustdocImport, TagMerge,
+    UpdatedTag, UpdatedTopic,
+};
 use pavex::{
     config::ConfigLoader,
     http::{HeaderMap, HeaderValue},
@@ -45,17 +50,47 @@ pub struct TestApi {
     pub api_address: String,
     pub api_client: reqwest::Client,
     pub api_db_pool: PgPool,
+    pub api_db_name: String,
     pub api_key: String,
 }
 
 impl TestApi {
     pub async fn spawn() -> Self {
+        Self::spawn_with_config(|_| {}).await
+    }
+
+    /// Spawn the API, after letting the caller tweak the test configuration.
+    pub async fn spawn_with_config(customize: impl FnOnce(&mut ApplicationConfig)) -> Self {
+        Self::spawn_inner(customize, true).await
+    }
+
+    /// Spawn the API on top of a fresh SQLite database file, no Postgres server required.
+    #[cfg(feature = "sqlite")]
+    pub async fn spawn_sqlite() -> Self {
+        Self::spawn_inner(
+            |config| {
+                config.database.backend = app::configuration::StorageBackend::Sqlite;
+                config.database.sqlite_path =
+                    std::env::temp_dir().join(format!("{}.db", Uuid::new_v4()));
+            },
+            false,
+        )
+        .await
+    }
+
+    async fn spawn_inner(
+        customize: impl FnOnce(&mut ApplicationConfig),
+        with_postgres: bool,
+    ) -> Self {
         Self::init_telemetry();
         let mut config = Self::get_config();
+        customize(&mut config);
         config.database.database_name = Uuid::new_v4().to_string();
         config.templateconfig.dir = Cow::Owned("../templates".to_string());
         config.staticserverconfig.root_dir = PathBuf::from("../static");
-        configure_database(&config).await;
+        if with_postgres {
+            configure_database(&config).await;
+        }
         let tcp_listener = config
             .server
             .listener()
@@ -67,7 +102,9 @@ impl TestApi {
         let server_builder = Server::new().listen(tcp_listener);
         let api_address = format!("http://{}:{}", config.server.ip, address.port());
         let api_client = reqwest::Client::new();
-        let api_db_pool = config.database.get_pool().await;
+        // unlike those of the API, the connections of the tests go by no application name
+        let api_db_pool = PgPool::connect_lazy_with(config.database.with_db());
+        let api_db_name = config.database.database_name.clone();
         let api_key = config.authorization.api_key.clone();
 
         let application_state = ApplicationState::new(config)
@@ -80,10 +117,42 @@ impl TestApi {
             api_address,
             api_client,
             api_db_pool,
+            api_db_name,
             api_key,
         }
     }
 
+    /// Insert a flash card, and its tags, straight into the database of the default
+    /// organization.
+    pub async fn insert_flashcard(&self, flash_card: &FlashCard) -> Uuid {
+        self.insert_organization_flashcard(DEFAULT_ORGANIZATION_ID, flash_card)
+            .await
+    }
+
+    /// Insert a flash card, and its tags, straight into the database of an organization.
+    pub async fn insert_organization_flashcard(
+        &self,
+        organization_id: Uuid,
+        flash_card: &FlashCard,
+    ) -> Uuid {
+        app::queries::create_flashcard(&self.api_db_pool, organization_id, flash_card)
+            .await
+            .expect("Failed to insert the flash card.")
+            .id
+    }
+
+    /// The same API, its admin requests made with another API key, e.g. the one of another
+    /// organization.
+    pub fn with_api_key(&self, api_key: &str) -> Self {
+        TestApi {
+            api_address: self.api_address.clone(),
+            api_client: self.api_client.clone(),
+            api_db_pool: self.api_db_pool.clone(),
+            api_db_name: self.api_db_name.clone(),
+            api_key: api_key.to_string(),
+        }
+    }
+
     /// Load the dev configuration and tweak it to ensure that tests are
     /// properly isolated from each other.
     fn get_config() -> ApplicationConfig {
@@ -180,6 +249,18 @@ impl TestApi {
             .expect("Failed to execute request.")
     }
 
+    pub async fn get_flashcards_by_topic_and_descendants(&self, topic: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards?topic={}&descendants=true",
+                &self.api_address, topic
+            ))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_flashcards_by_tag(&self, query: Option<&str>) -> reqwest::Response {
         let url = match query {
             Some(tag) => format!("{}/v1/flashcards?tag={}", &self.api_address, tag),
@@ -211,6 +292,24 @@ impl TestApi {
             .expect("Failed to execute request.")
     }
 
+    pub async fn get_flashcard_stats(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/flashcards/stats", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_topic_tree(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/topics/tree", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_flashcard(&self, id: String) -> reqwest::Response {
         self.api_client
             .get(format!("{}/v1/flashcards/{}", &self.api_address, id))
@@ -220,6 +319,40 @@ impl TestApi {
             .expect("Failed to execute request.")
     }
 
+    /// List the flash cards of the organization served at the given host, as forwarded by the
+    /// reverse proxy.
+    pub async fn get_flashcards_for_host(&self, host: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/flashcards", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .header("x-forwarded-host", host)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Send a GET request for `path`, with the API key, to `host` or, without one, to the
+    /// address the API listens at.
+    pub async fn get_path(&self, host: Option<&str>, path: &str) -> reqwest::Response {
+        let mut request = self
+            .api_client
+            .get(format!("{}{}", &self.api_address, path))
+            .bearer_auth(&self.api_key);
+        if let Some(host) = host {
+            request = request.header(HOST, host);
+        }
+        request.send().await.expect("Failed to execute request.")
+    }
+
+    pub async fn get_flashcard_page(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/flashcards/{}", &self.api_address, id))
+            .header(HOST, "app.rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_random_flashcard(&self) -> reqwest::Response {
         self.api_client
             .get(format!("{}/v1/flashcards/random", &self.api_address))
@@ -229,6 +362,59 @@ impl TestApi {
             .expect("Failed to execute request.")
     }
 
+    pub async fn get_random_flashcards(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards/random?{}",
+                &self.api_address, query
+            ))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_daily_flashcard(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards/daily?{}",
+                &self.api_address, query
+            ))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_quiz(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/quizzes?{}", &self.api_address, query))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn submit_quiz(&self, id: String, payload: &QuizSubmission) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/quizzes/{}/submit", &self.api_address, id))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn check_answer(&self, id: String, payload: &AnswerAttempt) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/flashcards/{}/check", &self.api_address, id))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn create_flashcard(&self, payload: &NewFlashCard) -> reqwest::Response {
         self.api_client
             .post(format!("{}/v1/flashcards", &self.api_address))
@@ -239,6 +425,349 @@ impl TestApi {
             .expect("Failed to execute request.")
     }
 
+    /// Create a flash card, and take it through the review workflow so that it is published.
+    pub async fn create_published_flashcard(&self, payload: &NewFlashCard) -> reqwest::Response {
+        let response = self.create_flashcard(payload).await;
+        if !response.status().is_success() {
+            return response;
+        }
+        let created: app::routes::flashcards::FlashCardResponse = response
+            .json()
+            .await
+            .expect("Failed to read the created flash card.");
+        let id = created.content.id.to_string();
+        self.submit_flashcard(id.clone()).await;
+        self.approve_flashcard(id, &ReviewDecision::default()).await
+    }
+
+    pub async fn submit_flashcard(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/flashcards/{}/submit", &self.api_address, id))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn approve_flashcard(
+        &self,
+        id: String,
+        decision: &ReviewDecision,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/approve",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .json(decision)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn reject_flashcard(
+        &self,
+        id: String,
+        decision: &ReviewDecision,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/flashcards/{}/reject", &self.api_address, id))
+            .headers(self.set_headers().await)
+            .json(decision)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn archive_flashcard(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/archive",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_reviews(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards/{}/reviews",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_review_queue(&self, status: Option<&str>) -> reqwest::Response {
+        let url = match status {
+            Some(status) => format!(
+                "{}/v1/review/flashcards?status={}",
+                &self.api_address, status
+            ),
+            None => format!("{}/v1/review/flashcards", &self.api_address),
+        };
+        self.api_client
+            .get(url)
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn register_user(&self, payload: &NewUser) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/users", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_current_user(&self, token: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/me", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn record_study_review(
+        &self,
+        token: &str,
+        payload: &NewStudyReview,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/me/reviews", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_progress(&self, token: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/me/progress", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn suggest_flashcard(
+        &self,
+        token: &str,
+        payload: &NewFlashCard,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/suggestions", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn suggest_edit(
+        &self,
+        token: &str,
+        id: String,
+        payload: &UpdatedFlashCard,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/suggestions",
+                &self.api_address, id
+            ))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_user_suggestions(&self, token: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/suggestions", &self.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .bearer_auth(token)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_moderation_queue(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/moderation/queue", &self.api_address))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn approve_suggestion(
+        &self,
+        id: String,
+        decision: &ReviewDecision,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/moderation/suggestions/{}/approve",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .json(decision)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn reject_suggestion(
+        &self,
+        id: String,
+        decision: &ReviewDecision,
+    ) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/moderation/suggestions/{}/reject",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .json(decision)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn leave_feedback(&self, id: String, payload: &NewFeedback) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/feedback",
+                &self.api_address, id
+            ))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_feedback_report(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/feedback/report", &self.api_address))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_flags(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards/{}/feedback",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn resolve_flags(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/feedback/resolve",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn verify_code(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/flashcards/{}/verify-code",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_code_verification(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/v1/flashcards/{}/verify-code",
+                &self.api_address, id
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn verify_all_code(&self) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/flashcards/verify-code", &self.api_address))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn import_rustdoc(&self, payload: &RustdocImport) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/drafts/import", &self.api_address))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_drafts(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/drafts", &self.api_address))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn accept_draft(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/drafts/{}/accept", &self.api_address, id))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_draft(&self, id: String) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/v1/drafts/{}", &self.api_address, id))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn delete_flashcard(&self, id: String) -> reqwest::Response {
         self.api_client
             .delete(format!("{}/v1/flashcards/{}", &self.api_address, id))
@@ -261,4 +790,75 @@ impl TestApi {
             .await
             .expect("Failed to execute request.")
     }
+
+    pub async fn update_tag(&self, payload: &UpdatedTag, slug: &str) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/v1/tags/{}", &self.api_address, slug))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn merge_tags(&self, payload: &TagMerge, slug: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/tags/{}/merge", &self.api_address, slug))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn create_topic(&self, payload: &NewTopic) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/topics", &self.api_address))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn update_topic(&self, payload: &UpdatedTopic, slug: &str) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/v1/topics/{}", &self.api_address, slug))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn create_organization(&self, payload: &NewOrganization) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/v1/organizations", &self.api_address))
+            .headers(self.set_headers().await)
+            .json(&payload)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_organizations(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/organizations", &self.api_address))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn create_api_key(&self, slug: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/organizations/{}/api-keys",
+                &self.api_address, slug
+            ))
+            .headers(self.set_headers().await)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
 }
@@ -2,15 +2,9 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::NewFlashCard;
+use app::models::{CardKind, NewFlashCard};
+use app::routes::flashcards::TagsResponse;
 use pavex::http::StatusCode;
-use serde::Deserialize;
-
-#[derive(Debug, Deserialize)]
-struct TagsResponse {
-    msg: String,
-    content: Vec<String>,
-}
 
 #[tokio::test]
 async fn list_all_tags_returns_200_ok() {
@@ -23,6 +17,7 @@ async fn list_all_tags_returns_200_ok() {
         topic: "memory".to_string(),
         tags: vec!["ownership".to_string()],
         difficulty: 2,
+        kind: CardKind::FreeText,
     };
 
     let card_2 = NewFlashCard {
@@ -31,6 +26,7 @@ async fn list_all_tags_returns_200_ok() {
         topic: "memory".to_string(), // ✅ Overlapping topic with card_1
         tags: vec!["borrowing".to_string()],
         difficulty: 3,
+        kind: CardKind::FreeText,
     };
 
     let card_3 = NewFlashCard {
@@ -39,11 +35,12 @@ async fn list_all_tags_returns_200_ok() {
         topic: "syntax".to_string(),
         tags: vec!["match".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
 
-    api.create_flashcard(&card_1).await;
-    api.create_flashcard(&card_2).await;
-    api.create_flashcard(&card_3).await;
+    api.create_published_flashcard(&card_1).await;
+    api.create_published_flashcard(&card_2).await;
+    api.create_published_flashcard(&card_3).await;
 
     // Act
     let response = api.get_all_tags().await;
@@ -56,10 +53,10 @@ async fn list_all_tags_returns_200_ok() {
 
     assert_eq!(msg, "success");
 
-    // Check expected tags are present
-    assert!(content.contains(&"ownership".to_string()));
-    assert!(content.contains(&"borrowing".to_string()));
-    assert!(content.contains(&"match".to_string()));
+    // Check expected tags are present, each carried by a single card
+    let slugs: Vec<&str> = content.iter().map(|tag| tag.slug.as_str()).collect();
+    assert_eq!(slugs, vec!["borrowing", "match", "ownership"]);
+    assert!(content.iter().all(|tag| tag.card_count == 1));
 
     // Check deduplication, exact count if known
     assert_eq!(content.len(), 3);
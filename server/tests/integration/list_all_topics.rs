@@ -2,7 +2,7 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::NewFlashCard;
+use app::models::{CardKind, NewFlashCard};
 use pavex::http::StatusCode;
 use serde::Deserialize;
 
@@ -23,6 +23,7 @@ async fn list_all_topics_returns_200_ok() {
         topic: "memory".to_string(),
         tags: vec!["ownership".to_string()],
         difficulty: 2,
+        kind: CardKind::FreeText,
     };
 
     let card_2 = NewFlashCard {
@@ -31,6 +32,7 @@ async fn list_all_topics_returns_200_ok() {
         topic: "memory".to_string(), // ✅ Overlapping topic with card_1
         tags: vec!["borrowing".to_string()],
         difficulty: 3,
+        kind: CardKind::FreeText,
     };
 
     let card_3 = NewFlashCard {
@@ -39,11 +41,12 @@ async fn list_all_topics_returns_200_ok() {
         topic: "syntax".to_string(),
         tags: vec!["match".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
 
-    api.create_flashcard(&card_1).await;
-    api.create_flashcard(&card_2).await;
-    api.create_flashcard(&card_3).await;
+    api.create_published_flashcard(&card_1).await;
+    api.create_published_flashcard(&card_2).await;
+    api.create_published_flashcard(&card_3).await;
 
     // Act
     let response = api.get_all_topics().await;
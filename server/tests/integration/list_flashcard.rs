@@ -1,5 +1,5 @@
 use crate::helpers::TestApi;
-use app::models::FlashCard;
+use app::models::{CardKind, CardStatus, FlashCard};
 use app::routes::flashcards::{FlashCardContent, FlashCardResponse};
 use jiff_sqlx::ToSqlx;
 use pavex::http::StatusCode;
@@ -17,22 +17,13 @@ async fn list_single_flashcard_returns_200_ok_and_flash_card() {
         topic: "test topic".to_string(),
         tags: vec!["tag1".to_string(), "tag2".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
         created_at: PavexTimestamp::now().to_sqlx(),
         updated_at: None,
     };
 
-    sqlx::query("INSERT INTO flashcards (id, question, answer, topic, tags, difficulty, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8);")
-        .bind(flash_card.id)
-        .bind(&flash_card.question)
-        .bind(&flash_card.answer)
-        .bind(&flash_card.topic)
-        .bind(&flash_card.tags)
-        .bind(flash_card.difficulty)
-        .bind(flash_card.created_at)
-        .bind(flash_card.updated_at)
-        .execute(&api.api_db_pool)
-        .await
-        .unwrap();
+    api.insert_flashcard(&flash_card).await;
 
     // Act
     let response = api.get_flashcard(flash_card.id.to_string()).await;
@@ -61,3 +52,85 @@ async fn list_flashcard_returns_400_for_invalid_id() {
     // Assert
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn list_flashcard_returns_404_for_non_existent_id() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let non_existent_id = Uuid::new_v4().to_string();
+
+    // Act
+    let response = api.get_flashcard(non_existent_id).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// a flash card whose answer is Markdown holding a Rust code block
+fn flashcard_with_code() -> FlashCard {
+    FlashCard {
+        id: Uuid::new_v4(),
+        question: "How do you print a value with *debug* formatting?".to_string(),
+        answer: "Use `{:?}`:\n\n```rust\nfn main() {\n    println!(\"{:?}\", vec![1, 2]);\n}\n```\n<script>alert(1)</script>".to_string(),
+        topic: "formatting".to_string(),
+        tags: vec!["macros".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
+        created_at: PavexTimestamp::now().to_sqlx(),
+        updated_at: None,
+    }
+}
+
+#[tokio::test]
+async fn list_flashcard_returns_the_answer_rendered_to_html() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let flash_card = flashcard_with_code();
+    api.insert_flashcard(&flash_card).await;
+
+    // Act
+    let response = api.get_flashcard(flash_card.id.to_string()).await;
+
+    // Assert
+    let body: FlashCardResponse = response.json().await.unwrap();
+    assert_eq!(body.content.answer_markdown, flash_card.answer);
+    assert!(body.content.question_html.contains("<em>debug</em>"));
+    assert!(
+        body.content
+            .answer_html
+            .contains("<pre class=\"code rust\">")
+    );
+    assert!(body.content.answer_html.contains("    "));
+    assert!(!body.content.answer_html.contains("<script>"));
+}
+
+#[tokio::test]
+async fn flashcard_page_renders_the_card_on_the_server() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let flash_card = flashcard_with_code();
+    api.insert_flashcard(&flash_card).await;
+
+    // Act
+    let response = api.get_flashcard_page(flash_card.id.to_string()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = response.text().await.unwrap();
+    assert!(page.contains("<em>debug</em>"));
+    assert!(page.contains("<pre class=\"code rust\">"));
+    assert!(!page.contains("<script>alert(1)</script>"));
+}
+
+#[tokio::test]
+async fn flashcard_page_returns_404_for_an_unknown_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_flashcard_page(Uuid::new_v4().to_string()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -1,5 +1,5 @@
 use crate::helpers::TestApi;
-use app::models::FlashCard;
+use app::models::{CardKind, CardStatus, FlashCard};
 use app::routes::flashcards::{FlashCardContent, FlashCardResponse};
 use jiff_sqlx::ToSqlx;
 use pavex::http::StatusCode;
@@ -18,6 +18,8 @@ async fn list_flashcards_returns_200_and_list_of_flash_cards() {
             topic: "test topic".to_string(),
             tags: vec!["tag1".to_string(), "tag2".to_string()],
             difficulty: 1,
+            kind: CardKind::FreeText,
+            status: CardStatus::Published,
             created_at: PavexTimestamp::now().to_sqlx(),
             updated_at: None,
         },
@@ -28,24 +30,15 @@ async fn list_flashcards_returns_200_and_list_of_flash_cards() {
             topic: "test topic 2".to_string(),
             tags: vec!["tag2".to_string(), "tag3".to_string()],
             difficulty: 1,
+            kind: CardKind::FreeText,
+            status: CardStatus::Published,
             created_at: PavexTimestamp::now().to_sqlx(),
             updated_at: None,
         },
     ];
 
     for item in flash_cards.iter() {
-        sqlx::query("INSERT INTO flashcards (id, question, answer, topic, tags, difficulty, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)")
-            .bind(item.id)
-            .bind(&item.question)
-            .bind(&item.answer)
-            .bind(&item.topic)
-            .bind(&item.tags)
-            .bind(item.difficulty)
-            .bind(item.created_at)
-            .bind(item.updated_at)
-            .execute(&api.api_db_pool)
-            .await
-            .unwrap();
+        api.insert_flashcard(item).await;
     }
 
     // Act
@@ -2,7 +2,7 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::NewFlashCard;
+use app::models::{CardKind, NewFlashCard};
 use pavex::http::StatusCode;
 
 #[tokio::test]
@@ -16,6 +16,7 @@ async fn list_flashcards_by_tag_returns_200_and_only_matching_cards() {
         topic: "memory".to_string(),
         tags: vec!["ownership".to_string()],
         difficulty: 2,
+        kind: CardKind::FreeText,
     };
 
     let non_matching_card = NewFlashCard {
@@ -24,10 +25,11 @@ async fn list_flashcards_by_tag_returns_200_and_only_matching_cards() {
         topic: "syntax".to_string(),
         tags: vec!["match".to_string()],
         difficulty: 2,
+        kind: CardKind::FreeText,
     };
 
-    api.create_flashcard(&matching_card).await;
-    api.create_flashcard(&non_matching_card).await;
+    api.create_published_flashcard(&matching_card).await;
+    api.create_published_flashcard(&non_matching_card).await;
 
     // Act
     let response = api
@@ -0,0 +1,93 @@
+// server/tests/integration/load.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::configuration::POOL_APPLICATION_NAME;
+use app::models::{CardKind, NewFlashCard};
+use pavex::http::StatusCode;
+use reqwest::header::HOST;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+// number of requests fired at the API at the same time
+const CONCURRENT_REQUESTS: usize = 200;
+
+// size of the connection pool used by the API under test
+const MAX_CONNECTIONS: u32 = 5;
+
+// the number of connections the API has open to its database, told apart from those of the test
+// by their application name
+async fn api_connections(api: &TestApi) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pg_stat_activity WHERE datname = $1 AND application_name = $2;",
+    )
+    .bind(&api.api_db_name)
+    .bind(POOL_APPLICATION_NAME)
+    .fetch_one(&api.api_db_pool)
+    .await
+    .unwrap()
+}
+
+// fire a wave of concurrent requests at the API, and collect their statuses along with the most
+// connections the API had open while serving them
+async fn fire_requests(api: &TestApi) -> (Vec<StatusCode>, i64) {
+    let mut requests = JoinSet::new();
+    for _ in 0..CONCURRENT_REQUESTS {
+        let client = api.api_client.clone();
+        let url = format!("{}/v1/flashcards/random", &api.api_address);
+        requests.spawn(async move {
+            client
+                .get(url)
+                .header(HOST, "rusty-flash-knowledge.net")
+                .send()
+                .await
+                .expect("Failed to execute request.")
+                .status()
+        });
+    }
+
+    let mut statuses = Vec::new();
+    let mut peak = 0;
+    while !requests.is_empty() {
+        peak = peak.max(api_connections(api).await);
+        while let Some(status) = requests.try_join_next() {
+            statuses.push(status.unwrap());
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    (statuses, peak)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_requests_share_a_bounded_connection_pool() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.database.max_connections = MAX_CONNECTIONS;
+        // every request comes from the same client, which the rate limiter would turn away
+        config.rate_limit.enabled = false;
+    })
+    .await;
+    let card = NewFlashCard {
+        question: "What is a connection pool?".to_string(),
+        answer: "A set of reusable database connections.".to_string(),
+        topic: "databases".to_string(),
+        tags: vec!["pooling".to_string()],
+        difficulty: 2,
+        kind: CardKind::FreeText,
+    };
+    api.create_published_flashcard(&card).await;
+
+    // Act
+    let (statuses, peak) = fire_requests(&api).await;
+
+    // Assert
+    assert_eq!(statuses.len(), CONCURRENT_REQUESTS);
+    assert!(statuses.iter().all(|status| *status == StatusCode::OK));
+
+    // With a pool per request, every concurrent request opened its own connection; the shared
+    // pool never goes beyond its configured size, however many requests wait for it.
+    assert!(
+        peak <= i64::from(MAX_CONNECTIONS),
+        "{peak} connections open for a pool of {MAX_CONNECTIONS}"
+    );
+}
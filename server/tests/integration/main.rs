@@ -1,5 +1,11 @@
+mod check_answer;
+mod cors;
 mod create_flashcard;
+mod daily_flashcard;
 mod delete_flashcard;
+mod drafts;
+mod feedback;
+mod flashcard_stats;
 mod health_check;
 mod helpers;
 mod list_all_tags;
@@ -8,6 +14,19 @@ mod list_flashcard;
 mod list_flashcards;
 mod list_flashcards_tag;
 mod list_flashcards_topic;
+mod load;
+mod manage_tags;
 mod middleware;
+mod organizations;
+mod progress;
+mod quizzes;
 mod random_flashcard;
+mod rate_limit;
+mod review_workflow;
+mod routing;
+#[cfg(feature = "sqlite")]
+mod sqlite_backend;
+mod suggestions;
+mod topic_tree;
 mod update_flashcard;
+mod verify_code;
@@ -0,0 +1,142 @@
+// server/tests/integration/manage_tags.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard, TagMerge, UpdatedTag};
+use app::routes::flashcards::{FlashCardResponse, TagsResponse};
+use app::routes::tags::TagResponse;
+use pavex::http::StatusCode;
+
+fn new_card(question: &str, tags: &[&str]) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "an answer".to_string(),
+        topic: "memory".to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+#[tokio::test]
+async fn tags_differing_only_by_case_or_spacing_are_the_same_tag() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", &["Memory Safety"]))
+        .await;
+    api.create_published_flashcard(&new_card("What is borrowing?", &["memory-safety"]))
+        .await;
+
+    // Act
+    let response = api.get_all_tags().await;
+
+    // Assert
+    let TagsResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.len(), 1);
+    assert_eq!(content[0].slug, "memory-safety");
+    assert_eq!(content[0].card_count, 2);
+}
+
+#[tokio::test]
+async fn update_tag_renames_it_on_every_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", &["owner ship"]))
+        .await;
+    api.create_published_flashcard(&new_card("What is borrowing?", &["owner ship"]))
+        .await;
+    let payload = UpdatedTag {
+        name: Some("Ownership".to_string()),
+        description: Some("Who is responsible for freeing a value".to_string()),
+    };
+
+    // Act
+    let response = api.update_tag(&payload, "owner-ship").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let TagResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.slug, "ownership");
+    assert_eq!(content.name, "Ownership");
+    assert_eq!(content.card_count, 2);
+
+    let response = api.get_flashcards_by_tag(Some("ownership")).await;
+    let cards: Vec<FlashCardResponse> = response.json().await.unwrap();
+    assert_eq!(cards.len(), 2);
+}
+
+#[tokio::test]
+async fn update_tag_returns_404_for_an_unknown_tag() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let payload = UpdatedTag {
+        name: Some("anything".to_string()),
+        description: None,
+    };
+
+    // Act
+    let response = api.update_tag(&payload, "does-not-exist").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn update_tag_returns_409_when_renaming_onto_an_existing_tag() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", &["ownership", "memory"]))
+        .await;
+    let payload = UpdatedTag {
+        name: Some("Memory".to_string()),
+        description: None,
+    };
+
+    // Act
+    let response = api.update_tag(&payload, "ownership").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn merge_tags_moves_every_card_onto_the_target() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", &["borrow", "borrowing"]))
+        .await;
+    api.create_published_flashcard(&new_card("What is borrowing?", &["borrow"]))
+        .await;
+    let payload = TagMerge {
+        into: "borrowing".to_string(),
+    };
+
+    // Act
+    let response = api.merge_tags(&payload, "borrow").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let TagResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.slug, "borrowing");
+    assert_eq!(content.card_count, 2);
+
+    let TagsResponse { content, .. } = api.get_all_tags().await.json().await.unwrap();
+    assert_eq!(content.len(), 1);
+}
+
+#[tokio::test]
+async fn merge_tags_returns_404_when_the_target_does_not_exist() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is ownership?", &["borrow"]))
+        .await;
+    let payload = TagMerge {
+        into: "nowhere".to_string(),
+    };
+
+    // Act
+    let response = api.merge_tags(&payload, "borrow").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
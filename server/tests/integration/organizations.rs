@@ -0,0 +1,264 @@
+use crate::helpers::TestApi;
+use app::models::{CardKind, CardStatus, FlashCard, NewFlashCard, NewOrganization};
+use app::routes::flashcards::FlashCardResponse;
+use app::routes::organizations::{OrganizationKeyResponse, OrganizationsResponse};
+use jiff_sqlx::ToSqlx;
+use pavex::http::StatusCode;
+use pavex::time::Timestamp as PavexTimestamp;
+use uuid::Uuid;
+
+fn new_organization(name: &str, hosts: &[&str]) -> NewOrganization {
+    NewOrganization {
+        name: name.to_string(),
+        hosts: hosts.iter().map(|host| host.to_string()).collect(),
+    }
+}
+
+fn new_card(question: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: "memory".to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+fn published_card(question: &str) -> FlashCard {
+    FlashCard {
+        id: Uuid::new_v4(),
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: "memory".to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
+        created_at: PavexTimestamp::now().to_sqlx(),
+        updated_at: None,
+    }
+}
+
+#[tokio::test]
+async fn organizations_are_created_with_an_api_key() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api
+        .create_organization(&new_organization(
+            "Platform Team",
+            &["Cards.Platform.example.com"],
+        ))
+        .await;
+    let listed: OrganizationsResponse = api.list_organizations().await.json().await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: OrganizationKeyResponse = response.json().await.unwrap();
+    assert_eq!(created.content.organization.slug, "platform-team");
+    assert_eq!(
+        created.content.organization.hosts,
+        vec!["cards.platform.example.com".to_string()]
+    );
+    assert_eq!(created.content.api_key.len(), 64);
+    let slugs: Vec<&str> = listed.content.iter().map(|o| o.slug.as_str()).collect();
+    assert_eq!(slugs, vec!["default", "platform-team"]);
+}
+
+#[tokio::test]
+async fn organization_slugs_and_hosts_are_unique() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_organization(&new_organization("Platform Team", &["cards.example.com"]))
+        .await;
+
+    // Act
+    let same_name = api
+        .create_organization(&new_organization("Platform team", &[]))
+        .await;
+    let same_host = api
+        .create_organization(&new_organization("Data Team", &["cards.example.com"]))
+        .await;
+
+    // Assert
+    assert_eq!(same_name.status(), StatusCode::CONFLICT);
+    assert_eq!(same_host.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn an_api_key_only_reaches_the_content_of_its_organization() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let created: OrganizationKeyResponse = api
+        .create_organization(&new_organization("Platform Team", &[]))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let team = api.with_api_key(&created.content.api_key);
+
+    // Act
+    let team_card = team
+        .create_published_flashcard(&new_card("What is ownership?"))
+        .await;
+    // the same question may be asked in every organization
+    let default_card = api
+        .create_published_flashcard(&new_card("What is ownership?"))
+        .await;
+    let team_queue: Vec<FlashCardResponse> = team
+        .get_review_queue(Some("published"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let public: Vec<FlashCardResponse> = api.get_flashcards().await.json().await.unwrap();
+
+    // Assert
+    assert_eq!(team_card.status(), StatusCode::OK);
+    assert_eq!(default_card.status(), StatusCode::OK);
+    let team_card: FlashCardResponse = team_card.json().await.unwrap();
+    let default_card: FlashCardResponse = default_card.json().await.unwrap();
+    assert_eq!(team_queue.len(), 1);
+    assert_eq!(team_queue[0].content.id, team_card.content.id);
+    assert_eq!(public.len(), 1);
+    assert_eq!(public[0].content.id, default_card.content.id);
+    assert_eq!(
+        api.delete_flashcard(team_card.content.id.to_string())
+            .await
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn the_forwarded_host_selects_the_organization() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.proxy.trusted_addresses = vec!["127.0.0.1".parse().unwrap()];
+    })
+    .await;
+    let created: OrganizationKeyResponse = api
+        .create_organization(&new_organization(
+            "Platform Team",
+            &["cards.platform.example.com"],
+        ))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = api
+        .insert_organization_flashcard(
+            created.content.organization.id,
+            &published_card("What is a lifetime?"),
+        )
+        .await;
+
+    // Act
+    let team: Vec<FlashCardResponse> = api
+        .get_flashcards_for_host("Cards.Platform.example.com:443")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let unknown_host: Vec<FlashCardResponse> = api
+        .get_flashcards_for_host("cards.elsewhere.example.com")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(team.len(), 1);
+    assert_eq!(team[0].content.id, id);
+    assert!(unknown_host.is_empty());
+}
+
+#[tokio::test]
+async fn the_host_forwarded_by_an_untrusted_peer_is_ignored() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let created: OrganizationKeyResponse = api
+        .create_organization(&new_organization(
+            "Platform Team",
+            &["cards.platform.example.com"],
+        ))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let team_id = api
+        .insert_organization_flashcard(
+            created.content.organization.id,
+            &published_card("What is a lifetime?"),
+        )
+        .await;
+
+    // Act
+    let served: Vec<FlashCardResponse> = api
+        .get_flashcards_for_host("cards.platform.example.com")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert!(served.iter().all(|card| card.content.id != team_id));
+}
+
+#[tokio::test]
+async fn another_api_key_reaches_the_same_organization() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let created: OrganizationKeyResponse = api
+        .create_organization(&new_organization("Platform Team", &[]))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let team = api.with_api_key(&created.content.api_key);
+    team.create_published_flashcard(&new_card("What is ownership?"))
+        .await;
+
+    // Act
+    let response = api.create_api_key("platform-team").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let rotated: OrganizationKeyResponse = response.json().await.unwrap();
+    assert_ne!(rotated.content.api_key, created.content.api_key);
+    let queue: Vec<FlashCardResponse> = api
+        .with_api_key(&rotated.content.api_key)
+        .get_review_queue(Some("published"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(queue.len(), 1);
+}
+
+#[tokio::test]
+async fn only_the_default_organization_manages_organizations() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let created: OrganizationKeyResponse = api
+        .create_organization(&new_organization("Platform Team", &[]))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let team = api.with_api_key(&created.content.api_key);
+
+    // Act
+    let listed = team.list_organizations().await;
+    let nested = team
+        .create_organization(&new_organization("Data Team", &[]))
+        .await;
+    let unknown = api.create_api_key("data-team").await;
+
+    // Assert
+    assert_eq!(listed.status(), StatusCode::FORBIDDEN);
+    assert_eq!(nested.status(), StatusCode::FORBIDDEN);
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+}
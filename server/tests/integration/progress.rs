@@ -0,0 +1,144 @@
+// server/tests/integration/progress.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::{CardKind, NewFlashCard, NewStudyReview, NewUser, RecallGrade};
+use app::progress::{CardCounts, HISTORY_DAYS, Streak};
+use app::routes::flashcards::FlashCardResponse;
+use app::routes::progress::ProgressResponse;
+use app::routes::users::RegisteredUserResponse;
+use pavex::http::StatusCode;
+use uuid::Uuid;
+
+fn new_card(question: &str, topic: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: topic.to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+// register a learner, returning their token
+async fn register(api: &TestApi) -> String {
+    let registered: RegisteredUserResponse = api
+        .register_user(&NewUser {
+            name: "ferris".to_string(),
+        })
+        .await
+        .json()
+        .await
+        .unwrap();
+    registered.content.token
+}
+
+// publish a card, returning its id
+async fn publish(api: &TestApi, question: &str, topic: &str) -> Uuid {
+    let created: FlashCardResponse = api
+        .create_published_flashcard(&new_card(question, topic))
+        .await
+        .json()
+        .await
+        .unwrap();
+    created.content.id
+}
+
+#[tokio::test]
+async fn progress_is_derived_from_the_reviews_of_the_user() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api).await;
+    let other_token = register(&api).await;
+    let studied = publish(&api, "What is ownership?", "memory").await;
+    publish(&api, "What is a trait?", "traits").await;
+
+    // Act
+    let recorded = api
+        .record_study_review(
+            &token,
+            &NewStudyReview {
+                flashcard_id: studied,
+                grade: RecallGrade::Good,
+            },
+        )
+        .await;
+    let progress: ProgressResponse = api.get_progress(&token).await.json().await.unwrap();
+    let other_progress: ProgressResponse =
+        api.get_progress(&other_token).await.json().await.unwrap();
+
+    // Assert
+    assert_eq!(recorded.status(), StatusCode::OK);
+    let progress = progress.content;
+    assert_eq!(
+        progress.cards,
+        CardCounts {
+            learned: 0,
+            learning: 1,
+            new: 1
+        }
+    );
+    assert_eq!(
+        progress.streak,
+        Streak {
+            current: 1,
+            longest: 1
+        }
+    );
+    assert_eq!(progress.daily_reviews.len(), HISTORY_DAYS as usize);
+    assert_eq!(progress.daily_reviews.last().unwrap().reviews, 1);
+    let topics: Vec<&str> = progress
+        .topics
+        .iter()
+        .map(|topic| topic.topic.as_str())
+        .collect();
+    assert_eq!(topics, vec!["memory", "traits"]);
+    // just reviewed, the card is still fully remembered
+    assert!(progress.predicted_retention.unwrap() > 99.0);
+
+    assert_eq!(other_progress.content.cards.new, 2);
+    assert_eq!(other_progress.content.predicted_retention, None);
+}
+
+#[tokio::test]
+async fn progress_requires_a_user_token() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_progress("not-a-token").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let problem: ProblemDetails = response.json().await.unwrap();
+    assert_eq!(problem.code, "invalid_user_token");
+}
+
+#[tokio::test]
+async fn reviews_of_unpublished_cards_are_refused() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api).await;
+    let draft: FlashCardResponse = api
+        .create_flashcard(&new_card("What is borrowing?", "memory"))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Act
+    let response = api
+        .record_study_review(
+            &token,
+            &NewStudyReview {
+                flashcard_id: draft.content.id,
+                grade: RecallGrade::Easy,
+            },
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,130 @@
+// server/tests/integration/quizzes.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard, QuizAnswer, QuizSubmission};
+use app::routes::quizzes::{QuizResponse, QuizResultResponse};
+use pavex::http::StatusCode;
+use uuid::Uuid;
+
+// seed a few flash cards of the same topic, with distinct answers
+async fn seed_flashcards(api: &TestApi) {
+    for (question, answer) in [
+        ("What is ownership?", "Each value has a single owner."),
+        ("What is borrowing?", "Taking a reference to a value."),
+        ("What is a lifetime?", "The scope a reference is valid for."),
+        ("What is a box?", "A pointer to a heap allocation."),
+    ] {
+        let card = NewFlashCard {
+            question: question.to_string(),
+            answer: answer.to_string(),
+            topic: "memory".to_string(),
+            tags: vec!["ownership".to_string()],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+        api.create_published_flashcard(&card).await;
+    }
+}
+
+#[tokio::test]
+async fn get_quiz_returns_multiple_choice_questions() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+
+    // Act
+    let response = api.get_quiz("topic=memory&size=2").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let quiz: QuizResponse = response.json().await.unwrap();
+    assert_eq!(quiz.content.questions.len(), 2);
+    assert!(quiz.content.questions.iter().all(|q| q.options.len() == 4));
+}
+
+#[tokio::test]
+async fn submit_quiz_returns_a_score_breakdown() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+    let quiz: QuizResponse = api.get_quiz("size=3").await.json().await.unwrap();
+    let submission = QuizSubmission {
+        answers: vec![QuizAnswer {
+            question: 0,
+            option: 0,
+        }],
+    };
+
+    // Act
+    let response = api
+        .submit_quiz(quiz.content.id.to_string(), &submission)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: QuizResultResponse = response.json().await.unwrap();
+    assert_eq!(result.content.total, 3);
+    assert_eq!(result.content.unanswered, 2);
+    assert_eq!(result.content.correct + result.content.incorrect, 1);
+}
+
+#[tokio::test]
+async fn submit_quiz_returns_422_for_an_unknown_option() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    seed_flashcards(&api).await;
+    let quiz: QuizResponse = api.get_quiz("size=1").await.json().await.unwrap();
+    let submission = QuizSubmission {
+        answers: vec![QuizAnswer {
+            question: 0,
+            option: 4,
+        }],
+    };
+
+    // Act
+    let response = api
+        .submit_quiz(quiz.content.id.to_string(), &submission)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn submit_quiz_returns_404_for_an_unknown_quiz() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api
+        .submit_quiz(Uuid::new_v4().to_string(), &QuizSubmission::default())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_quiz_returns_404_without_enough_flashcards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_quiz("").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_quiz_returns_422_for_an_invalid_size() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api.get_quiz("size=0").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -2,7 +2,8 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::NewFlashCard;
+use app::models::{CardKind, NewFlashCard};
+use app::routes::flashcards::FlashCardResponse;
 use pavex::http::StatusCode;
 use reqwest::header::HOST;
 
@@ -17,8 +18,9 @@ async fn get_random_flashcard_returns_200() {
         topic: "random".to_string(),
         tags: vec!["rand".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
     };
-    api.create_flashcard(&card).await;
+    api.create_published_flashcard(&card).await;
 
     let response = api.get_random_flashcard().await;
 
@@ -44,3 +46,85 @@ async fn get_random_flashcard_returns_404_when_no_flashcards_exist() {
     // Assert
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn get_random_flashcards_returns_distinct_matching_cards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    for (question, topic, difficulty) in [
+        ("What is ownership?", "memory", 2),
+        ("What is borrowing?", "memory", 3),
+        ("What is a lifetime?", "memory", 5),
+        ("What is a trait?", "traits", 2),
+    ] {
+        let card = NewFlashCard {
+            question: question.to_string(),
+            answer: "An answer.".to_string(),
+            topic: topic.to_string(),
+            tags: vec!["rust".to_string()],
+            difficulty,
+            kind: CardKind::FreeText,
+        };
+        api.create_published_flashcard(&card).await;
+    }
+
+    // Act
+    let response = api
+        .get_random_flashcards("topic=memory&tags=rust&max_difficulty=3&count=10&weighting=harder")
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let cards: Vec<FlashCardResponse> = response.json().await.unwrap();
+    let mut questions: Vec<String> = cards.into_iter().map(|c| c.content.question).collect();
+    questions.sort();
+    assert_eq!(questions, vec!["What is borrowing?", "What is ownership?"]);
+}
+
+#[tokio::test]
+async fn get_random_flashcards_skips_excluded_cards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let mut ids = Vec::new();
+    for question in ["Seen question?", "Unseen question?"] {
+        let card = NewFlashCard {
+            question: question.to_string(),
+            answer: "An answer.".to_string(),
+            topic: "random".to_string(),
+            tags: vec!["rand".to_string()],
+            difficulty: 1,
+            kind: CardKind::FreeText,
+        };
+        let created: FlashCardResponse = api
+            .create_published_flashcard(&card)
+            .await
+            .json()
+            .await
+            .unwrap();
+        ids.push(created.content.id);
+    }
+
+    // Act
+    let response = api
+        .get_random_flashcards(&format!("exclude={}", ids[0]))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let card: FlashCardResponse = response.json().await.unwrap();
+    assert_eq!(card.content.id, ids[1]);
+}
+
+#[tokio::test]
+async fn get_random_flashcards_returns_422_for_an_inverted_difficulty_range() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api
+        .get_random_flashcards("min_difficulty=4&max_difficulty=2")
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,170 @@
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::NewUser;
+use app::routes::users::RegisteredUserResponse;
+use pavex::http::StatusCode;
+use reqwest::header::{HOST, RETRY_AFTER};
+
+// limits low enough to be reached by a test, and refilled slowly enough not to recover during one
+async fn spawn_rate_limited() -> TestApi {
+    TestApi::spawn_with_config(|config| {
+        config.rate_limit.public.requests = 2;
+        config.rate_limit.public.per = std::time::Duration::from_secs(60 * 60);
+        config.rate_limit.admin.requests = 3;
+        config.rate_limit.admin.per = std::time::Duration::from_secs(60 * 60);
+    })
+    .await
+}
+
+fn header<'a>(response: &'a reqwest::Response, name: &str) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn public_requests_beyond_the_limit_are_refused() {
+    // Arrange
+    let api = spawn_rate_limited().await;
+
+    // Act
+    let first = api.get_flashcards().await;
+    let second = api.get_flashcards().await;
+    let refused = api.get_random_flashcard().await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(header(&first, "ratelimit-limit"), Some("2"));
+    assert_eq!(header(&first, "ratelimit-remaining"), Some("1"));
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(header(&second, "ratelimit-remaining"), Some("0"));
+    assert_eq!(header(&second, "ratelimit-reset"), Some("3600"));
+    assert_eq!(refused.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header(&refused, "ratelimit-remaining"), Some("0"));
+    let retry_after: u64 = header(&refused, RETRY_AFTER.as_str())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 1800);
+    let problem: ProblemDetails = refused.json().await.unwrap();
+    assert_eq!(problem.code, "rate_limited");
+}
+
+#[tokio::test]
+async fn api_keys_and_users_have_their_own_limits() {
+    // Arrange
+    let api = spawn_rate_limited().await;
+    let registered: RegisteredUserResponse = api
+        .register_user(&NewUser {
+            name: "ferris".to_string(),
+        })
+        .await
+        .json()
+        .await
+        .unwrap();
+    // the registration and the first request of the user, with a token not seen before, empty
+    // the bucket of the IP address
+    let first = api.get_current_user(&registered.content.token).await;
+
+    // Act
+    let anonymous = api.get_flashcards().await;
+    let user = api.get_current_user(&registered.content.token).await;
+    let api_key = api
+        .get_path(Some("rusty-flash-knowledge.net"), "/v1/flashcards")
+        .await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(header(&first, "ratelimit-remaining"), Some("1"));
+    assert_eq!(anonymous.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(user.status(), StatusCode::OK);
+    assert_eq!(header(&user, "ratelimit-remaining"), Some("0"));
+    assert_eq!(api_key.status(), StatusCode::OK);
+    assert_eq!(header(&api_key, "ratelimit-remaining"), Some("1"));
+}
+
+#[tokio::test]
+async fn unknown_tokens_do_not_get_past_the_limit_of_the_ip_address() {
+    // Arrange
+    let api = spawn_rate_limited().await;
+    api.get_flashcards().await;
+    api.get_flashcards().await;
+
+    // Act
+    let random_token = api.get_current_user("not-a-token").await;
+
+    // Assert
+    assert_eq!(random_token.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn clients_behind_a_trusted_proxy_have_their_own_limits() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.rate_limit.public.requests = 1;
+        config.rate_limit.public.per = std::time::Duration::from_secs(60 * 60);
+        config.proxy.trusted_addresses = vec!["127.0.0.1".parse().unwrap()];
+    })
+    .await;
+    let get_flashcards_for = |client: &'static str| {
+        api.api_client
+            .get(format!("{}/v1/flashcards", api.api_address))
+            .header(HOST, "rusty-flash-knowledge.net")
+            .header("x-forwarded-for", client)
+            .send()
+    };
+
+    // Act
+    let first = get_flashcards_for("203.0.113.7").await.unwrap();
+    let second = get_flashcards_for("198.51.100.1").await.unwrap();
+    let refused = get_flashcards_for("203.0.113.7").await.unwrap();
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(refused.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn the_admin_api_has_its_own_limit() {
+    // Arrange
+    let api = spawn_rate_limited().await;
+    api.get_flashcards().await;
+    api.get_flashcards().await;
+
+    // Act
+    let mut statuses = Vec::new();
+    for _ in 0..4 {
+        statuses.push(api.get_review_queue(None).await.status());
+    }
+
+    // Assert
+    assert_eq!(
+        statuses,
+        vec![
+            StatusCode::OK,
+            StatusCode::OK,
+            StatusCode::OK,
+            StatusCode::TOO_MANY_REQUESTS
+        ]
+    );
+}
+
+#[tokio::test]
+async fn disabled_rate_limiting_adds_no_headers() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.rate_limit.enabled = false;
+        config.rate_limit.public.requests = 1;
+    })
+    .await;
+
+    // Act
+    api.get_flashcards().await;
+    let response = api.get_flashcards().await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header(&response, "ratelimit-limit"), None);
+}
@@ -0,0 +1,159 @@
+// server/tests/integration/review_workflow.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::errors::ProblemDetails;
+use app::models::{CardKind, CardStatus, NewFlashCard, ReviewDecision};
+use app::routes::flashcards::FlashCardResponse;
+use app::routes::reviews::CardReviewsResponse;
+use pavex::http::StatusCode;
+
+fn new_card(question: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: "memory".to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+fn note(note: &str) -> ReviewDecision {
+    ReviewDecision {
+        note: Some(note.to_string()),
+    }
+}
+
+// create a card, returning its id
+async fn create_draft(api: &TestApi, question: &str) -> String {
+    let created: FlashCardResponse = api
+        .create_flashcard(&new_card(question))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(created.content.status, CardStatus::Draft);
+    created.content.id.to_string()
+}
+
+#[tokio::test]
+async fn new_cards_stay_off_the_public_api_until_approved() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_draft(&api, "What is ownership?").await;
+    assert_eq!(
+        api.get_flashcard(id.clone()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Act
+    let submitted = api.submit_flashcard(id.clone()).await;
+    let queue: Vec<FlashCardResponse> = api.get_review_queue(None).await.json().await.unwrap();
+    let approved = api
+        .approve_flashcard(id.clone(), &note("Clear and correct."))
+        .await;
+
+    // Assert
+    assert_eq!(submitted.status(), StatusCode::OK);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(approved.status(), StatusCode::OK);
+    let approved: FlashCardResponse = approved.json().await.unwrap();
+    assert_eq!(approved.content.status, CardStatus::Published);
+
+    assert_eq!(api.get_flashcard(id.clone()).await.status(), StatusCode::OK);
+    let deck: Vec<FlashCardResponse> = api.get_flashcards().await.json().await.unwrap();
+    assert_eq!(deck.len(), 1);
+
+    let reviews: CardReviewsResponse = api.list_reviews(id).await.json().await.unwrap();
+    let statuses: Vec<(CardStatus, CardStatus)> = reviews
+        .content
+        .iter()
+        .map(|review| (review.from_status, review.to_status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (CardStatus::Draft, CardStatus::InReview),
+            (CardStatus::InReview, CardStatus::Published)
+        ]
+    );
+    assert_eq!(
+        reviews.content[1].note.as_deref(),
+        Some("Clear and correct.")
+    );
+}
+
+#[tokio::test]
+async fn rejecting_a_card_requires_a_note_and_sends_it_back_to_draft() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_draft(&api, "What is borrowing?").await;
+    api.submit_flashcard(id.clone()).await;
+
+    // Act
+    let without_note = api
+        .reject_flashcard(id.clone(), &ReviewDecision::default())
+        .await;
+    let rejected = api
+        .reject_flashcard(id.clone(), &note("The answer is too vague."))
+        .await;
+
+    // Assert
+    assert_eq!(without_note.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = without_note.json().await.unwrap();
+    assert_eq!(problem.errors[0].rule, "missing_review_note");
+
+    assert_eq!(rejected.status(), StatusCode::OK);
+    let rejected: FlashCardResponse = rejected.json().await.unwrap();
+    assert_eq!(rejected.content.status, CardStatus::Draft);
+    assert_eq!(api.get_flashcard(id).await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn cards_cannot_skip_a_step_of_the_workflow() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_draft(&api, "What is a lifetime?").await;
+
+    // Act
+    let response = api
+        .approve_flashcard(id.clone(), &ReviewDecision::default())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let problem: ProblemDetails = response.json().await.unwrap();
+    assert_eq!(problem.code, "invalid_status_transition");
+    assert_eq!(
+        api.archive_flashcard(id).await.status(),
+        StatusCode::CONFLICT
+    );
+}
+
+#[tokio::test]
+async fn archived_cards_leave_the_public_api() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let published: FlashCardResponse = api
+        .create_published_flashcard(&new_card("What is a box?"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = published.content.id.to_string();
+
+    // Act
+    let response = api.archive_flashcard(id.clone()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(api.get_flashcard(id).await.status(), StatusCode::NOT_FOUND);
+    let archived: Vec<FlashCardResponse> = api
+        .get_review_queue(Some("archived"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(archived.len(), 1);
+}
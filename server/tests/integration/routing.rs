@@ -0,0 +1,109 @@
+use crate::helpers::TestApi;
+use app::configuration::RoutingMode;
+use pavex::http::StatusCode;
+
+async fn spawn_single_host() -> TestApi {
+    TestApi::spawn_with_config(|config| {
+        config.routing.mode = RoutingMode::SingleHost;
+        config.routing.public.prefix = "api".to_string();
+        config.routing.admin.prefix = "admin".to_string();
+    })
+    .await
+}
+
+#[tokio::test]
+async fn domains_serve_each_api_at_its_configured_host() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.routing.public.host = "cards.example.com".to_string();
+        config.routing.admin.host = "admin.cards.example.com".to_string();
+        config.routing.web.host = "www.cards.example.com".to_string();
+    })
+    .await;
+
+    // Act
+    let public = api
+        .get_path(Some("Cards.Example.com:8000"), "/v1/flashcards")
+        .await;
+    let admin = api
+        .get_path(Some("admin.cards.example.com"), "/v1/review/flashcards")
+        .await;
+    let web = api.get_path(Some("www.cards.example.com"), "/").await;
+    let former_host = api
+        .get_path(Some("rusty-flash-knowledge.net"), "/v1/flashcards")
+        .await;
+    let admin_at_public_host = api
+        .get_path(Some("cards.example.com"), "/v1/review/flashcards")
+        .await;
+
+    // Assert
+    assert_eq!(public.status(), StatusCode::OK);
+    assert_eq!(admin.status(), StatusCode::OK);
+    assert_eq!(web.status(), StatusCode::OK);
+    assert_eq!(former_host.status(), StatusCode::NOT_FOUND);
+    assert_eq!(admin_at_public_host.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn domains_serve_each_api_under_its_configured_prefix() {
+    // Arrange
+    let api = TestApi::spawn_with_config(|config| {
+        config.routing.public.prefix = "v2".to_string();
+    })
+    .await;
+
+    // Act
+    let configured = api
+        .get_path(Some("rusty-flash-knowledge.net"), "/v2/flashcards")
+        .await;
+    let default = api
+        .get_path(Some("rusty-flash-knowledge.net"), "/v1/flashcards")
+        .await;
+    let admin = api
+        .get_path(
+            Some("api.rusty-flash-knowledge.net"),
+            "/v1/review/flashcards",
+        )
+        .await;
+
+    // Assert
+    assert_eq!(configured.status(), StatusCode::OK);
+    assert_eq!(default.status(), StatusCode::NOT_FOUND);
+    assert_eq!(admin.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn a_single_host_serves_everything_under_path_prefixes() {
+    // Arrange
+    let api = spawn_single_host().await;
+
+    // Act
+    let public = api.get_path(None, "/api/flashcards").await;
+    let admin = api.get_path(None, "/admin/review/flashcards").await;
+    let web = api.get_path(None, "/").await;
+    let other_host = api
+        .get_path(Some("cards.example.com"), "/api/flashcards")
+        .await;
+
+    // Assert
+    assert_eq!(public.status(), StatusCode::OK);
+    assert_eq!(admin.status(), StatusCode::OK);
+    assert_eq!(web.status(), StatusCode::OK);
+    assert_eq!(other_host.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn a_single_host_keeps_each_api_under_its_own_prefix() {
+    // Arrange
+    let api = spawn_single_host().await;
+
+    // Act
+    let public_under_admin = api.get_path(None, "/admin/flashcards").await;
+    let admin_under_public = api.get_path(None, "/api/review/flashcards").await;
+    let unknown_prefix = api.get_path(None, "/v1/flashcards").await;
+
+    // Assert
+    assert_eq!(public_under_admin.status(), StatusCode::NOT_FOUND);
+    assert_eq!(admin_under_public.status(), StatusCode::NOT_FOUND);
+    assert_eq!(unknown_prefix.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,96 @@
+// server/tests/integration/sqlite_backend.rs
+
+// these tests run against the SQLite storage backend and don't need a Postgres server;
+// run them with `cargo test --features sqlite`
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard, UpdatedFlashCard};
+use app::routes::flashcards::FlashCardResponse;
+use pavex::http::StatusCode;
+
+fn new_card(question: &str, topic: &str, tags: &[&str]) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "an answer".to_string(),
+        topic: topic.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+#[tokio::test]
+async fn sqlite_backend_supports_the_flashcard_lifecycle() {
+    // Arrange
+    let api = TestApi::spawn_sqlite().await;
+
+    // Act
+    let created = api
+        .create_published_flashcard(&new_card("What is Rust?", "intro", &["basics", "language"]))
+        .await;
+    assert_eq!(created.status(), StatusCode::OK);
+    let id = created
+        .json::<FlashCardResponse>()
+        .await
+        .unwrap()
+        .content
+        .id
+        .to_string();
+
+    let update = UpdatedFlashCard {
+        answer: Some("A systems programming language.".to_string()),
+        ..Default::default()
+    };
+    let updated = api.update_flashcard(&update, id.clone()).await;
+    assert_eq!(updated.status(), StatusCode::OK);
+
+    let fetched = api.get_flashcard(id.clone()).await;
+    assert_eq!(fetched.status(), StatusCode::OK);
+    let body = fetched.json::<FlashCardResponse>().await.unwrap();
+    assert_eq!(body.content.question, "What is Rust?");
+    assert_eq!(body.content.answer, "A systems programming language.");
+
+    let deleted = api.delete_flashcard(id.clone()).await;
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+
+    // Assert
+    let missing = api.get_flashcard(id).await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn sqlite_backend_rejects_duplicate_questions() {
+    // Arrange
+    let api = TestApi::spawn_sqlite().await;
+    let card = new_card("What is Rust?", "intro", &["basics"]);
+    api.create_flashcard(&card).await;
+
+    // Act
+    let response = api.create_flashcard(&card).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn sqlite_backend_filters_by_tag_and_lists_tags() {
+    // Arrange
+    let api = TestApi::spawn_sqlite().await;
+    api.create_published_flashcard(&new_card("What is ownership?", "memory", &["ownership"]))
+        .await;
+    api.create_published_flashcard(&new_card("What is borrowing?", "memory", &["borrowing"]))
+        .await;
+
+    // Act
+    let by_tag = api.get_flashcards_by_tag(Some("borrowing")).await;
+    let tags = api.get_all_tags().await;
+
+    // Assert
+    let cards = by_tag.json::<Vec<FlashCardResponse>>().await.unwrap();
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0].content.question, "What is borrowing?");
+
+    let tags = tags.text().await.unwrap();
+    assert!(tags.contains("borrowing") && tags.contains("ownership"));
+}
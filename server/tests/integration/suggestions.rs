@@ -0,0 +1,250 @@
+// server/tests/integration/suggestions.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::UpdatedFlashCard;
+use app::errors::ProblemDetails;
+use app::models::{CardKind, CardStatus, NewFlashCard, NewUser, ReviewDecision, SuggestionStatus};
+use app::routes::flashcards::FlashCardResponse;
+use app::routes::suggestions::{
+    ApprovedSuggestionResponse, SuggestionResponse, SuggestionsResponse,
+};
+use app::routes::users::{RegisteredUserResponse, UserResponse};
+use pavex::http::StatusCode;
+
+fn new_card(question: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "A single owner per value.".to_string(),
+        topic: "memory".to_string(),
+        tags: vec!["ownership".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+fn note(note: &str) -> ReviewDecision {
+    ReviewDecision {
+        note: Some(note.to_string()),
+    }
+}
+
+// register a learner, returning their token
+async fn register(api: &TestApi, name: &str) -> String {
+    let registered: RegisteredUserResponse = api
+        .register_user(&NewUser {
+            name: name.to_string(),
+        })
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(registered.content.user.name, name);
+    registered.content.token
+}
+
+// publish a card, returning its id
+async fn publish(api: &TestApi, question: &str) -> String {
+    let created: FlashCardResponse = api
+        .create_published_flashcard(&new_card(question))
+        .await
+        .json()
+        .await
+        .unwrap();
+    created.content.id.to_string()
+}
+
+#[tokio::test]
+async fn registered_users_are_recognised_by_their_token() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+
+    // Act
+    let known = api.get_current_user(&token).await;
+    let unknown = api.get_current_user("not-a-token").await;
+
+    // Assert
+    assert_eq!(known.status(), StatusCode::OK);
+    let known: UserResponse = known.json().await.unwrap();
+    assert_eq!(known.content.name, "ferris");
+
+    assert_eq!(unknown.status(), StatusCode::UNAUTHORIZED);
+    let problem: ProblemDetails = unknown.json().await.unwrap();
+    assert_eq!(problem.code, "invalid_user_token");
+}
+
+#[tokio::test]
+async fn suggestions_require_a_user_token() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api
+        .suggest_flashcard("not-a-token", &new_card("What is ownership?"))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let queue: SuggestionsResponse = api.get_moderation_queue().await.json().await.unwrap();
+    assert!(queue.content.is_empty());
+}
+
+#[tokio::test]
+async fn approving_an_edit_applies_it_to_the_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+    let id = publish(&api, "What is ownership?").await;
+    let edit = UpdatedFlashCard {
+        answer: Some("Every value has exactly one owner.".to_string()),
+        ..UpdatedFlashCard::default()
+    };
+
+    // Act
+    let suggested: SuggestionResponse = api
+        .suggest_edit(&token, id.clone(), &edit)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let queue: SuggestionsResponse = api.get_moderation_queue().await.json().await.unwrap();
+    let approved = api
+        .approve_suggestion(suggested.content.id.to_string(), &ReviewDecision::default())
+        .await;
+
+    // Assert
+    assert_eq!(queue.content.len(), 1);
+    let diff = &queue.content[0].diff;
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].field, "answer");
+    assert_eq!(diff[0].current, "A single owner per value.");
+    assert_eq!(diff[0].proposed, "Every value has exactly one owner.");
+
+    assert_eq!(approved.status(), StatusCode::OK);
+    let approved: ApprovedSuggestionResponse = approved.json().await.unwrap();
+    assert_eq!(
+        approved.content.suggestion.status,
+        SuggestionStatus::Approved
+    );
+
+    let card: FlashCardResponse = api.get_flashcard(id).await.json().await.unwrap();
+    assert_eq!(card.content.answer, "Every value has exactly one owner.");
+    let queue: SuggestionsResponse = api.get_moderation_queue().await.json().await.unwrap();
+    assert!(queue.content.is_empty());
+    let mine: SuggestionsResponse = api
+        .list_user_suggestions(&token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(mine.content[0].status, SuggestionStatus::Approved);
+}
+
+#[tokio::test]
+async fn approving_a_new_card_creates_a_draft() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+    let suggested: SuggestionResponse = api
+        .suggest_flashcard(&token, &new_card("What is borrowing?"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(suggested.content.flashcard_id, None);
+
+    // Act
+    let approved: ApprovedSuggestionResponse = api
+        .approve_suggestion(suggested.content.id.to_string(), &ReviewDecision::default())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(approved.content.flashcard.question, "What is borrowing?");
+    assert_eq!(approved.content.flashcard.status, CardStatus::Draft);
+}
+
+#[tokio::test]
+async fn concurrent_approvals_apply_a_suggestion_once() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+    let suggested: SuggestionResponse = api
+        .suggest_flashcard(&token, &new_card("What is a lifetime?"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = suggested.content.id.to_string();
+
+    // Act
+    let (first, second) = tokio::join!(
+        api.approve_suggestion(id.clone(), &ReviewDecision::default()),
+        api.approve_suggestion(id, &ReviewDecision::default()),
+    );
+
+    // Assert
+    let mut statuses = [first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::CONFLICT]);
+}
+
+#[tokio::test]
+async fn rejected_suggestions_are_closed() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+    let suggested: SuggestionResponse = api
+        .suggest_flashcard(&token, &new_card("What is a lifetime?"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = suggested.content.id.to_string();
+
+    // Act
+    let without_note = api
+        .reject_suggestion(id.clone(), &ReviewDecision::default())
+        .await;
+    let rejected = api
+        .reject_suggestion(id.clone(), &note("Already covered by another card."))
+        .await;
+    let approved = api.approve_suggestion(id, &ReviewDecision::default()).await;
+
+    // Assert
+    assert_eq!(without_note.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(rejected.status(), StatusCode::OK);
+    let rejected: SuggestionResponse = rejected.json().await.unwrap();
+    assert_eq!(rejected.content.status, SuggestionStatus::Rejected);
+    assert_eq!(
+        rejected.content.note.as_deref(),
+        Some("Already covered by another card.")
+    );
+
+    assert_eq!(approved.status(), StatusCode::CONFLICT);
+    let problem: ProblemDetails = approved.json().await.unwrap();
+    assert_eq!(problem.code, "suggestion_closed");
+}
+
+#[tokio::test]
+async fn edits_which_change_nothing_are_refused() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let token = register(&api, "ferris").await;
+    let id = publish(&api, "What is ownership?").await;
+    let edit = UpdatedFlashCard {
+        answer: Some("A single owner per value.".to_string()),
+        ..UpdatedFlashCard::default()
+    };
+
+    // Act
+    let response = api.suggest_edit(&token, id, &edit).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = response.json().await.unwrap();
+    assert_eq!(problem.errors[0].rule, "empty_suggestion");
+}
@@ -0,0 +1,186 @@
+// server/tests/integration/topic_tree.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, NewFlashCard, NewTopic, UpdatedTopic};
+use app::routes::flashcards::FlashCardResponse;
+use app::routes::topics::{TopicResponse, TopicTreeResponse};
+use pavex::http::StatusCode;
+
+fn new_card(question: &str, topic: &str) -> NewFlashCard {
+    NewFlashCard {
+        question: question.to_string(),
+        answer: "an answer".to_string(),
+        topic: topic.to_string(),
+        tags: vec!["basics".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    }
+}
+
+fn new_topic(name: &str, parent: Option<&str>) -> NewTopic {
+    NewTopic {
+        name: name.to_string(),
+        description: None,
+        parent: parent.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn topic_tree_nests_subtopics_under_their_parent() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+    api.create_topic(&new_topic("Borrowing", Some("ownership")))
+        .await;
+    api.create_topic(&new_topic("Lifetimes", Some("borrowing")))
+        .await;
+    api.create_published_flashcard(&new_card("What is a lifetime?", "Lifetimes"))
+        .await;
+    api.create_published_flashcard(&new_card("What is a trait?", "Traits"))
+        .await;
+
+    // Act
+    let response = api.get_topic_tree().await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let TopicTreeResponse { content, .. } = response.json().await.unwrap();
+    let roots: Vec<&str> = content.iter().map(|node| node.slug.as_str()).collect();
+    assert_eq!(roots, vec!["ownership", "traits"]);
+    let ownership = &content[0];
+    assert_eq!(ownership.card_count, 0);
+    assert_eq!(ownership.total_card_count, 1);
+    assert_eq!(ownership.children[0].slug, "borrowing");
+    assert_eq!(ownership.children[0].children[0].slug, "lifetimes");
+}
+
+#[tokio::test]
+async fn topic_filter_can_include_descendants() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+    api.create_topic(&new_topic("Borrowing", Some("ownership")))
+        .await;
+    api.create_published_flashcard(&new_card("What is ownership?", "Ownership"))
+        .await;
+    api.create_published_flashcard(&new_card("What is borrowing?", "Borrowing"))
+        .await;
+
+    // Act
+    let direct: Vec<FlashCardResponse> = api
+        .get_flashcards_by_topic(Some("ownership"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let nested: Vec<FlashCardResponse> = api
+        .get_flashcards_by_topic_and_descendants("ownership")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(direct.len(), 1);
+    assert_eq!(nested.len(), 2);
+}
+
+#[tokio::test]
+async fn update_topic_moves_it_under_a_new_parent() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_published_flashcard(&new_card("What is borrowing?", "Borrowing"))
+        .await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+    let payload = UpdatedTopic {
+        description: Some("Shared and mutable references".to_string()),
+        parent: Some(Some("ownership".to_string())),
+        ..Default::default()
+    };
+
+    // Act
+    let response = api.update_topic(&payload, "borrowing").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let TopicResponse { content, .. } = response.json().await.unwrap();
+    assert_eq!(content.parent.as_deref(), Some("ownership"));
+    assert_eq!(content.card_count, 1);
+}
+
+#[tokio::test]
+async fn update_topic_returns_422_when_moving_a_topic_under_its_descendant() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+    api.create_topic(&new_topic("Borrowing", Some("ownership")))
+        .await;
+    let payload = UpdatedTopic {
+        parent: Some(Some("borrowing".to_string())),
+        ..Default::default()
+    };
+
+    // Act
+    let response = api.update_topic(&payload, "ownership").await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn concurrent_moves_can_not_form_a_cycle() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+    api.create_topic(&new_topic("Borrowing", None)).await;
+    let under = |parent: &str| UpdatedTopic {
+        parent: Some(Some(parent.to_string())),
+        ..Default::default()
+    };
+    let under_borrowing = under("borrowing");
+    let under_ownership = under("ownership");
+
+    // Act
+    let (ownership, borrowing) = tokio::join!(
+        api.update_topic(&under_borrowing, "ownership"),
+        api.update_topic(&under_ownership, "borrowing"),
+    );
+
+    // Assert
+    let mut statuses = vec![ownership.status(), borrowing.status()];
+    statuses.sort();
+    assert_eq!(
+        statuses,
+        vec![StatusCode::OK, StatusCode::UNPROCESSABLE_ENTITY]
+    );
+    let tree = api.get_topic_tree().await;
+    assert_eq!(tree.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn create_topic_returns_404_for_an_unknown_parent() {
+    // Arrange
+    let api = TestApi::spawn().await;
+
+    // Act
+    let response = api
+        .create_topic(&new_topic("Borrowing", Some("ownership")))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn create_topic_returns_409_for_an_existing_topic() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    api.create_topic(&new_topic("Ownership", None)).await;
+
+    // Act
+    let response = api.create_topic(&new_topic("ownership", None)).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
@@ -2,7 +2,7 @@
 
 // dependencies
 use crate::helpers::TestApi;
-use app::models::{FlashCard, UpdatedFlashCard};
+use app::models::{CardKind, CardStatus, FlashCard, NewFlashCard, UpdatedFlashCard};
 use app::routes::flashcards::FlashCardResponse;
 use jiff_sqlx::ToSqlx;
 use pavex::http::StatusCode;
@@ -20,22 +20,13 @@ async fn update_flashcard_returns_200_and_updated_flash_card() {
         topic: "test topic".to_string(),
         tags: vec!["tag1".to_string(), "tag2".to_string()],
         difficulty: 1,
+        kind: CardKind::FreeText,
+        status: CardStatus::Published,
         created_at: PavexTimestamp::now().to_sqlx(),
         updated_at: None,
     };
 
-    let id: Uuid = sqlx::query_scalar("INSERT INTO flashcards (id, question, answer, topic, tags, difficulty, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id;")
-        .bind(flash_card.id)
-        .bind(flash_card.question)
-        .bind(flash_card.answer)
-        .bind(flash_card.topic)
-        .bind(flash_card.tags)
-        .bind(flash_card.difficulty)
-        .bind(flash_card.created_at)
-        .bind(flash_card.updated_at)
-        .fetch_one(&api.api_db_pool)
-        .await
-        .unwrap();
+    let id = api.insert_flashcard(&flash_card).await;
 
     let updated_flash_card = UpdatedFlashCard {
         question: Some("updated test question".to_string()),
@@ -46,6 +37,7 @@ async fn update_flashcard_returns_200_and_updated_flash_card() {
             "differenttag2".to_string(),
         ]),
         difficulty: Some(2),
+        kind: None,
     };
 
     // Act
@@ -75,6 +67,7 @@ async fn update_flashcard_returns_400_for_invalid_id() {
             "differenttag2".to_string(),
         ]),
         difficulty: Some(2),
+        kind: None,
     };
     let id = "the-wrong-card-id-value".to_string();
 
@@ -84,3 +77,155 @@ async fn update_flashcard_returns_400_for_invalid_id() {
     // Assert
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn update_flashcard_returns_422_for_invalid_data() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let updated_flash_card = UpdatedFlashCard {
+        question: Some("".to_string()),
+        tags: Some(vec!["rust".to_string(), "rust".to_string()]),
+        ..Default::default()
+    };
+    let id = Uuid::new_v4().to_string();
+
+    // Act
+    let response = api.update_flashcard(&updated_flash_card, id).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn update_flashcard_returns_404_for_non_existent_id() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let updated_flash_card = UpdatedFlashCard {
+        answer: Some("updated test answer".to_string()),
+        ..Default::default()
+    };
+    let non_existent_id = Uuid::new_v4().to_string();
+
+    // Act
+    let response = api
+        .update_flashcard(&updated_flash_card, non_existent_id)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn update_flashcard_returns_409_for_duplicate_question() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let first = NewFlashCard {
+        question: "What is Rust?".to_string(),
+        answer: "A systems programming language.".to_string(),
+        topic: "intro".to_string(),
+        tags: vec!["basics".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+    let second = NewFlashCard {
+        question: "What is Cargo?".to_string(),
+        ..first.clone()
+    };
+    api.create_flashcard(&first).await;
+    let created = api
+        .create_flashcard(&second)
+        .await
+        .json::<FlashCardResponse>()
+        .await
+        .unwrap();
+
+    let updated_flash_card = UpdatedFlashCard {
+        question: Some(first.question.clone()),
+        ..Default::default()
+    };
+
+    // Act
+    let response = api
+        .update_flashcard(&updated_flash_card, created.content.id.to_string())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn update_flashcard_returns_422_for_a_check_constraint_violation() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let new_flash_card = NewFlashCard {
+        question: "What is Rust?".to_string(),
+        answer: "A systems programming language.".to_string(),
+        topic: "intro".to_string(),
+        tags: vec!["basics".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+    let created = api
+        .create_flashcard(&new_flash_card)
+        .await
+        .json::<FlashCardResponse>()
+        .await
+        .unwrap();
+
+    sqlx::query(
+        "ALTER TABLE flashcards ADD CONSTRAINT answer_not_retired CHECK (answer <> 'retired');",
+    )
+    .execute(&api.api_db_pool)
+    .await
+    .unwrap();
+
+    let updated_flash_card = UpdatedFlashCard {
+        answer: Some("retired".to_string()),
+        ..Default::default()
+    };
+
+    // Act
+    let response = api
+        .update_flashcard(&updated_flash_card, created.content.id.to_string())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn update_flashcard_keeps_fields_missing_from_the_update() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let new_flash_card = NewFlashCard {
+        question: "What is Rust?".to_string(),
+        answer: "A systems programming language.".to_string(),
+        topic: "intro".to_string(),
+        tags: vec!["basics".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+    let created = api
+        .create_flashcard(&new_flash_card)
+        .await
+        .json::<FlashCardResponse>()
+        .await
+        .unwrap();
+
+    let updated_flash_card = UpdatedFlashCard {
+        answer: Some("A fast and memory-safe language.".to_string()),
+        ..Default::default()
+    };
+
+    // Act
+    let response = api
+        .update_flashcard(&updated_flash_card, created.content.id.to_string())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.json::<FlashCardResponse>().await.unwrap();
+    assert_eq!(body.content.question, "What is Rust?");
+    assert_eq!(body.content.answer, "A fast and memory-safe language.");
+}
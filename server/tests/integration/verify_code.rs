@@ -0,0 +1,147 @@
+// server/tests/integration/verify_code.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{CardKind, CodeStatus, NewFlashCard};
+use app::routes::code_checks::{CodeCheckReportResponse, CodeVerificationResponse};
+use app::routes::flashcards::FlashCardResponse;
+use pavex::http::StatusCode;
+
+// create a flash card whose answer holds the given Markdown, and return its id
+async fn create_card(api: &TestApi, question: &str, answer: &str) -> String {
+    let card = NewFlashCard {
+        question: question.to_string(),
+        answer: answer.to_string(),
+        topic: "syntax".to_string(),
+        tags: vec!["code".to_string()],
+        difficulty: 1,
+        kind: CardKind::FreeText,
+    };
+    let created: FlashCardResponse = api.create_flashcard(&card).await.json().await.unwrap();
+    created.content.id.to_string()
+}
+
+#[tokio::test]
+async fn verify_code_passes_a_card_whose_snippets_compile() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "How do you declare a mutable binding?",
+        "With `mut`:\n\n```rust\nlet mut x = 1;\nx += 1;\n```",
+    )
+    .await;
+
+    // Act
+    let response = api.verify_code(id.clone()).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: CodeVerificationResponse = response.json().await.unwrap();
+    assert_eq!(body.content.status, CodeStatus::Passed);
+    assert_eq!(body.content.snippet_count, 1);
+
+    let stored: CodeVerificationResponse =
+        api.get_code_verification(id).await.json().await.unwrap();
+    assert_eq!(stored.content, body.content);
+}
+
+#[tokio::test]
+async fn verify_code_fails_a_card_whose_snippets_do_not_compile() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "How do you add two numbers?",
+        "```rust\nlet x: u32 = \"one\" + 1;\n```",
+    )
+    .await;
+
+    // Act
+    let response = api.verify_code(id).await;
+
+    // Assert
+    let body: CodeVerificationResponse = response.json().await.unwrap();
+    assert_eq!(body.content.status, CodeStatus::Failed);
+    assert!(body.content.output.contains("error"));
+    assert!(!body.content.output.contains("/tmp"));
+}
+
+#[tokio::test]
+async fn verify_code_refuses_snippets_reading_host_files() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "Who can log in?",
+        "```rust\nlet users: u32 = include_str!(\"/etc/passwd\");\n```",
+    )
+    .await;
+
+    // Act
+    let response = api.verify_code(id).await;
+
+    // Assert
+    let body: CodeVerificationResponse = response.json().await.unwrap();
+    assert_eq!(body.content.status, CodeStatus::Failed);
+    assert!(
+        body.content
+            .output
+            .contains("`include_str!` is not allowed")
+    );
+    assert!(!body.content.output.contains("root:"));
+}
+
+#[tokio::test]
+async fn verify_code_respects_rustdoc_annotations() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(
+        &api,
+        "Can a value be used after it was moved?",
+        "No:\n\n```rust,compile_fail\nlet s = String::new();\nlet t = s;\nprintln!(\"{}\", s);\n```\n\n```ignore\nthis is not rust\n```",
+    )
+    .await;
+
+    // Act
+    let response = api.verify_code(id).await;
+
+    // Assert
+    let body: CodeVerificationResponse = response.json().await.unwrap();
+    assert_eq!(body.content.status, CodeStatus::Passed);
+    assert_eq!(body.content.snippet_count, 1);
+}
+
+#[tokio::test]
+async fn verify_all_code_reports_the_failing_cards() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    create_card(&api, "What is Rust?", "A language.").await;
+    create_card(&api, "How do you print?", "```rust\nprintln!(\"hi\");\n```").await;
+    let broken = create_card(&api, "How do you loop?", "```rust\nloop {\n```").await;
+
+    // Act
+    let response = api.verify_all_code().await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: CodeCheckReportResponse = response.json().await.unwrap();
+    assert_eq!(body.content.checked, 3);
+    assert_eq!(body.content.passed, 1);
+    assert_eq!(body.content.no_code, 1);
+    assert_eq!(body.content.failed, 1);
+    assert_eq!(body.content.failures[0].flashcard_id.to_string(), broken);
+}
+
+#[tokio::test]
+async fn get_code_verification_returns_404_for_an_unchecked_card() {
+    // Arrange
+    let api = TestApi::spawn().await;
+    let id = create_card(&api, "What is Rust?", "A language.").await;
+
+    // Act
+    let response = api.get_code_verification(id).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -11,12 +11,22 @@ year.textContent = currentYear;
 // variable declarations
 const q_text = document.getElementById("question_text");
 const a_text = document.getElementById("answer_text");
+const guess_input = document.getElementById("guess_input");
+const verdict_text = document.getElementById("verdict_text");
 let answer;
-const fetch_url = "https://rusty-flash-knowledge.net/v1/flashcards/random";
+let card_id;
+// the public API, at the host and under the prefix the page was rendered with
+const api_base = document.getElementById("flashcards")?.dataset.apiBase ?? "";
+const fetch_url = `${api_base}/flashcards/random`;
+const verdicts = {
+  exact: "Correct!",
+  close: "Almost, check the spelling.",
+  wrong: "Not quite.",
+};
 
 // eventlistener for the question button
 const q_button = document.getElementById("question_button");
-q_button.addEventListener("click",  async () => {
+q_button?.addEventListener("click",  async () => {
   try {
     const response = await fetch(fetch_url);
     if (!response.ok) {
@@ -24,8 +34,36 @@ q_button.addEventListener("click",  async () => {
     }
 
     const json = await response.json();
-    q_text.innerText = json.content.question;
-    answer = json.content.answer;
+    // the HTML is rendered from Markdown and sanitized by the API
+    q_text.innerHTML = json.content.question_html;
+    answer = json.content.answer_html;
+    card_id = json.content.id;
+    guess_input.value = "";
+    verdict_text.innerText = "";
+  } catch (error) {
+    console.error(error.message);
+  }
+});
+
+// eventlistener for the check button, which grades the typed answer
+const check_button = document.getElementById("check_button");
+check_button?.addEventListener("click", async () => {
+  if (!card_id) {
+    return;
+  }
+  try {
+    const response = await fetch(`${api_base}/flashcards/${card_id}/check`, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify({ answer: guess_input.value }),
+    });
+    if (!response.ok) {
+      throw new Error(`Response status: ${response.status}`);
+    }
+
+    const json = await response.json();
+    verdict_text.innerText = verdicts[json.content.verdict];
+    a_text.innerHTML = answer;
   } catch (error) {
     console.error(error.message);
   }
@@ -33,13 +71,15 @@ q_button.addEventListener("click",  async () => {
 
 // eventlistener for the answer button
 const a_button = document.getElementById("answer_button");
-a_button.addEventListener("click", () => {
-  a_text.innerText = answer;
+a_button?.addEventListener("click", () => {
+  a_text.innerHTML = answer;
 });
 
 // eventlistener for the reset button
 const reset_button = document.getElementById("reset_button");
-reset_button.addEventListener("click", () => {
+reset_button?.addEventListener("click", () => {
   q_text.innerText = "";
   a_text.innerText = "";
+  guess_input.value = "";
+  verdict_text.innerText = "";
 });
\ No newline at end of file